@@ -150,6 +150,7 @@ fn main() {
         if let Event::Render(args) = event {
             gl.draw([0, 0, args.width as i32, args.height as i32], |_, gl| {
                 draw_ui(gl, &mut uic, &mut demo);
+                uic.draw_layers(gl);
             });
         }
     }
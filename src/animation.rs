@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use ui_context::UIID;
+
+/// The shape of an animation's progress over its duration.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    /// Start slowly and accelerate.
+    EaseIn,
+    /// Start quickly and decelerate, which suits responses to input.
+    EaseOut,
+    /// Accelerate then decelerate.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Map the linear progress `t` (0.0 to 1.0) to the eased progress.
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = if t < 0.0 { 0.0 } else if t > 1.0 { 1.0 } else { t };
+        match *self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => { let u = 1.0 - t; 1.0 - u * u * u },
+            Easing::EaseInOut => if t < 0.5 { 4.0 * t * t * t }
+                                 else { let u = 2.0 - 2.0 * t; 1.0 - u * u * u / 2.0 },
+        }
+    }
+}
+
+/// A value moving from one value to another.
+#[derive(Copy, Clone, Debug)]
+struct Tween {
+    from: f64,
+    to: f64,
+    start: f64,
+    duration: f64,
+    easing: Easing,
+}
+
+impl Tween {
+    /// The value at the given time.
+    fn value(&self, now: f64) -> f64 {
+        if self.duration <= 0.0 { return self.to }
+        let t = self.easing.apply((now - self.start) / self.duration);
+        self.from + (self.to - self.from) * t
+    }
+
+    /// Whether the tween has yet to reach its target.
+    fn is_active(&self, now: f64) -> bool {
+        now - self.start < self.duration
+    }
+}
+
+/// The animated values of the UiContext, keyed by UIID.
+#[derive(Clone, Debug)]
+pub struct Animations {
+    tweens: HashMap<UIID, Tween>,
+}
+
+impl Animations {
+
+    /// Construct an empty set of Animations.
+    pub fn new() -> Animations {
+        Animations { tweens: HashMap::new() }
+    }
+
+    /// Return the current value of the animation with the given UIID. The
+    /// first call starts animating from `from` to `to`. If a later call gives
+    /// a different `to`, the animation restarts towards it from its current
+    /// value, so that interrupted transitions don't jump.
+    pub fn animate(&mut self, ui_id: UIID, from: f64, to: f64, duration: f64, easing: Easing, now: f64) -> f64 {
+        let tween = match self.tweens.get(&ui_id) {
+            Some(tween) if tween.to == to => *tween,
+            Some(tween) => Tween { from: tween.value(now), to: to, start: now, duration: duration, easing: easing },
+            None => Tween { from: from, to: to, start: now, duration: duration, easing: easing },
+        };
+        self.tweens.insert(ui_id, tween);
+        tween.value(now)
+    }
+
+    /// Whether any animation has yet to reach its target.
+    pub fn is_active(&self, now: f64) -> bool {
+        self.tweens.values().any(|tween| tween.is_active(now))
+    }
+
+    /// Forget the animation with the given UIID.
+    pub fn forget(&mut self, ui_id: UIID) {
+        self.tweens.remove(&ui_id);
+    }
+
+}
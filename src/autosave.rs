@@ -0,0 +1,121 @@
+use rustc_serialize::{
+    json,
+    Encodable,
+    Decodable,
+};
+use std::error::Error;
+use std::fs;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::str;
+
+/// The format version of saved sessions. Sessions saved with a different
+/// version are considered stale and are not recovered.
+pub const VERSION: u32 = 1;
+
+/// The state written by each autosave.
+#[derive(Debug, Clone, RustcEncodable, RustcDecodable)]
+struct Session<T> {
+    version: u32,
+    ui_scale: f64,
+    state: T,
+}
+
+/// Periodically writes the application's state (along with the UI's own
+/// state) to a file so that a session may be recovered after a crash.
+#[derive(Clone, Debug)]
+pub struct Autosave {
+    /// The path of the file that sessions are saved to.
+    pub path: String,
+    /// The minimum time in seconds between saves.
+    pub interval: f64,
+    maybe_last_save: Option<f64>,
+}
+
+impl Autosave {
+
+    /// Construct an Autosave writing to the given path at the given interval.
+    pub fn new(path: &str, interval: f64) -> Autosave {
+        Autosave {
+            path: path.to_string(),
+            interval: interval,
+            maybe_last_save: None,
+        }
+    }
+
+    /// Whether or not a save is due at the given time.
+    pub fn is_due(&self, now: f64) -> bool {
+        match self.maybe_last_save {
+            Some(last_save) => now - last_save >= self.interval,
+            None => true,
+        }
+    }
+
+    /// Save the session. The session is first written to a temporary file
+    /// which then replaces the previous save, so that a crash mid-write
+    /// never leaves a partial file behind.
+    pub fn save<T: Encodable>(&mut self, ui_scale: f64, state: &T, now: f64) -> Result<(), String> {
+        self.maybe_last_save = Some(now);
+        let session = Session { version: VERSION, ui_scale: ui_scale, state: state };
+        let json_string = match json::encode(&session) {
+            Ok(x) => x,
+            Err(e) => return Err(e.description().to_string()),
+        };
+        let tmp_path = format!("{}.tmp", self.path);
+        let mut file = match File::create(&Path::new(&tmp_path)) {
+            Ok(file) => file,
+            Err(e) => return Err(format!("Failed to create a File at the given path: {}", Error::description(&e))),
+        };
+        if let Err(e) = ::std::io::Write::write_all(&mut file, json_string.as_bytes()) {
+            return Err(format!("Session failed to save correctly: {}", Error::description(&e)));
+        }
+        match fs::rename(&Path::new(&tmp_path), &Path::new(&self.path)) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(format!("Failed to replace the previous session: {}", Error::description(&e))),
+        }
+    }
+
+    /// Remove the saved session. This should be called when the application
+    /// exits cleanly so that the next startup doesn't offer to recover it.
+    pub fn clear(&mut self) -> Result<(), String> {
+        self.maybe_last_save = None;
+        match fs::remove_file(&Path::new(&self.path)) {
+            Ok(()) => Ok(()),
+            Err(ref e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to remove the saved session: {}", Error::description(&e))),
+        }
+    }
+
+}
+
+/// Load the session saved at the given path, returning the UI scale and
+/// the application's state. Returns None if there is no session to recover
+/// (the last session exited cleanly) or if it was saved by an incompatible
+/// version.
+pub fn recover<T: Decodable>(path: &str) -> Result<Option<(f64, T)>, String> {
+    let mut file = match File::open(&Path::new(path)) {
+        Ok(file) => file,
+        Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to open file for Session: {}", Error::description(&e))),
+    };
+    let mut contents = Vec::new();
+    if let Err(e) = ::std::io::Read::read_to_end(&mut file, &mut contents) {
+        return Err(format!("Failed to load Session correctly: {}", Error::description(&e)));
+    }
+    let contents = match str::from_utf8(&contents[..]) {
+        Ok(contents) => contents,
+        Err(e) => return Err(format!("Session is not valid UTF-8: {}", Error::description(&e))),
+    };
+    let json_object = match json::Json::from_str(contents) {
+        Ok(json_object) => json_object,
+        Err(e) => return Err(format!("Failed to construct json_object from str: {}", Error::description(&e))),
+    };
+    let is_current = json_object.find("version").and_then(|v| v.as_u64()) == Some(VERSION as u64);
+    if !is_current { return Ok(None) }
+    let mut decoder = json::Decoder::new(json_object);
+    match Decodable::decode(&mut decoder) {
+        Ok(Session { ui_scale, state, .. }) => Ok(Some((ui_scale, state))),
+        Err(e) => Err(format!("Failed to construct Session from json decoder: {}", Error::description(&e))),
+    }
+}
@@ -0,0 +1,71 @@
+use color::Color;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label::FontSize;
+use point::Point;
+use primitive;
+use ui_context::UiContext;
+use utils::val_to_string;
+
+/// A grid line at a position from 0.0 to 1.0 along an axis (measured from
+/// the left or the bottom of the pad) with its tick label.
+pub type Tick = (f64, String);
+
+/// The number of grid divisions along the X and Y axes of a pad.
+pub type Divisions = (usize, usize);
+
+/// The ticks dividing a linear axis into `divisions` equal parts. Ticks at
+/// the edges of the pad are omitted as they would be drawn over the frame.
+pub fn linear_ticks(min: f64, max: f64, divisions: usize) -> Vec<(f64, f64)> {
+    if divisions < 2 { return Vec::new() }
+    (1..divisions).map(|i| {
+        let perc = i as f64 / divisions as f64;
+        (perc, min + (max - min) * perc)
+    }).collect()
+}
+
+/// A concise label for the value of a tick on an axis of the given range
+/// that spans the given number of pixels.
+pub fn tick_label(value: f64, min: f64, max: f64, pixels: f64) -> String {
+    val_to_string(value, max, max - min, pixels as usize)
+}
+
+/// Draw faint grid lines across the pad at each tick, along with their
+/// labels if `labels` is set. X labels sit along the bottom of the pad and
+/// Y labels along its left edge.
+pub fn draw<B, C>(
+    uic: &mut UiContext<C>,
+    graphics: &mut B,
+    pad_pos: Point,
+    pad_dim: Dimensions,
+    x_ticks: &[Tick],
+    y_ticks: &[Tick],
+    labels: bool,
+    color: Color,
+    font_size: FontSize
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    let line_color = color.with_alpha(0.2);
+    let text_color = color.with_alpha(0.6);
+    for &(perc, ref label) in x_ticks.iter() {
+        let x = pad_pos[0] + pad_dim[0] * perc;
+        primitive::line(uic.win_w, uic.win_h, graphics, [x, pad_pos[1]],
+                        [x, pad_pos[1] + pad_dim[1]], 1.0, line_color);
+        if labels {
+            let label_pos = [x + 2.0, pad_pos[1] + pad_dim[1] - font_size as f64];
+            uic.draw_value_text(graphics, label_pos, font_size, text_color, label);
+        }
+    }
+    for &(perc, ref label) in y_ticks.iter() {
+        let y = pad_pos[1] + pad_dim[1] * (1.0 - perc);
+        primitive::line(uic.win_w, uic.win_h, graphics, [pad_pos[0], y],
+                        [pad_pos[0] + pad_dim[0], y], 1.0, line_color);
+        if labels {
+            uic.draw_value_text(graphics, [pad_pos[0] + 2.0, y], font_size, text_color, label);
+        }
+    }
+}
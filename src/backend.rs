@@ -0,0 +1,107 @@
+use clip::Scissor;
+use graphics::{ DrawState, Graphics, ImageSize };
+use piston::event::{
+    GenericEvent,
+    MouseCursorEvent,
+    MouseScrollEvent,
+    PressEvent,
+    ReleaseEvent,
+    RenderEvent,
+    TextEvent,
+};
+
+pub use piston::input::{ Button, MouseButton };
+pub use piston::input::keyboard::Key;
+
+/// The input events handled by the UiContext. Back ends translate their
+/// window's events into these and pass them to `UiContext::handle_input`,
+/// so the UI isn't tied to piston's event loop. Keys and mouse buttons are
+/// described using piston's input types, which back ends map their own
+/// key codes to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Input {
+    /// The window was resized to the given width and height in pixels.
+    Resize(u32, u32),
+    /// The mouse moved to the given position in pixels.
+    Move(f64, f64),
+    /// The mouse wheel or trackpad scrolled horizontally and vertically.
+    Scroll(f64, f64),
+    /// A mouse button or key was pressed.
+    Press(Button),
+    /// A mouse button or key was released.
+    Release(Button),
+    /// Text was entered.
+    Text(String),
+}
+
+/// Translate a piston event into the Input it describes (if any).
+pub fn from_piston<E: GenericEvent>(event: &E) -> Option<Input> {
+    let mut maybe_input = None;
+    event.render(|args| maybe_input = Some(Input::Resize(args.width, args.height)));
+    event.mouse_cursor(|x, y| maybe_input = Some(Input::Move(x, y)));
+    event.mouse_scroll(|x, y| maybe_input = Some(Input::Scroll(x, y)));
+    event.press(|button| maybe_input = Some(Input::Press(button)));
+    event.release(|button| maybe_input = Some(Input::Release(button)));
+    event.text(|text| maybe_input = Some(Input::Text(text.to_string())));
+    maybe_input
+}
+
+/// The interface through which the UI is drawn. Back ends that don't use
+/// piston's graphics implement this and draw via `UiContext::render_to`.
+/// Vertices are given as x, y pairs in normalized device coordinates.
+pub trait Renderer {
+    /// The texture type in which glyphs are cached, which must match that
+    /// of the UiContext's CharacterCache.
+    type Texture: ImageSize;
+
+    /// Clear the window to the color.
+    fn clear(&mut self, color: [f32; 4]);
+
+    /// Draw triangles of the color, clipped to the scissor rectangle (if any).
+    fn triangles(&mut self, maybe_scissor: Option<Scissor>, color: [f32; 4], vertices: &[f32]);
+
+    /// Draw triangles textured with the texture and tinted with the color,
+    /// i.e. glyphs. `uvs` holds a texture coordinate pair for each vertex.
+    fn textured_triangles(&mut self,
+                          maybe_scissor: Option<Scissor>,
+                          color: [f32; 4],
+                          texture: &Self::Texture,
+                          vertices: &[f32],
+                          uvs: &[f32]);
+}
+
+/// Adapts a Renderer to piston's Graphics trait, through which widgets draw.
+pub struct RendererGraphics<'a, R: 'a>(pub &'a mut R);
+
+/// The scissor rectangle of the draw state (if any).
+fn scissor_of(draw_state: &DrawState) -> Option<Scissor> {
+    draw_state.scissor.map(|rect| [rect.x, rect.y, rect.w, rect.h])
+}
+
+impl<'a, R: Renderer> Graphics for RendererGraphics<'a, R> {
+    type Texture = R::Texture;
+
+    fn clear(&mut self, color: [f32; 4]) {
+        self.0.clear(color);
+    }
+
+    fn tri_list<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], mut f: F)
+        where
+            F: FnMut(&mut FnMut(&[f32]))
+    {
+        let renderer = &mut *self.0;
+        let maybe_scissor = scissor_of(draw_state);
+        f(&mut |vertices: &[f32]| renderer.triangles(maybe_scissor, *color, vertices));
+    }
+
+    fn tri_list_uv<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], texture: &R::Texture, mut f: F)
+        where
+            F: FnMut(&mut FnMut(&[f32], &[f32]))
+    {
+        let renderer = &mut *self.0;
+        let maybe_scissor = scissor_of(draw_state);
+        f(&mut |vertices: &[f32], uvs: &[f32]| {
+            renderer.textured_triangles(maybe_scissor, *color, texture, vertices, uvs)
+        });
+    }
+}
@@ -34,8 +34,8 @@ impl Drawable for Background {
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
-        let Color(col) = self.maybe_color
-            .unwrap_or(uic.theme.background_color);
+        let color = self.maybe_color.unwrap_or(uic.theme.background_color);
+        let Color(col) = uic.signals.modulate_background(color);
         graphics::clear(col, graphics);
     }
 }
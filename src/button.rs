@@ -1,9 +1,12 @@
 
+use animation::Easing;
 use color::Color;
 use dimensions::Dimensions;
+use font::FontId;
 use mouse::Mouse;
 use point::Point;
 use rectangle;
+use rectangle::Gradient;
 use ui_context::{
     Id,
     UIID,
@@ -13,6 +16,7 @@ use widget::{ DefaultWidgetState, Widget };
 use graphics::Graphics;
 use graphics::character::CharacterCache;
 use Callback;
+use Font;
 use FrameColor;
 use FrameWidth;
 use LabelText;
@@ -20,6 +24,10 @@ use LabelColor;
 use LabelFontSize;
 use Position;
 use Size;
+use Visible;
+
+/// The time in seconds over which the highlight fades in and out.
+const HOVER_DURATION: f64 = 0.1;
 
 /// Represents the state of the Button widget.
 #[derive(PartialEq, Clone, Copy)]
@@ -61,6 +69,7 @@ fn get_new_state(is_over: bool,
 pub struct Button<'a, F> {
     ui_id: UIID,
     pos: Point,
+    visible: bool,
     dim: Dimensions,
     maybe_color: Option<Color>,
     maybe_frame: Option<f64>,
@@ -68,6 +77,8 @@ pub struct Button<'a, F> {
     maybe_label: Option<&'a str>,
     maybe_label_color: Option<Color>,
     maybe_label_font_size: Option<u32>,
+    maybe_font: Option<FontId>,
+    maybe_gradient: Option<Gradient>,
     maybe_callback: Option<F>,
 }
 
@@ -78,6 +89,7 @@ impl<'a, F> Button<'a, F> {
         Button {
             ui_id: ui_id,
             pos: [0.0, 0.0],
+            visible: true,
             dim: [64.0, 64.0],
             maybe_callback: None,
             maybe_color: None,
@@ -86,21 +98,31 @@ impl<'a, F> Button<'a, F> {
             maybe_label: None,
             maybe_label_color: None,
             maybe_label_font_size: None,
+            maybe_font: None,
+            maybe_gradient: None,
         }
     }
 
+    /// Fill the button with the given gradient rather than a flat color.
+    pub fn gradient(self, gradient: Gradient) -> Button<'a, F> {
+        Button { maybe_gradient: Some(gradient), ..self }
+    }
+
 }
 
 quack! {
     button: Button['a, F]
     get:
         fn () -> Size [] { Size(button.dim) }
+        fn () -> Position [] { Position(button.pos) }
+        fn () -> Visible [] { Visible(button.visible) }
         fn () -> DefaultWidgetState [] {
             DefaultWidgetState(Widget::Button(State::Normal))
         }
         fn () -> Id [] { Id(button.ui_id) }
     set:
         fn (val: Color) [] { button.maybe_color = Some(val) }
+        fn (val: Font) [] { button.maybe_font = Some(val.0) }
         fn (val: Callback<F>) [where F: FnMut() + 'a] {
             button.maybe_callback = Some(val.0)
         }
@@ -110,53 +132,91 @@ quack! {
         fn (val: LabelColor) [] { button.maybe_label_color = Some(val.0) }
         fn (val: LabelFontSize) [] { button.maybe_label_font_size = Some(val.0) }
         fn (val: Position) [] { button.pos = val.0 }
+        fn (val: Visible) [] { button.visible = val.0 }
         fn (val: Size) [] { button.dim = val.0 }
     action:
 }
 
-impl<'a, F> ::draw::Drawable for Button<'a, F>
+impl<'a, F> Button<'a, F>
     where
         F: FnMut() + 'a
 {
 
-    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+    /// Update the button's state, calling the callback (if any) and drawing
+    /// it. Return whether or not the button was clicked.
+    fn update<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B) -> bool
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
-
+        if !self.visible { uic.keep_state(self.ui_id); return false }
+        let maybe_prev_font = self.maybe_font.map(|font| uic.set_font(font));
         let state = *get_state(uic, self.ui_id);
         let mouse = uic.get_mouse_state();
-        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let is_over = uic.is_over(self.ui_id, self.pos, self.dim, mouse.pos);
         let new_state = get_new_state(is_over, state, mouse);
 
         // Callback.
-        match (is_over, state, new_state) {
-            (true, State::Clicked, State::Highlighted) => match self.maybe_callback {
+        let clicked = match (is_over, state, new_state) {
+            (true, State::Clicked, State::Highlighted) => true,
+            _ => false,
+        };
+        if clicked {
+            match self.maybe_callback {
                 Some(ref mut callback) => (*callback)(), None => (),
-            }, _ => (),
+            }
         }
 
         // Draw.
         let rect_state = new_state.as_rectangle_state();
-        let color = self.maybe_color.unwrap_or(uic.theme.shape_color);
+        // The highlight fades in and out rather than switching, and is applied
+        // to both colors of a gradient along with any flash.
+        let hover = uic.animate(self.ui_id, 0.0, match new_state { State::Normal => 0.0, _ => 1.0 },
+                                HOVER_DURATION, Easing::EaseOut) as f32;
+        let solid_state = match new_state {
+            State::Clicked => rect_state,
+            _ => rectangle::State::Normal,
+        };
+        let (color, maybe_gradient) = {
+            let uic = &*uic;
+            let shade = |color: Color| {
+                let color = uic.flash_color(self.ui_id, color);
+                match new_state {
+                    State::Clicked => color,
+                    _ => uic.mix_colors(color, color.highlighted(), hover),
+                }
+            };
+            (shade(self.maybe_color.unwrap_or(uic.theme.shape_color)),
+             self.maybe_gradient.map(|gradient| gradient.map(|color| shade(color))))
+        };
         let frame_w = self.maybe_frame.unwrap_or(uic.theme.frame_width);
         let maybe_frame = match frame_w > 0.0 {
             true => Some((frame_w, self.maybe_frame_color.unwrap_or(uic.theme.frame_color))),
             false => None,
         };
-        match self.maybe_label {
-            None => {
+        match (maybe_gradient, self.maybe_label) {
+            (Some(gradient), maybe_label) => {
+                rectangle::draw_gradient(
+                    uic.win_w, uic.win_h, graphics, solid_state, self.pos,
+                    self.dim, maybe_frame, gradient
+                );
+                if let Some(text) = maybe_label {
+                    let text_color = self.maybe_label_color.unwrap_or(uic.theme.label_color);
+                    let size = self.maybe_label_font_size.unwrap_or(uic.theme.font_size_medium);
+                    rectangle::draw_centered_label(graphics, uic, self.pos, self.dim, text, size, text_color);
+                }
+            },
+            (None, None) => {
                 rectangle::draw(
-                    uic.win_w, uic.win_h, graphics, rect_state, self.pos,
+                    uic.win_w, uic.win_h, graphics, solid_state, self.pos,
                     self.dim, maybe_frame, color
                 )
             },
-            Some(text) => {
+            (None, Some(text)) => {
                 let text_color = self.maybe_label_color.unwrap_or(uic.theme.label_color);
                 let size = self.maybe_label_font_size.unwrap_or(uic.theme.font_size_medium);
                 rectangle::draw_with_centered_label(
-                    uic.win_w, uic.win_h, graphics, uic, rect_state,
+                    uic.win_w, uic.win_h, graphics, uic, solid_state,
                     self.pos, self.dim, maybe_frame, color,
                     text, size, text_color
                 )
@@ -164,6 +224,38 @@ impl<'a, F> ::draw::Drawable for Button<'a, F>
         }
 
         set_state(uic, self.ui_id, Widget::Button(new_state), self.pos, self.dim);
+        if let Some(font) = maybe_prev_font { uic.set_font(font); }
+
+        clicked
+    }
+
+}
+
+impl<'a> Button<'a, fn()> {
+
+    /// Draw the button and return whether or not it was clicked. This is an
+    /// alternative to providing a callback.
+    pub fn clicked<B, C>(mut self, uic: &mut UiContext<C>, graphics: &mut B) -> bool
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics)
+    }
 
+}
+
+impl<'a, F> ::draw::Drawable for Button<'a, F>
+    where
+        F: FnMut() + 'a
+{
+
+    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics);
     }
+
 }
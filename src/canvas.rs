@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use dimensions::Dimensions;
+use point::Point;
+
+/// Canvas Identifier. Widgets may be attached to a canvas by its id
+/// rather than by absolute position.
+pub type CanvasId = u64;
+
+/// The edge of a parent (or the window) to which a canvas is docked
+/// along with the canvas' length away from that edge.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Dock {
+    Top(f64),
+    Bottom(f64),
+    Left(f64),
+    Right(f64),
+    /// Fill whatever space remains in the parent.
+    Fill,
+}
+
+/// The direction in which a canvas is split into children.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Direction {
+    /// Children are laid out side by side from left to right.
+    Horizontal,
+    /// Children are laid out on top of each other from top to bottom.
+    Vertical,
+}
+
+/// The area occupied by a canvas.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rect {
+    pub pos: Point,
+    pub dim: Dimensions,
+}
+
+impl Rect {
+    /// Return the absolute position of a point given relative to the Rect.
+    pub fn point(&self, offset: Point) -> Point {
+        [self.pos[0] + offset[0], self.pos[1] + offset[1]]
+    }
+}
+
+/// Stores the Rect of every canvas declared since the last render
+/// along with the space that remains for docking within each.
+#[derive(Clone, Debug)]
+pub struct Canvases {
+    window: Rect,
+    rects: HashMap<CanvasId, Rect>,
+    remaining: HashMap<CanvasId, Rect>,
+}
+
+impl Canvases {
+
+    /// Construct an empty set of canvases.
+    pub fn new() -> Canvases {
+        Canvases {
+            window: Rect { pos: [0.0, 0.0], dim: [0.0, 0.0] },
+            rects: HashMap::new(),
+            remaining: HashMap::new(),
+        }
+    }
+
+    /// Reset the space available for docking to the given window size.
+    /// This should be called once per frame before canvases are declared.
+    pub fn reset(&mut self, win_w: f64, win_h: f64) {
+        self.window = Rect { pos: [0.0, 0.0], dim: [win_w, win_h] };
+        self.remaining.clear();
+    }
+
+    /// Return the Rect of the canvas with the given id.
+    pub fn get(&self, id: CanvasId) -> Option<Rect> {
+        self.rects.get(&id).map(|rect| *rect)
+    }
+
+    /// Dock a canvas to an edge of the space remaining in its parent (or
+    /// the window if there is no parent). The docked canvas' area is
+    /// removed from the parent's remaining space.
+    pub fn dock(&mut self, id: CanvasId, maybe_parent: Option<CanvasId>, dock: Dock) -> Rect {
+        let available = self.available(maybe_parent);
+        let Rect { pos, dim } = available;
+        let clamp = |len: f64, max: f64| if len < 0.0 { 0.0 } else if len > max { max } else { len };
+        let (rect, remaining) = match dock {
+            Dock::Top(h) => {
+                let h = clamp(h, dim[1]);
+                (Rect { pos: pos, dim: [dim[0], h] },
+                 Rect { pos: [pos[0], pos[1] + h], dim: [dim[0], dim[1] - h] })
+            },
+            Dock::Bottom(h) => {
+                let h = clamp(h, dim[1]);
+                (Rect { pos: [pos[0], pos[1] + dim[1] - h], dim: [dim[0], h] },
+                 Rect { pos: pos, dim: [dim[0], dim[1] - h] })
+            },
+            Dock::Left(w) => {
+                let w = clamp(w, dim[0]);
+                (Rect { pos: pos, dim: [w, dim[1]] },
+                 Rect { pos: [pos[0] + w, pos[1]], dim: [dim[0] - w, dim[1]] })
+            },
+            Dock::Right(w) => {
+                let w = clamp(w, dim[0]);
+                (Rect { pos: [pos[0] + dim[0] - w, pos[1]], dim: [w, dim[1]] },
+                 Rect { pos: pos, dim: [dim[0] - w, dim[1]] })
+            },
+            Dock::Fill => (available, Rect { pos: pos, dim: [0.0, 0.0] }),
+        };
+        self.set_remaining(maybe_parent, remaining);
+        self.insert(id, rect);
+        rect
+    }
+
+    /// Split the space remaining in the parent canvas between the given
+    /// children in proportion to their weights.
+    pub fn split(&mut self, parent: CanvasId, direction: Direction, children: &[(CanvasId, f64)]) {
+        let Rect { pos, dim } = self.available(Some(parent));
+        let total = children.iter().fold(0.0, |total, &(_, weight)| total + weight);
+        if total <= 0.0 { return }
+        let mut offset = 0.0;
+        for &(id, weight) in children.iter() {
+            let rect = match direction {
+                Direction::Horizontal => {
+                    let w = dim[0] * weight / total;
+                    Rect { pos: [pos[0] + offset, pos[1]], dim: [w, dim[1]] }
+                },
+                Direction::Vertical => {
+                    let h = dim[1] * weight / total;
+                    Rect { pos: [pos[0], pos[1] + offset], dim: [dim[0], h] }
+                },
+            };
+            offset += match direction { Direction::Horizontal => rect.dim[0], Direction::Vertical => rect.dim[1] };
+            self.insert(id, rect);
+        }
+        self.set_remaining(Some(parent), Rect { pos: pos, dim: [0.0, 0.0] });
+    }
+
+    /// The space remaining for docking within the given parent.
+    fn available(&self, maybe_parent: Option<CanvasId>) -> Rect {
+        match maybe_parent {
+            Some(parent) => match self.remaining.get(&parent) {
+                Some(rect) => *rect,
+                None => self.get(parent).unwrap_or(Rect { pos: [0.0, 0.0], dim: [0.0, 0.0] }),
+            },
+            None => self.window,
+        }
+    }
+
+    fn set_remaining(&mut self, maybe_parent: Option<CanvasId>, rect: Rect) {
+        match maybe_parent {
+            Some(parent) => { self.remaining.insert(parent, rect); },
+            None => self.window = rect,
+        }
+    }
+
+    fn insert(&mut self, id: CanvasId, rect: Rect) {
+        self.rects.insert(id, rect);
+        self.remaining.remove(&id);
+    }
+
+}
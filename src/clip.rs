@@ -0,0 +1,41 @@
+use std::num::Float;
+use dimensions::Dimensions;
+use graphics::DrawState;
+use point::Point;
+use utils::clamp;
+
+/// A clip rectangle in window pixels as `[x, y, w, h]`, measured from the
+/// bottom left of the window as the backend's scissor test expects.
+pub type Scissor = [u16; 4];
+
+/// Return the intersection of two rectangles. Rectangles that don't
+/// overlap intersect with zero dimensions.
+pub fn intersect(a: (Point, Dimensions), b: (Point, Dimensions)) -> (Point, Dimensions) {
+    let ((a_pos, a_dim), (b_pos, b_dim)) = (a, b);
+    let axis = |i: usize| {
+        let start = a_pos[i].max(b_pos[i]);
+        let end = (a_pos[i] + a_dim[i]).min(b_pos[i] + b_dim[i]);
+        (start, (end - start).max(0.0))
+    };
+    let ((x, w), (y, h)) = (axis(0), axis(1));
+    ([x, y], [w, h])
+}
+
+/// Convert a rectangle in UI coordinates to a Scissor for a window of the
+/// given height in pixels. The rectangle is rounded outwards to whole pixels.
+pub fn scissor(pos: Point, dim: Dimensions, ui_scale: f64, physical_h: f64) -> Scissor {
+    let to_px = |v: f64| clamp(v, 0.0, ::std::u16::MAX as f64) as u16;
+    let left = (pos[0] * ui_scale).floor();
+    let right = ((pos[0] + dim[0]) * ui_scale).ceil();
+    let bottom = (physical_h - (pos[1] + dim[1]) * ui_scale).floor();
+    let top = (physical_h - pos[1] * ui_scale).ceil();
+    [to_px(left), to_px(bottom), to_px(right - left), to_px(top - bottom)]
+}
+
+/// Return the draw state clipped to the scissor rectangle (if any).
+pub fn apply(draw_state: &DrawState, maybe_scissor: Option<Scissor>) -> DrawState {
+    match maybe_scissor {
+        Some(s) => draw_state.clone().scissor(s[0], s[1], s[2], s[3]),
+        None => draw_state.clone(),
+    }
+}
@@ -82,6 +82,91 @@ impl Color {
         c[3]
     }
 
+    /// Construct a color from a hue in degrees, saturation and lightness.
+    pub fn hsl(h: f32, s: f32, l: f32, a: f32) -> Color {
+        let (s, l) = (clampf32(s), clampf32(l));
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        from_hue_chroma(h, c, l - c / 2.0, a)
+    }
+
+    /// Construct a color from a hue in degrees, saturation and value.
+    pub fn hsv(h: f32, s: f32, v: f32, a: f32) -> Color {
+        let (s, v) = (clampf32(s), clampf32(v));
+        let c = v * s;
+        from_hue_chroma(h, c, v - c, a)
+    }
+
+    /// Return the hue in degrees, saturation and lightness of the color.
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (max, min) = (self.max_channel(), self.min_channel());
+        let l = (max + min) / 2.0;
+        let s = if max == min { 0.0 } else { (max - min) / (1.0 - (2.0 * l - 1.0).abs()) };
+        (self.hue(), clampf32(s), l)
+    }
+
+    /// Return the hue in degrees, saturation and value of the color.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (max, min) = (self.max_channel(), self.min_channel());
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        (self.hue(), s, max)
+    }
+
+    /// The hue of the color in degrees.
+    fn hue(&self) -> f32 {
+        let (r, g, b) = (self.r(), self.g(), self.b());
+        let (max, min) = (self.max_channel(), self.min_channel());
+        let d = max - min;
+        if d == 0.0 { return 0.0 }
+        let h = if max == r { ((g - b) / d) % 6.0 }
+                else if max == g { (b - r) / d + 2.0 }
+                else { (r - g) / d + 4.0 };
+        let h = h * 60.0;
+        if h < 0.0 { h + 360.0 } else { h }
+    }
+
+    fn max_channel(&self) -> f32 {
+        self.r().max(self.g()).max(self.b())
+    }
+
+    fn min_channel(&self) -> f32 {
+        self.r().min(self.g()).min(self.b())
+    }
+
+    /// Return the color with its lightness increased by `amount` (0.0 .. 1.0).
+    pub fn lighten(&self, amount: f32) -> Color {
+        let (h, s, l) = self.to_hsl();
+        Color::hsl(h, s, l + amount, self.a())
+    }
+
+    /// Return the color with its lightness decreased by `amount` (0.0 .. 1.0).
+    pub fn darken(&self, amount: f32) -> Color {
+        self.lighten(-amount)
+    }
+
+    /// Return the color with its saturation increased by `amount` (0.0 .. 1.0).
+    pub fn saturate(&self, amount: f32) -> Color {
+        let (h, s, l) = self.to_hsl();
+        Color::hsl(h, s + amount, l, self.a())
+    }
+
+    /// Return the color with its saturation decreased by `amount` (0.0 .. 1.0).
+    pub fn desaturate(&self, amount: f32) -> Color {
+        self.saturate(-amount)
+    }
+
+    /// Return the color with the given alpha.
+    pub fn with_alpha(&self, a: f32) -> Color {
+        Color([self.r(), self.g(), self.b(), clampf32(a)])
+    }
+
+    /// Return a shade of the color that stands apart from it by `amount`
+    /// (0.0 .. 1.0), lightening dark colors and darkening light ones. This
+    /// may be used to derive hover and pressed shades from a theme color.
+    pub fn shade(&self, amount: f32) -> Color {
+        if self.relative_luminance() > 0.18 { self.darken(amount) }
+        else { self.lighten(amount) }
+    }
+
     /// Basic constructor for a Black Color struct.
     pub fn black() -> Color {
         Color([0f32, 0f32, 0f32, 1f32])
@@ -150,6 +235,37 @@ impl Color {
         Color::new(r, g, b, a)
     }
 
+    /// Return the color `amount` (0.0 .. 1.0) of the way between
+    /// this color and the `other` color.
+    pub fn mix(&self, other: Color, amount: f32) -> Color {
+        let amount = clampf32(amount);
+        let Color(a) = *self;
+        let Color(b) = other;
+        Color([a[0] + (b[0] - a[0]) * amount,
+               a[1] + (b[1] - a[1]) * amount,
+               a[2] + (b[2] - a[2]) * amount,
+               a[3] + (b[3] - a[3]) * amount])
+    }
+
+    /// Return the color `amount` (0.0 .. 1.0) of the way between this
+    /// color and the `other` color, interpolating in linear space. This
+    /// avoids the dark, muddy midpoints of interpolating sRGB values.
+    pub fn mix_linear(&self, other: Color, amount: f32) -> Color {
+        self.to_linear().mix(other.to_linear(), amount).to_srgb()
+    }
+
+    /// Convert the color's channels from sRGB to linear space. Alpha is
+    /// left unchanged.
+    pub fn to_linear(&self) -> Color {
+        Color([srgb_to_linear(self.r()), srgb_to_linear(self.g()), srgb_to_linear(self.b()), self.a()])
+    }
+
+    /// Convert the color's channels from linear space to sRGB. Alpha is
+    /// left unchanged.
+    pub fn to_srgb(&self) -> Color {
+        Color([linear_to_srgb(self.r()), linear_to_srgb(self.g()), linear_to_srgb(self.b()), self.a()])
+    }
+
     /// Return a random color.
     pub fn random() -> Color {
         let r = random::<f32>();
@@ -171,7 +287,7 @@ impl Color {
     /// the Color the most. This will be useful for determining
     /// a readable color for text on any given background Color.
     pub fn plain_contrast(&self) -> Color {
-        if self.luminance() > 0.5f32 { Color::black() }
+        if self.contrast_ratio(Color::black()) > self.contrast_ratio(Color::white()) { Color::black() }
         else { Color::white() }
     }
 
@@ -180,6 +296,22 @@ impl Color {
         (self.r() + self.g() + self.b()) / 3f32
     }
 
+    /// Return the perceived brightness of the color (0.0 .. 1.0), weighting
+    /// each linear channel by the eye's sensitivity to it as in WCAG 2.0.
+    pub fn relative_luminance(&self) -> f32 {
+        let Color(c) = self.to_linear();
+        0.2126 * c[0] + 0.7152 * c[1] + 0.0722 * c[2]
+    }
+
+    /// Return the WCAG 2.0 contrast ratio between this color and the other,
+    /// from 1.0 (none) to 21.0 (black on white). Text should have a ratio
+    /// of at least 4.5 against its background to be readable.
+    pub fn contrast_ratio(&self, other: Color) -> f32 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+        (lighter + 0.05) / (darker + 0.05)
+    }
+
     /// Return an array of the channels in this color
     /// clamped to [0..255]
     pub fn to_32_bit(&self) -> [u8; 4] {
@@ -202,6 +334,34 @@ impl Color {
     }
 }
 
+/// Construct a color from a hue in degrees, a chroma and the amount `m`
+/// added to each channel to match the lightness or value.
+fn from_hue_chroma(h: f32, c: f32, m: f32, a: f32) -> Color {
+    let h = ((h % 360.0) + 360.0) % 360.0 / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    Color([clampf32(r + m), clampf32(g + m), clampf32(b + m), clampf32(a)])
+}
+
+fn srgb_to_linear(chan: f32) -> f32 {
+    let chan = clampf32(chan);
+    if chan <= 0.04045 { chan / 12.92 }
+    else { ((chan + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(chan: f32) -> f32 {
+    let chan = clampf32(chan);
+    if chan <= 0.0031308 { chan * 12.92 }
+    else { 1.055 * chan.powf(1.0 / 2.4) - 0.055 }
+}
+
 fn to_8_bit(chan: f32) -> u8 {
     let chan = clampf32(chan);
     (chan * 255.0) as u8
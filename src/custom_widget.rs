@@ -0,0 +1,113 @@
+use dimensions::Dimensions;
+use graphics;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use graphics::vecmath::Matrix2d;
+use mouse::Mouse;
+use point::Point;
+use ui_context::{
+    Id,
+    UIID,
+    UiContext,
+};
+use widget::{ DefaultWidgetState, Widget };
+use Position;
+use Size;
+use Visible;
+
+/// Represents the state of the CustomWidget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted,
+    Clicked,
+}
+
+widget_fns!(CustomWidget, State, Widget::CustomWidget(State::Normal));
+
+/// Check the current state of the custom widget.
+fn get_new_state(is_over: bool,
+                 prev: State,
+                 mouse: Mouse) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Clicked};
+    match (is_over, prev, mouse.left) {
+        (true,  Normal,  Down) => Normal,
+        (true,  _,       Down) => Clicked,
+        (true,  _,       Up)   => Highlighted,
+        (false, Clicked, Down) => Clicked,
+        _                      => Normal,
+    }
+}
+
+/// A widget that is positioned, laid out and hit-tested like any other but
+/// delegates its drawing to the user. This is an escape hatch for bespoke
+/// visualizations that should live within the layout and UIID system.
+///
+/// As the drawing function is specific to the graphics backend, the
+/// CustomWidget is drawn with its own `draw` method rather than via
+/// `Drawable`.
+pub struct CustomWidget<F> {
+    ui_id: UIID,
+    pos: Point,
+    visible: bool,
+    dim: Dimensions,
+    draw_fn: F,
+}
+
+impl<F> CustomWidget<F> {
+
+    /// Create a custom widget that is drawn by the given function. The
+    /// function is called with the graphics backend, the window's transform,
+    /// the widget's position and dimensions and its current state.
+    pub fn new(ui_id: UIID, draw_fn: F) -> CustomWidget<F> {
+        CustomWidget {
+            ui_id: ui_id,
+            pos: [0.0, 0.0],
+            visible: true,
+            dim: [64.0, 64.0],
+            draw_fn: draw_fn,
+        }
+    }
+
+    /// Update the widget's state and call its drawing function. Return
+    /// whether or not the widget was clicked.
+    pub fn draw<B, C>(mut self, uic: &mut UiContext<C>, graphics: &mut B) -> bool
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache,
+            F: FnMut(&mut B, Matrix2d, Point, Dimensions, State)
+    {
+        if !self.visible { uic.keep_state(self.ui_id); return false }
+        let state = *get_state(uic, self.ui_id);
+        let mouse = uic.get_mouse_state();
+        let is_over = uic.is_over(self.ui_id, self.pos, self.dim, mouse.pos);
+        let new_state = get_new_state(is_over, state, mouse);
+        let clicked = match (state, new_state) {
+            (State::Clicked, State::Highlighted) => true,
+            _ => false,
+        };
+        let transform = graphics::abs_transform(uic.win_w, uic.win_h);
+        (self.draw_fn)(graphics, transform, self.pos, self.dim, new_state);
+        set_state(uic, self.ui_id, Widget::CustomWidget(new_state), self.pos, self.dim);
+        clicked
+    }
+
+}
+
+quack! {
+    cw: CustomWidget[F]
+    get:
+        fn () -> Size [] { Size(cw.dim) }
+        fn () -> Position [] { Position(cw.pos) }
+        fn () -> Visible [] { Visible(cw.visible) }
+        fn () -> DefaultWidgetState [] {
+            DefaultWidgetState(Widget::CustomWidget(State::Normal))
+        }
+        fn () -> Id [] { Id(cw.ui_id) }
+    set:
+        fn (val: Position) [] { cw.pos = val.0 }
+        fn (val: Visible) [] { cw.visible = val.0 }
+        fn (val: Size) [] { cw.dim = val.0 }
+    action:
+}
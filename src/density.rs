@@ -0,0 +1,12 @@
+
+/// How much detail a widget should display. This allows the same widgets
+/// to be used within both dense dashboards and spacious editors.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum DisplayDensity {
+    /// Only the essentials, i.e. value readouts and labels are hidden.
+    Compact,
+    /// The default amount of detail.
+    Normal,
+    /// Extra detail, i.e. markers for every point of an envelope.
+    Detailed,
+}
@@ -1,14 +1,26 @@
 
 use graphics::Graphics;
 use graphics::character::CharacterCache;
+use layer::Depth;
 use UiContext;
 
 /// A trait to be implemented for all
-/// drawable widget contexts.
+/// drawable widget contexts. Within `UiContext::render`, widgets draw
+/// into the frame's render command list rather than the backend itself.
 pub trait Drawable {
     fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     ;
+
+    /// Draw the widget to the layer at the given depth, so that it is drawn
+    /// over (or under) the widgets of other layers regardless of the order
+    /// in which they are declared.
+    fn draw_at_depth<C>(&mut self, uic: &mut UiContext<C>, depth: Depth)
+        where
+            C: CharacterCache
+    {
+        uic.draw_layer(depth, |uic, graphics| self.draw(uic, graphics));
+    }
 }
@@ -1,6 +1,9 @@
+use animation::Easing;
 use color::Color;
 use dimensions::Dimensions;
+use layer;
 use mouse::Mouse;
+use overlay;
 use point::Point;
 use rectangle;
 use ui_context::{
@@ -20,6 +23,10 @@ use LabelColor;
 use LabelFontSize;
 use Position;
 use Size;
+use Visible;
+
+/// The time in seconds over which the list slides open.
+const OPEN_DURATION: f64 = 0.12;
 
 /// Tuple / Callback params.
 pub type Idx = usize;
@@ -64,6 +71,7 @@ widget_fns!(DropDownList, State, Widget::DropDownList(State::Closed(DrawState::N
 
 /// Is the cursor currently over the widget? If so which item?
 fn is_over(pos: Point,
+           list_pos: Point,
            mouse_pos: Point,
            dim: Dimensions,
            state: State,
@@ -77,9 +85,9 @@ fn is_over(pos: Point,
         },
         State::Open(_) => {
             let total_h = dim[1] * len as f64;
-            match rectangle::is_over(pos, mouse_pos, [dim[0], total_h]) {
+            match rectangle::is_over(list_pos, mouse_pos, [dim[0], total_h]) {
                 false => None,
-                true => Some((((mouse_pos[1] - pos[1]) / total_h) * len as f64) as usize),
+                true => Some((((mouse_pos[1] - list_pos[1]) / total_h) * len as f64) as usize),
             }
         },
     }
@@ -138,6 +146,7 @@ pub struct DropDownList<'a, F> {
     strings: &'a mut Vec<String>,
     selected: &'a mut Option<Idx>,
     pos: Point,
+    visible: bool,
     dim: Dimensions,
     maybe_callback: Option<F>,
     maybe_color: Option<Color>,
@@ -157,6 +166,7 @@ impl<'a, F> DropDownList<'a, F> {
             strings: strings,
             selected: selected,
             pos: [0.0, 0.0],
+            visible: true,
             dim: [128.0, 32.0],
             maybe_callback: None,
             maybe_color: None,
@@ -173,6 +183,8 @@ quack! {
     list: DropDownList['a, F]
     get:
         fn () -> Size [] { Size(list.dim) }
+        fn () -> Position [] { Position(list.pos) }
+        fn () -> Visible [] { Visible(list.visible) }
         fn () -> DefaultWidgetState [] {
             DefaultWidgetState(
                 Widget::DropDownList(State::Closed(DrawState::Normal))
@@ -190,6 +202,7 @@ quack! {
         fn (val: LabelColor) [] { list.maybe_label_color = Some(val.0) }
         fn (val: LabelFontSize) [] { list.maybe_label_font_size = Some(val.0) }
         fn (val: Position) [] { list.pos = val.0 }
+        fn (val: Visible) [] { list.visible = val.0 }
         fn (val: Size) [] { list.dim = val.0 }
     action:
 }
@@ -204,17 +217,35 @@ impl<'a, F> ::draw::Drawable for DropDownList<'a, F>
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        if !self.visible { uic.keep_state(self.ui_id); return }
 
         let state = *get_state(uic, self.ui_id);
         let mouse = uic.get_mouse_state();
-        let is_over_idx = is_over(self.pos, mouse.pos, self.dim, state, self.strings.len());
+        // Slide the open list so that it remains within the window.
+        let list_dim = [self.dim[0], self.dim[1] * self.strings.len() as f64];
+        let list_pos = overlay::slide(self.pos, list_dim, uic.win_w, uic.win_h);
+        let is_over_idx = is_over(self.pos, list_pos, mouse.pos, self.dim, state, self.strings.len());
         let new_state = get_new_state(is_over_idx, self.strings.len(), state, mouse);
 
+        // Close the menu if it was dismissed via Escape or a click elsewhere.
+        let new_state = match new_state {
+            State::Open(_) if uic.take_dismissed(self.ui_id) => State::Closed(DrawState::Normal),
+            State::Open(_) => { uic.open_overlay(self.ui_id, list_pos, list_dim); new_state },
+            State::Closed(_) => { uic.close_overlay(self.ui_id); new_state },
+        };
+        if new_state != state { uic.damage(list_pos, list_dim) }
+
+        // The open list slides open and the animation restarts each time.
+        let open = match new_state {
+            State::Open(_) => uic.animate(self.ui_id, 0.0, 1.0, OPEN_DURATION, Easing::EaseOut),
+            State::Closed(_) => uic.animate(self.ui_id, 0.0, 0.0, 0.0, Easing::Linear),
+        };
+
         let sel = match *self.selected {
             Some(idx) if idx < self.strings.len() => { Some(idx) },
             _ => None,
         };
-        let color = self.maybe_color.unwrap_or(uic.theme.shape_color);
+        let color = uic.flash_color(self.ui_id, self.maybe_color.unwrap_or(uic.theme.shape_color));
         let t_size = self.maybe_label_font_size.unwrap_or(uic.theme.font_size_medium);
         let t_color = self.maybe_label_color.unwrap_or(uic.theme.label_color);
 
@@ -233,6 +264,25 @@ impl<'a, F> ::draw::Drawable for DropDownList<'a, F>
             }, _ => (),
         }
 
+        // Step through the items with the scroll wheel while hovering the
+        // closed menu.
+        match (new_state, is_over_idx) {
+            (State::Closed(_), Some(_)) if mouse.scroll[1] != 0.0 && self.strings.len() > 0 => {
+                let last = self.strings.len() - 1;
+                let idx = match sel {
+                    Some(idx) if mouse.scroll[1] > 0.0 => if idx > 0 { idx - 1 } else { 0 },
+                    Some(idx) => if idx < last { idx + 1 } else { last },
+                    None => 0,
+                };
+                if sel != Some(idx) {
+                    match self.maybe_callback {
+                        Some(ref mut callback) => (*callback)(self.selected, idx, (*self.strings)[idx].clone()),
+                        None => (),
+                    }
+                }
+            }, _ => (),
+        }
+
         let frame_w = self.maybe_frame.unwrap_or(uic.theme.frame_width);
         let maybe_frame = match frame_w > 0.0 {
             true => Some((frame_w, self.maybe_frame_color.unwrap_or(uic.theme.frame_color))),
@@ -258,24 +308,14 @@ impl<'a, F> ::draw::Drawable for DropDownList<'a, F>
             },
 
             State::Open(draw_state) => {
-                for (i, string) in self.strings.iter().enumerate() {
-                    let rect_state = match sel {
-                        None => {
-                            match draw_state {
-                                DrawState::Normal => rectangle::State::Normal,
-                                DrawState::Highlighted(idx, _) => {
-                                    if i == idx { rectangle::State::Highlighted }
-                                    else { rectangle::State::Normal }
-                                },
-                                DrawState::Clicked(idx, _) => {
-                                    if i == idx { rectangle::State::Clicked }
-                                    else { rectangle::State::Normal }
-                                },
-                            }
-                        },
-                        Some(sel_idx) => {
-                            if sel_idx == i { rectangle::State::Clicked }
-                            else {
+                // The open list is drawn to the overlay layer so that it isn't
+                // covered by widgets declared later in the frame.
+                let (strings, dim) = (&*self.strings, self.dim);
+                uic.draw_layer(layer::OVERLAY, |uic, graphics| {
+                    uic.push_clip(list_pos, [list_dim[0], list_dim[1] * open]);
+                    for (i, string) in strings.iter().enumerate() {
+                        let rect_state = match sel {
+                            None => {
                                 match draw_state {
                                     DrawState::Normal => rectangle::State::Normal,
                                     DrawState::Highlighted(idx, _) => {
@@ -287,17 +327,34 @@ impl<'a, F> ::draw::Drawable for DropDownList<'a, F>
                                         else { rectangle::State::Normal }
                                     },
                                 }
-                            }
-                        },
-                    };
-                    let idx_y = self.dim[1] * i as f64 - i as f64 * frame_w;
-                    let idx_pos = vec2_add(self.pos, [0.0, idx_y]);
-                    rectangle::draw_with_centered_label(
-                        uic.win_w, uic.win_h, graphics, uic, rect_state, idx_pos,
-                        self.dim, maybe_frame, color, &string,
-                        t_size, t_color
-                    )
-                }
+                            },
+                            Some(sel_idx) => {
+                                if sel_idx == i { rectangle::State::Clicked }
+                                else {
+                                    match draw_state {
+                                        DrawState::Normal => rectangle::State::Normal,
+                                        DrawState::Highlighted(idx, _) => {
+                                            if i == idx { rectangle::State::Highlighted }
+                                            else { rectangle::State::Normal }
+                                        },
+                                        DrawState::Clicked(idx, _) => {
+                                            if i == idx { rectangle::State::Clicked }
+                                            else { rectangle::State::Normal }
+                                        },
+                                    }
+                                }
+                            },
+                        };
+                        let idx_y = dim[1] * i as f64 - i as f64 * frame_w;
+                        let idx_pos = vec2_add(list_pos, [0.0, idx_y]);
+                        rectangle::draw_with_centered_label(
+                            uic.win_w, uic.win_h, graphics, uic, rect_state, idx_pos,
+                            dim, maybe_frame, color, &string,
+                            t_size, t_color
+                        )
+                    }
+                    uic.pop_clip();
+                });
             },
 
         }
@@ -1,18 +1,23 @@
-use std::cmp::Ordering;
 use std::num::Float;
 use std::num::ToPrimitive;
 use std::num::FromPrimitive;
+use axis;
 use color::Color;
+use density::DisplayDensity;
 use dimensions::Dimensions;
-use graphics;
 use graphics::{
     Graphics,
 };
 use graphics::character::CharacterCache;
+use keyboard;
 use label;
 use label::FontSize;
-use mouse::Mouse;
+use mouse::{ ButtonState, Mouse };
+use piston::input::keyboard::Key;
 use point::Point;
+use primitive;
+use primitive::{ CircleQuality, LineStyle };
+use smoothing::Smoothing;
 use rectangle;
 use rectangle::{
     Corner
@@ -27,6 +32,7 @@ use utils::{
     map_range,
     percentage,
     val_to_string,
+    ValueFormatter,
 };
 use widget::{ DefaultWidgetState, Widget };
 use vecmath::{
@@ -41,6 +47,7 @@ use LabelColor;
 use LabelFontSize;
 use Position;
 use Size;
+use Visible;
 
 /// Represents the specific elements that the
 /// EnvelopeEditor is made up of. This is used to
@@ -58,6 +65,25 @@ pub enum Element {
     CurvePoint(usize, (f64, f64)),
 }
 
+/// The events passed to the EnvelopeEditor's callback along with the
+/// envelope. Each carries the index of the affected point.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// A point was added at the given index.
+    PointAdded(usize),
+    /// A point was dragged to a new position.
+    PointMoved(usize),
+    /// The point that was at the given index was removed.
+    PointRemoved(usize),
+    /// A point's curve value was changed.
+    CurveChanged(usize),
+    /// The user released a point that they were dragging. This is useful
+    /// for committing a value (i.e. to an undo history) only once.
+    DragFinished(usize),
+    /// The envelope was replaced with points pasted from the clipboard.
+    Pasted,
+}
+
 /// An enum to define which button is clicked.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum MouseButton {
@@ -185,22 +211,158 @@ fn get_new_state(is_over_elem: Option<Element>,
     }
 }
 
-/// Draw a circle at the given position.
-fn draw_circle<B: Graphics>(
-    win_w: f64,
-    win_h: f64,
-    graphics: &mut B,
-    pos: Point,
-    color: Color,
-    radius: f64
-) {
-    graphics::Ellipse::new(color.0)
-        .draw(
-            [pos[0], pos[1], 2.0 * radius, 2.0 * radius],
-            &graphics::default_draw_state(),
-            graphics::abs_transform(win_w, win_h),
-            graphics
-        );
+/// Clamp the given X value between the X values of the points
+/// neighbouring `idx` so that the envelope remains x-sorted.
+fn clamp_x_to_neighbours<E: EnvelopePoint>(env: &[E],
+                                           idx: usize,
+                                           x: <E as EnvelopePoint>::X) -> <E as EnvelopePoint>::X {
+    let x = if idx > 0 && x < env[idx - 1].get_x() { env[idx - 1].get_x() } else { x };
+    if idx + 1 < env.len() && x > env[idx + 1].get_x() { env[idx + 1].get_x() } else { x }
+}
+
+/// The slope (the change in Y per unit of X) of the segment of the
+/// envelope spanning the given X value. Vertical segments have no slope.
+fn segment_slope<E: EnvelopePoint>(env: &[E], x: f64) -> Option<f64> {
+    (1..env.len()).find(|&i| env[i].get_x().to_f64().unwrap() >= x).and_then(|i| {
+        let (start_x, end_x) = (env[i - 1].get_x().to_f64().unwrap(), env[i].get_x().to_f64().unwrap());
+        let (start_y, end_y) = (env[i - 1].get_y().to_f64().unwrap(), env[i].get_y().to_f64().unwrap());
+        if x < start_x || end_x == start_x { None }
+        else { Some((end_y - start_y) / (end_x - start_x)) }
+    })
+}
+
+/// The Y value of the envelope at the given X value, interpolated linearly
+/// between the points either side as the envelope is drawn.
+fn interpolate_y<E: EnvelopePoint>(env: &[E], x: f64) -> Option<f64> {
+    if env.len() == 0 { return None }
+    let (first, last) = (&env[0], &env[env.len() - 1]);
+    if x <= first.get_x().to_f64().unwrap() { return first.get_y().to_f64() }
+    if x >= last.get_x().to_f64().unwrap() { return last.get_y().to_f64() }
+    (1..env.len()).find(|&i| env[i].get_x().to_f64().unwrap() >= x).and_then(|i| {
+        let (start_x, end_x) = (env[i - 1].get_x().to_f64().unwrap(), env[i].get_x().to_f64().unwrap());
+        let (start_y, end_y) = (env[i - 1].get_y().to_f64().unwrap(), env[i].get_y().to_f64().unwrap());
+        if end_x == start_x { Some(end_y) }
+        else { Some(start_y + (end_y - start_y) * (x - start_x) / (end_x - start_x)) }
+    })
+}
+
+/// Whether the X axis can be logarithmic, which requires a positive range.
+fn is_log_range(min: f64, max: f64) -> bool {
+    min > 0.0 && max > min
+}
+
+/// The position (0.0 to 1.0) of the X value along the pad. Positions are
+/// proportional to the logarithm of the value when `log_x` is set.
+fn x_to_perc<X: Float + ToPrimitive + FromPrimitive>(x: X, min: X, max: X, log_x: bool) -> f32 {
+    let (x, min, max) = (x.to_f64().unwrap(), min.to_f64().unwrap(), max.to_f64().unwrap());
+    if log_x && is_log_range(min, max) { ((x.max(min) / min).ln() / (max / min).ln()) as f32 }
+    else { ((x - min) / (max - min)) as f32 }
+}
+
+/// The X value at the given position (0.0 to 1.0) along the pad.
+fn perc_to_x<X: Float + ToPrimitive + FromPrimitive>(perc: f32, min: X, max: X, log_x: bool) -> X {
+    let (min_f, max_f) = (min.to_f64().unwrap(), max.to_f64().unwrap());
+    if log_x && is_log_range(min_f, max_f) {
+        FromPrimitive::from_f64(min_f * (max_f / min_f).powf(perc as f64)).unwrap()
+    } else {
+        map_range(perc, 0.0, 1.0, min, max)
+    }
+}
+
+/// The powers of `base` within the range, at which the lines of a
+/// logarithmic X axis are drawn.
+fn log_decades(min: f64, max: f64, base: f64) -> Vec<f64> {
+    let mut decades = Vec::new();
+    if !is_log_range(min, max) || base <= 1.0 { return decades }
+    let mut decade = base.powf((min.ln() / base.ln()).ceil());
+    while decade <= max {
+        decades.push(decade);
+        decade = decade * base;
+    }
+    decades
+}
+
+/// The values dividing each power of `base` within the range into the given
+/// number of equal parts (i.e. 2, 3 ... 9 times each power of ten for nine
+/// divisions), at which the minor lines of a logarithmic X axis are drawn.
+fn log_subdivisions(min: f64, max: f64, base: f64, divisions: usize) -> Vec<f64> {
+    let mut values = Vec::new();
+    if !is_log_range(min, max) || base <= 1.0 || divisions < 2 { return values }
+    let mut decade = base.powf((min.ln() / base.ln()).floor());
+    while decade < max {
+        for i in 1..divisions {
+            let x = decade + decade * (base - 1.0) * i as f64 / divisions as f64;
+            if x > min && x < max { values.push(x) }
+        }
+        decade = decade * base;
+    }
+    values
+}
+
+/// Whether the point at `idx` is the first or last of an envelope of `len`
+/// points, which stay in place horizontally when endpoints are locked.
+fn is_endpoint(len: usize, idx: usize) -> bool {
+    idx == 0 || idx + 1 == len
+}
+
+/// Whether or not the envelope's points are sorted by their X value.
+pub fn is_x_sorted<E: EnvelopePoint>(env: &[E]) -> bool {
+    (1..env.len()).all(|i| env[i - 1].get_x() <= env[i].get_x())
+}
+
+/// The position (0.0 to 1.0) of the value within the range, or 0.0 if the
+/// range is empty.
+fn normalise(value: f64, min: f64, max: f64) -> f64 {
+    if max == min { 0.0 } else { (value - min) / (max - min) }
+}
+
+/// The rules by which an EnvelopeEditor's points may be edited.
+#[derive(Copy, Clone, Debug)]
+struct Restrictions {
+    lock_endpoints: bool,
+    allow_add: bool,
+    allow_remove: bool,
+    maybe_max_points: Option<usize>,
+}
+
+/// The envelope that results from pasting the points, copied relative to
+/// their editor's ranges, into `env` with the given ranges. Points whose
+/// values can't be represented are skipped. With locked endpoints, the
+/// points are fitted between the current endpoints. Returns None if the
+/// paste would add or remove points when that isn't allowed.
+fn paste_points<E: EnvelopePoint>(env: &[E],
+                                  points: &[(f64, f64, f32)],
+                                  (min_x, max_x): (f64, f64),
+                                  (min_y, max_y): (f64, f64),
+                                  restrictions: Restrictions) -> Option<Vec<E>> {
+    let locked = restrictions.lock_endpoints && env.len() >= 2;
+    let (min_x, max_x) = match locked {
+        true => (env[0].get_x().to_f64().unwrap(), env[env.len() - 1].get_x().to_f64().unwrap()),
+        false => (min_x, max_x),
+    };
+    let max_points = restrictions.maybe_max_points.unwrap_or(points.len());
+    let mut pasted: Vec<E> = points.iter().filter_map(|&(x, y, curve)| {
+        let x = min_x + (max_x - min_x) * clamp(x, 0.0, 1.0);
+        let y = min_y + (max_y - min_y) * clamp(y, 0.0, 1.0);
+        match (FromPrimitive::from_f64(x), FromPrimitive::from_f64(y)) {
+            (Some(x), Some(y)) => {
+                let mut pt: E = EnvelopePoint::new(x, y);
+                pt.set_curve(curve);
+                Some(pt)
+            },
+            _ => None,
+        }
+    }).take(max_points).collect();
+    if pasted.len() > env.len() && !restrictions.allow_add { return None }
+    if pasted.len() < env.len() && !restrictions.allow_remove { return None }
+    if locked {
+        if pasted.len() < 2 { return None }
+        let last = pasted.len() - 1;
+        pasted[0].set_x(env[0].get_x());
+        pasted[last].set_x(env[env.len() - 1].get_x());
+    }
+    pasted.sort_by(|a, b| a.get_x().partial_cmp(&b.get_x()).unwrap_or(::std::cmp::Ordering::Equal));
+    Some(pasted)
 }
 
 /// A context on which the builder pattern can be implemented.
@@ -211,9 +373,32 @@ pub struct EnvelopeEditor<'a, E:'a, F> where E: EnvelopePoint {
     min_x: <E as EnvelopePoint>::X, max_x: <E as EnvelopePoint>::X,
     min_y: <E as EnvelopePoint>::Y, max_y: <E as EnvelopePoint>::Y,
     pt_radius: f64,
+    maybe_pt_quality: Option<CircleQuality>,
+    maybe_smoothing: Option<Smoothing>,
+    maybe_density: Option<DisplayDensity>,
     line_width: f64,
     font_size: FontSize,
+    assert_sorted: bool,
+    slope_readout: bool,
+    read_only: bool,
+    lock_endpoints: bool,
+    maybe_max_points: Option<usize>,
+    log_x: bool,
+    log_base: f64,
+    allow_add: bool,
+    allow_remove: bool,
+    maybe_grid: Option<axis::Divisions>,
+    tick_labels: bool,
+    maybe_units: Option<(&'a str, &'a str)>,
+    maybe_value_formatter: Option<ValueFormatter<'a>>,
+    maybe_y_value_formatter: Option<ValueFormatter<'a>>,
+    maybe_line_style: Option<LineStyle>,
+    maybe_reference: Option<&'a [E]>,
+    maybe_playhead: Option<f64>,
+    playhead_value: bool,
+    maybe_segment_colors: Option<&'a [Color]>,
     pos: Point,
+    visible: bool,
     dim: Dimensions,
     maybe_callback: Option<F>,
     maybe_color: Option<Color>,
@@ -230,10 +415,51 @@ impl<'a, E, F> EnvelopeEditor<'a, E, F> where E: EnvelopePoint {
         EnvelopeEditor { pt_radius: radius, ..self }
     }
     #[inline]
+    pub fn point_quality(self, quality: CircleQuality) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { maybe_pt_quality: Some(quality), ..self }
+    }
+    /// Filter the pointer position while dragging points to tame jittery input.
+    #[inline]
+    pub fn smoothing(self, smoothing: Smoothing) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { maybe_smoothing: Some(smoothing), ..self }
+    }
+    /// Set the amount of detail to display. Value readouts are hidden when
+    /// Compact and every point is marked when Detailed.
+    #[inline]
+    pub fn density(self, density: DisplayDensity) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { maybe_density: Some(density), ..self }
+    }
+    #[inline]
     pub fn line_width(self, width: f64) -> EnvelopeEditor<'a, E, F> {
         EnvelopeEditor { line_width: width, ..self }
     }
     #[inline]
+    pub fn line_style(self, style: LineStyle) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { maybe_line_style: Some(style), ..self }
+    }
+    /// A read-only envelope drawn dimmed behind the editable one, i.e. a
+    /// preset or a copy of the envelope taken before a drag, for comparison.
+    #[inline]
+    pub fn reference(self, reference: &'a [E]) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { maybe_reference: Some(reference), ..self }
+    }
+    /// Draw a vertical cursor at the given X value, i.e. to show the
+    /// playback position over an automation curve.
+    #[inline]
+    pub fn playhead(self, x: f64) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { maybe_playhead: Some(x), ..self }
+    }
+    /// Mark the envelope's value where the playhead crosses it.
+    #[inline]
+    pub fn playhead_value(self, show: bool) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { playhead_value: show, ..self }
+    }
+    /// The colors used for each segment of the envelope.
+    #[inline]
+    pub fn segment_colors(self, colors: &'a [Color]) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { maybe_segment_colors: Some(colors), ..self }
+    }
+    #[inline]
     pub fn value_font_size(self, size: FontSize) -> EnvelopeEditor<'a, E, F> {
         EnvelopeEditor { font_size: size, ..self }
     }
@@ -241,6 +467,96 @@ impl<'a, E, F> EnvelopeEditor<'a, E, F> where E: EnvelopePoint {
     pub fn skew_y(self, skew: f32) -> EnvelopeEditor<'a, E, F> {
         EnvelopeEditor { skew_y_range: skew, ..self }
     }
+    /// Panic if the envelope is not x-sorted once the editor has
+    /// finished updating it. Useful for debugging.
+    #[inline]
+    pub fn assert_sorted(self, assert: bool) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { assert_sorted: assert, ..self }
+    }
+    /// Display the slope of the segment under the mouse, in Y units per
+    /// unit of X, while hovering over the pad. The slope is formatted by the
+    /// Y value formatter (if any).
+    #[inline]
+    pub fn slope_readout(self, readout: bool) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { slope_readout: readout, ..self }
+    }
+    /// Display the envelope without allowing points to be added, moved or
+    /// removed. Points are still highlighted and their values shown on hover.
+    #[inline]
+    pub fn read_only(self, read_only: bool) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { read_only: read_only, ..self }
+    }
+    /// Keep the first and last points at their X values so that the envelope
+    /// always spans the same range, i.e. for ADSR envelopes. They may still be
+    /// dragged vertically but can't be removed, and new points are only added
+    /// between them.
+    #[inline]
+    pub fn lock_endpoints(self, lock: bool) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { lock_endpoints: lock, ..self }
+    }
+    /// Space X values logarithmically, i.e. for frequencies in Hz. The
+    /// envelope's values are unchanged, only their positions on the pad.
+    /// This requires that `min_x` is greater than zero.
+    #[inline]
+    pub fn log_x(self, log: bool) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { log_x: log, ..self }
+    }
+    /// The base of the logarithmic X axis, at whose powers grid lines are
+    /// drawn across the pad (10.0 by default).
+    #[inline]
+    pub fn log_base(self, base: f64) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { log_base: base, ..self }
+    }
+    /// Draw grid lines dividing the X and Y axes into the given number of
+    /// divisions. A logarithmic X axis is always divided at each power of its
+    /// base, and the X divisions instead divide each of those decades.
+    #[inline]
+    pub fn grid(self, x_divisions: usize, y_divisions: usize) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { maybe_grid: Some((x_divisions, y_divisions)), ..self }
+    }
+    /// Label each grid line with its value.
+    #[inline]
+    pub fn tick_labels(self, labels: bool) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { tick_labels: labels, ..self }
+    }
+    /// The units of the X and Y values (i.e. "ms" and "dB"), which are
+    /// appended to the values in the readout.
+    #[inline]
+    pub fn units(self, x_unit: &'a str, y_unit: &'a str) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { maybe_units: Some((x_unit, y_unit)), ..self }
+    }
+    /// Format the values shown in the point readout. This formats both the
+    /// X and the Y value unless a `y_value_formatter` is also given.
+    #[inline]
+    pub fn value_formatter<G>(self, formatter: G) -> EnvelopeEditor<'a, E, F>
+        where G: Fn(f64) -> String + 'a
+    {
+        EnvelopeEditor { maybe_value_formatter: Some(Box::new(formatter)), ..self }
+    }
+    /// Format the Y value shown in the point readout.
+    #[inline]
+    pub fn y_value_formatter<G>(self, formatter: G) -> EnvelopeEditor<'a, E, F>
+        where G: Fn(f64) -> String + 'a
+    {
+        EnvelopeEditor { maybe_y_value_formatter: Some(Box::new(formatter)), ..self }
+    }
+    /// The maximum number of points, beyond which double-clicking no
+    /// longer adds points.
+    #[inline]
+    pub fn max_points(self, max: usize) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { maybe_max_points: Some(max), ..self }
+    }
+    /// Whether points may be added by double-clicking the pad.
+    #[inline]
+    pub fn allow_add(self, allow: bool) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { allow_add: allow, ..self }
+    }
+    /// Whether points may be removed by right-clicking them. Points may
+    /// still be dragged, so a fixed number of points can be edited.
+    #[inline]
+    pub fn allow_remove(self, allow: bool) -> EnvelopeEditor<'a, E, F> {
+        EnvelopeEditor { allow_remove: allow, ..self }
+    }
 }
 
 impl <'a, E, F> EnvelopeEditor<'a, E, F> where E: EnvelopePoint {
@@ -258,9 +574,32 @@ impl <'a, E, F> EnvelopeEditor<'a, E, F> where E: EnvelopePoint {
             min_x: min_x, max_x: max_x,
             min_y: min_y, max_y: max_y,
             pt_radius: 6.0, // Default envelope point radius.
+            maybe_pt_quality: None,
+            maybe_smoothing: None,
+            maybe_density: None,
             line_width: 2.0, // Default envelope line width.
             font_size: 18u32,
+            assert_sorted: false,
+            slope_readout: false,
+            read_only: false,
+            lock_endpoints: false,
+            maybe_max_points: None,
+            log_x: false,
+            log_base: 10.0,
+            allow_add: true,
+            allow_remove: true,
+            maybe_grid: None,
+            tick_labels: false,
+            maybe_units: None,
+            maybe_value_formatter: None,
+            maybe_y_value_formatter: None,
+            maybe_line_style: None,
+            maybe_reference: None,
+            maybe_playhead: None,
+            playhead_value: false,
+            maybe_segment_colors: None,
             pos: [0.0, 0.0],
+            visible: true,
             dim: [256.0, 128.0],
             maybe_callback: None,
             maybe_color: None,
@@ -277,13 +616,15 @@ quack! {
     env: EnvelopeEditor['a, E, F]
     get:
         fn () -> Size [where E: EnvelopePoint] { Size(env.dim) }
+        fn () -> Position [where E: EnvelopePoint] { Position(env.pos) }
+        fn () -> Visible [where E: EnvelopePoint] { Visible(env.visible) }
         fn () -> DefaultWidgetState [where E: EnvelopePoint] {
             DefaultWidgetState(Widget::EnvelopeEditor(State::Normal))
         }
         fn () -> Id [where E: EnvelopePoint] { Id(env.ui_id) }
     set:
         fn (val: Color) [where E: EnvelopePoint] { env.maybe_color = Some(val) }
-        fn (val: Callback<F>) [where E: EnvelopePoint, F: FnMut(&mut Vec<E>, usize) + 'a] {
+        fn (val: Callback<F>) [where E: EnvelopePoint, F: FnMut(&mut Vec<E>, Event) + 'a] {
             env.maybe_callback = Some(val.0)
         }
         fn (val: FrameColor) [where E: EnvelopePoint] { env.maybe_frame_color = Some(val.0) }
@@ -292,6 +633,7 @@ quack! {
         fn (val: LabelColor) [where E: EnvelopePoint] { env.maybe_label_color = Some(val.0) }
         fn (val: LabelFontSize) [where E: EnvelopePoint] { env.maybe_label_font_size = Some(val.0) }
         fn (val: Position) [where E: EnvelopePoint] { env.pos = val.0 }
+        fn (val: Visible) [where E: EnvelopePoint] { env.visible = val.0 }
         fn (val: Size) [where E: EnvelopePoint] { env.dim = val.0 }
     action:
 }
@@ -301,7 +643,7 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
         E: EnvelopePoint,
         <E as EnvelopePoint>::X: Float,
         <E as EnvelopePoint>::Y: Float,
-        F: FnMut(&mut Vec<E>, usize) + 'a
+        F: FnMut(&mut Vec<E>, Event) + 'a
 {
     #[inline]
     fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
@@ -309,15 +651,22 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        if !self.visible { uic.keep_state(self.ui_id); return }
         let state = *get_state(uic, self.ui_id);
         let mouse = uic.get_mouse_state();
         let skew = self.skew_y_range;
         let (min_x, max_x, min_y, max_y) = (self.min_x, self.max_x, self.min_y, self.max_y);
         let pt_radius = self.pt_radius;
+        let lock_endpoints = self.lock_endpoints;
+        let log_x = self.log_x;
+        let units = self.maybe_units;
+        let maybe_x_formatter = self.maybe_value_formatter.as_ref();
+        let maybe_y_formatter = self.maybe_y_value_formatter.as_ref().or(maybe_x_formatter);
+        let pt_quality = uic.circle_quality(self.maybe_pt_quality);
         let font_size = self.font_size;
 
         // Rect.
-        let color = self.maybe_color.unwrap_or(uic.theme.shape_color);
+        let color = uic.flash_color(self.ui_id, self.maybe_color.unwrap_or(uic.theme.shape_color));
         let frame_w = self.maybe_frame.unwrap_or(uic.theme.frame_width);
         let frame_w2 = frame_w * 2.0;
         let maybe_frame = match frame_w > 0.0 {
@@ -330,7 +679,7 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
         // Create a vector with each EnvelopePoint value represented as a
         // skewed percentage between 0.0 .. 1.0 .
         let perc_env: Vec<(f32, f32, f32)> = self.env.iter().map(|pt| {
-            (percentage(pt.get_x(), min_x, max_x),
+            (x_to_perc(pt.get_x(), min_x, max_x, log_x),
              percentage(pt.get_y(), min_y, max_y).powf(1.0 / skew),
              pt.get_curve())
         }).collect();
@@ -340,7 +689,29 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
             self.pos, mouse.pos, self.dim,
             pad_pos, pad_dim, &perc_env, pt_radius
         );
-        let new_state = get_new_state(is_over_elem, state, mouse);
+        // Read-only envelopes ignore presses so that they may only be highlighted.
+        let state_mouse = match self.read_only {
+            true => Mouse { left: ButtonState::Up, right: ButtonState::Up, ..mouse },
+            false => mouse,
+        };
+        let new_state = get_new_state(is_over_elem, state, state_mouse);
+        let is_left_drag = |state: State| match state {
+            State::Clicked(_, MouseButton::Left) => true,
+            _ => false,
+        };
+        let is_dragging = is_left_drag(new_state);
+        let drag_pos = match is_dragging || is_left_drag(state) {
+            true => {
+                // Ctrl drags finely and Shift locks the drag to one axis, as in most DAWs.
+                let fine_pos = uic.fine_pointer_with(self.ui_id, mouse.pos, keyboard::CTRL);
+                let locked_pos = uic.axis_locked_pointer(self.ui_id, fine_pos);
+                uic.smooth_pointer(self.ui_id, self.maybe_smoothing, locked_pos)
+            },
+            false => mouse.pos,
+        };
+        // The smoothing is only reset once the released point has taken its
+        // final (smoothed) position.
+        if !is_dragging { uic.reset_pointer_smoothing(self.ui_id) }
 
         // Draw rect.
         rectangle::draw(uic.win_w, uic.win_h, graphics,
@@ -357,29 +728,113 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
             uic.draw_text(graphics, l_pos, l_size, l_color, l_text);
         };
 
+        // Draw the grid. Each power of the base is always marked along a
+        // logarithmic X axis, with unlabelled lines dividing each decade.
+        {
+            let (min_x_f, max_x_f) = (min_x.to_f64().unwrap(), max_x.to_f64().unwrap());
+            let (min_y_f, max_y_f) = (min_y.to_f64().unwrap(), max_y.to_f64().unwrap());
+            let x_label = |x: f64| axis::tick_label(x, min_x_f, max_x_f, pad_dim[0]);
+            let x_ticks: Vec<axis::Tick> = match (log_x, self.maybe_grid) {
+                (true, maybe_grid) => {
+                    let mut ticks: Vec<axis::Tick> = log_decades(min_x_f, max_x_f, self.log_base).into_iter()
+                        .map(|x| (x_to_perc(x, min_x_f, max_x_f, true) as f64, x_label(x)))
+                        .collect();
+                    if let Some((x_divisions, _)) = maybe_grid {
+                        ticks.extend(log_subdivisions(min_x_f, max_x_f, self.log_base, x_divisions).into_iter()
+                            .map(|x| (x_to_perc(x, min_x_f, max_x_f, true) as f64, String::new())));
+                    }
+                    ticks
+                },
+                (false, Some((x_divisions, _))) => axis::linear_ticks(min_x_f, max_x_f, x_divisions).into_iter()
+                    .map(|(perc, x)| (perc, x_label(x)))
+                    .collect(),
+                (false, None) => Vec::new(),
+            };
+            let y_ticks: Vec<axis::Tick> = match self.maybe_grid {
+                Some((_, y_divisions)) => axis::linear_ticks(min_y_f, max_y_f, y_divisions).into_iter()
+                    .map(|(perc, y)| (perc.powf(1.0 / skew as f64),
+                                      axis::tick_label(y, min_y_f, max_y_f, pad_dim[1])))
+                    .collect(),
+                None => Vec::new(),
+            };
+            let small_font_size = uic.theme.font_size_small;
+            axis::draw(uic, graphics, pad_pos, pad_dim, &x_ticks, &y_ticks, self.tick_labels,
+                       color.plain_contrast(), small_font_size);
+        }
+
+        // Draw the reference envelope behind the editable one.
+        let line_style = self.maybe_line_style.or(uic.theme.line_style).unwrap_or(LineStyle::solid());
+        if let Some(reference) = self.maybe_reference {
+            let ref_points: Vec<Point> = reference.iter().map(|pt| {
+                [map_range(x_to_perc(pt.get_x(), min_x, max_x, log_x), 0.0, 1.0, pad_pos[0], pad_pos[0] + pad_dim[0]),
+                 map_range(percentage(pt.get_y(), min_y, max_y).powf(1.0 / skew), 0.0, 1.0, pad_pos[1] + pad_dim[1], pad_pos[1])]
+            }).collect();
+            primitive::styled_polyline(uic.win_w, uic.win_h, graphics, &ref_points,
+                                       self.line_width, &[color.plain_contrast().with_alpha(0.3)], line_style);
+        }
+
         // Draw the envelope lines.
-        match self.env.len() {
-            0 | 1 => (),
-            _ => {
-                let Color(col) = color.plain_contrast();
-                let line = graphics::Line::round(col, 0.5 * self.line_width);
-                let draw_state = graphics::default_draw_state();
-                let transform = graphics::abs_transform(uic.win_w, uic.win_h);
-                for i in 1..perc_env.len() {
-                    let (x_a, y_a, _) = perc_env[i - 1];
-                    let (x_b, y_b, _) = perc_env[i];
-                    let p_a = [map_range(x_a, 0.0, 1.0, pad_pos[0], pad_pos[0] + pad_dim[0]),
-                               map_range(y_a, 0.0, 1.0, pad_pos[1] + pad_dim[1], pad_pos[1])];
-                    let p_b = [map_range(x_b, 0.0, 1.0, pad_pos[0], pad_pos[0] + pad_dim[0]),
-                               map_range(y_b, 0.0, 1.0, pad_pos[1] + pad_dim[1], pad_pos[1])];
-                    line.draw(
-                        [p_a[0], p_a[1], p_b[0], p_b[1]],
-                        draw_state,
-                        transform,
-                        graphics
-                    );
+        let env_points: Vec<Point> = perc_env.iter().map(|&(x, y, _)| {
+            [map_range(x, 0.0, 1.0, pad_pos[0], pad_pos[0] + pad_dim[0]),
+             map_range(y, 0.0, 1.0, pad_pos[1] + pad_dim[1], pad_pos[1])]
+        }).collect();
+        let line_color = [color.plain_contrast()];
+        let line_colors = match self.maybe_segment_colors {
+            Some(colors) => colors,
+            None => &line_color[..],
+        };
+        primitive::styled_polyline(uic.win_w, uic.win_h, graphics, &env_points,
+                                   self.line_width, line_colors, line_style);
+
+        // Draw the playhead over the envelope.
+        if let Some(playhead_x) = self.maybe_playhead {
+            let (min_x_f, max_x_f) = (min_x.to_f64().unwrap(), max_x.to_f64().unwrap());
+            if playhead_x >= min_x_f && playhead_x <= max_x_f {
+                let playhead_perc = x_to_perc(playhead_x, min_x_f, max_x_f, log_x);
+                let x = map_range(playhead_perc, 0.0, 1.0, pad_pos[0], pad_pos[0] + pad_dim[0]);
+                let playhead_color = color.plain_contrast().highlighted();
+                primitive::line(uic.win_w, uic.win_h, graphics, [x, pad_pos[1]],
+                                [x, pad_pos[1] + pad_dim[1]], 1.0, playhead_color);
+                if self.playhead_value {
+                    if let Some(y) = interpolate_y(self.env, playhead_x) {
+                        let (min_y_f, max_y_f) = (min_y.to_f64().unwrap(), max_y.to_f64().unwrap());
+                        let y_perc = percentage(y, min_y_f, max_y_f).powf(1.0 / skew);
+                        let y = map_range(y_perc, 0.0, 1.0, pad_pos[1] + pad_dim[1], pad_pos[1]);
+                        primitive::circle_with_quality(uic.win_w, uic.win_h, graphics, [x, y],
+                                                       pt_radius / 2.0, playhead_color, pt_quality);
+                    }
                 }
-            },
+            }
+        }
+
+        // Mark every point when Detailed.
+        let density = self.maybe_density.or(uic.theme.display_density).unwrap_or(DisplayDensity::Normal);
+        if let DisplayDensity::Detailed = density {
+            for &p in env_points.iter() {
+                primitive::circle_with_quality(uic.win_w, uic.win_h, graphics, p,
+                                               pt_radius / 2.0, color.plain_contrast(), pt_quality);
+            }
+        }
+
+        // Draw the slope of the segment under the mouse.
+        let is_over_pad = mouse.pos[0] >= pad_pos[0] && mouse.pos[0] <= pad_pos[0] + pad_dim[0]
+                       && mouse.pos[1] >= pad_pos[1] && mouse.pos[1] <= pad_pos[1] + pad_dim[1];
+        if self.slope_readout && is_over_pad && new_state != State::Normal && density != DisplayDensity::Compact {
+            let (min_x_f, max_x_f) = (min_x.to_f64().unwrap(), max_x.to_f64().unwrap());
+            let (min_y_f, max_y_f) = (min_y.to_f64().unwrap(), max_y.to_f64().unwrap());
+            let mouse_perc: f32 = map_range(mouse.pos[0], pad_pos[0], pad_pos[0] + pad_dim[0], 0.0, 1.0);
+            let mouse_x = perc_to_x(mouse_perc, min_x_f, max_x_f, log_x);
+            if let Some(slope) = segment_slope(self.env, mouse_x) {
+                let max_slope = (max_y_f - min_y_f) / (max_x_f - min_x_f);
+                let slope_string = match maybe_y_formatter {
+                    Some(formatter) => format!("slope: {}", formatter(slope)),
+                    None => format!("slope: {}", val_to_string(slope, max_slope, max_slope, pad_dim[1] as usize)),
+                };
+                let slope_string_w = label::value_width(uic, font_size, &slope_string);
+                let slope_string_pos = [pad_pos[0] + pad_dim[0] - slope_string_w, pad_pos[1]];
+                uic.draw_value_text(graphics, slope_string_pos,
+                                    font_size, color.plain_contrast(), &slope_string);
+            }
         }
 
         // Determine the left and right X bounds for a point.
@@ -405,31 +860,41 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
                                        idx: usize,
                                        p_pos: Point| {
 
-                    let x_string = val_to_string(
-                        (*envelope)[idx].get_x(),
-                        max_x,
-                        max_x - min_x,
-                        pad_dim[0] as usize
-                    );
-                    let y_string = val_to_string(
-                        (*envelope)[idx].get_y(),
-                        max_y,
-                        max_y - min_y,
-                        pad_dim[1] as usize
-                    );
-                    let xy_string = format!("{}, {}", x_string, y_string);
-                    let xy_string_w = label::width(uic, font_size, &xy_string);
-                    let xy_string_pos = match rectangle::corner(pad_pos, p_pos, pad_dim) {
-                        Corner::TopLeft => [p_pos[0], p_pos[1]],
-                        Corner::TopRight => [p_pos[0] - xy_string_w, p_pos[1]],
-                        Corner::BottomLeft => [p_pos[0], p_pos[1] - font_size as f64],
-                        Corner::BottomRight => [p_pos[0] - xy_string_w, p_pos[1] - font_size as f64],
-                    };
-                    uic.draw_text(graphics, xy_string_pos,
-                                font_size, color.plain_contrast(), &xy_string);
-                    draw_circle(uic.win_w, uic.win_h, graphics,
-                                vec2_sub(p_pos, [pt_radius, pt_radius]),
-                                color.plain_contrast(), pt_radius);
+                    if density != DisplayDensity::Compact {
+                        let x_string = match maybe_x_formatter {
+                            Some(formatter) => formatter((*envelope)[idx].get_x().to_f64().unwrap()),
+                            None => val_to_string(
+                                (*envelope)[idx].get_x(),
+                                max_x,
+                                max_x - min_x,
+                                pad_dim[0] as usize
+                            ),
+                        };
+                        let y_string = match maybe_y_formatter {
+                            Some(formatter) => formatter((*envelope)[idx].get_y().to_f64().unwrap()),
+                            None => val_to_string(
+                                (*envelope)[idx].get_y(),
+                                max_y,
+                                max_y - min_y,
+                                pad_dim[1] as usize
+                            ),
+                        };
+                        let xy_string = match units {
+                            Some((x_unit, y_unit)) => format!("{} {}, {} {}", x_string, x_unit, y_string, y_unit),
+                            None => format!("{}, {}", x_string, y_string),
+                        };
+                        let xy_string_w = label::value_width(uic, font_size, &xy_string);
+                        let xy_string_pos = match rectangle::corner(pad_pos, p_pos, pad_dim) {
+                            Corner::TopLeft => [p_pos[0], p_pos[1]],
+                            Corner::TopRight => [p_pos[0] - xy_string_w, p_pos[1]],
+                            Corner::BottomLeft => [p_pos[0], p_pos[1] - font_size as f64],
+                            Corner::BottomRight => [p_pos[0] - xy_string_w, p_pos[1] - font_size as f64],
+                        };
+                        uic.draw_value_text(graphics, xy_string_pos,
+                                            font_size, color.plain_contrast(), &xy_string);
+                    }
+                    primitive::circle_with_quality(uic.win_w, uic.win_h, graphics, p_pos,
+                                                   pt_radius, color.plain_contrast(), pt_quality);
                 };
 
                 match elem {
@@ -440,7 +905,10 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
                         let (left_x_bound, right_x_bound) = get_x_bounds(&perc_env, idx);
                         let left_pixel_bound = map_range(left_x_bound, 0.0, 1.0, pad_pos[0], pad_x_right);
                         let right_pixel_bound = map_range(right_x_bound, 0.0, 1.0, pad_pos[0], pad_x_right);
-                        let p_pos_x_clamped = clamp(p_pos[0], left_pixel_bound, right_pixel_bound);
+                        let p_pos_x_clamped = match lock_endpoints && is_endpoint(env_points.len(), idx) {
+                            true => env_points[idx][0],
+                            false => clamp(p_pos[0], left_pixel_bound, right_pixel_bound),
+                        };
                         let p_pos_y_clamped = clamp(p_pos[1], pad_pos[1], pad_pos[1] + pad_dim[1]);
                         draw_env_pt(uic, self.env, idx, [p_pos_x_clamped, p_pos_y_clamped]);
                         Some(idx)
@@ -476,9 +944,9 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
             let new_x_perc = percentage(mouse_x_clamped, 0f64, pad_dim[0]);
             let new_y_perc = percentage(mouse_y_clamped, pad_dim[1], 0f64).powf(skew);
             let (left_bound, right_bound) = get_x_bounds(perc_envelope, idx);
-            (map_range(if new_x_perc > right_bound { right_bound }
+            (perc_to_x(if new_x_perc > right_bound { right_bound }
                        else if new_x_perc < left_bound { left_bound }
-                       else { new_x_perc }, 0.0, 1.0, min_x, max_x),
+                       else { new_x_perc }, min_x, max_x, log_x),
              map_range(new_y_perc, 0.0, 1.0, min_y, max_y))
         };
 
@@ -495,19 +963,29 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
                         match m_button {
                             MouseButton::Left => {
                                 // Adjust the point and trigger the callback.
-                                let (new_x, new_y) = get_new_value(&perc_env, idx, mouse.pos[0], mouse.pos[1]);
+                                let (new_x, new_y) = get_new_value(&perc_env, idx, drag_pos[0], drag_pos[1]);
+                                let new_x = clamp_x_to_neighbours(self.env, idx, new_x);
+                                let current_x = (*self.env)[idx].get_x();
+                                let new_x = if lock_endpoints && is_endpoint(self.env.len(), idx) { current_x } else { new_x };
+                                let current_y = (*self.env)[idx].get_y();
+                                let has_moved = new_x != current_x || new_y != current_y;
                                 self.env[idx].set_x(new_x);
                                 self.env[idx].set_y(new_y);
                                 match self.maybe_callback {
-                                    Some(ref mut callback) => callback(self.env, idx),
+                                    Some(ref mut callback) => {
+                                        if has_moved { callback(self.env, Event::PointMoved(idx)) }
+                                        callback(self.env, Event::DragFinished(idx));
+                                    },
                                     None => (),
                                 }
                             },
+                            MouseButton::Right if !self.allow_remove
+                                                || lock_endpoints && is_endpoint(self.env.len(), idx) => (),
                             MouseButton::Right => {
                                 // Delete the point and trigger the callback.
                                 self.env.remove(idx);
                                 match self.maybe_callback {
-                                    Some(ref mut callback) => callback(self.env, idx),
+                                    Some(ref mut callback) => callback(self.env, Event::PointRemoved(idx)),
                                     None => (),
                                 }
                             },
@@ -517,15 +995,18 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
                     (State::Clicked(_, prev_m_button), State::Clicked(_, m_button)) => {
                         match (prev_m_button, m_button) {
                             (MouseButton::Left, MouseButton::Left) => {
-                                let (new_x, new_y) = get_new_value(&perc_env, idx, mouse.pos[0], mouse.pos[1]);
+                                let (new_x, new_y) = get_new_value(&perc_env, idx, drag_pos[0], drag_pos[1]);
+                                let new_x = clamp_x_to_neighbours(self.env, idx, new_x);
                                 let current_x = (*self.env)[idx].get_x();
+                                let new_x = if lock_endpoints && is_endpoint(self.env.len(), idx) { current_x } else { new_x };
                                 let current_y = (*self.env)[idx].get_y();
                                 if new_x != current_x || new_y != current_y {
                                     // Adjust the point and trigger the callback.
+                                    uic.damage(self.pos, self.dim);
                                     self.env[idx].set_x(new_x);
                                     self.env[idx].set_y(new_y);
                                     match self.maybe_callback {
-                                        Some(ref mut callback) => callback(self.env, idx),
+                                        Some(ref mut callback) => callback(self.env, Event::PointMoved(idx)),
                                         None => (),
                                     }
                                 }
@@ -537,18 +1018,24 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
 
             },
 
+            None if !self.allow_add || self.maybe_max_points.map_or(false, |max| self.env.len() >= max) => (),
+
             None => {
 
                 // Check if a there are no points. If there are
-                // and the mouse was clicked, add a point.
+                // and the mouse was double-clicked, add a point.
                 if self.env.len() == 0 {
                     match (state, new_state) {
                         (State::Clicked(elem, m_button), State::Highlighted(_)) => {
                             match (elem, m_button) {
-                                (Element::Pad, MouseButton::Left) => {
+                                (Element::Pad, MouseButton::Left) if mouse.clicks >= 2 => {
                                     let (new_x, new_y) = get_new_value(&perc_env, 0, mouse.pos[0], mouse.pos[1]);
                                     let new_point = EnvelopePoint::new(new_x, new_y);
                                     self.env.push(new_point);
+                                    match self.maybe_callback {
+                                        Some(ref mut callback) => callback(self.env, Event::PointAdded(0)),
+                                        None => (),
+                                    }
                                 }, _ => (),
                             }
                         }, _ => (),
@@ -556,11 +1043,11 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
                 }
 
                 else {
-                    // Check if a new point should be created.
+                    // Check if a new point should be created via double-click.
                     match (state, new_state) {
                         (State::Clicked(elem, m_button), State::Highlighted(_)) => {
                             match (elem, m_button) {
-                                (Element::Pad, MouseButton::Left) => {
+                                (Element::Pad, MouseButton::Left) if mouse.clicks >= 2 => {
                                     let (new_x, new_y) = {
                                         let mouse_x_on_pad = mouse.pos[0] - pad_pos[0];
                                         let mouse_y_on_pad = mouse.pos[1] - pad_pos[1];
@@ -568,14 +1055,24 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
                                         let mouse_y_clamped = clamp(mouse_y_on_pad, 0.0, pad_dim[1]);
                                         let new_x_perc = percentage(mouse_x_clamped, 0f64, pad_dim[0]);
                                         let new_y_perc = percentage(mouse_y_clamped, pad_dim[1], 0f64).powf(skew);
-                                        (map_range(new_x_perc, 0.0, 1.0, min_x, max_x),
+                                        (perc_to_x(new_x_perc, min_x, max_x, log_x),
                                          map_range(new_y_perc, 0.0, 1.0, min_y, max_y))
                                     };
+                                    // Insert the point after any others with an equal or lesser X,
+                                    // keeping it between locked endpoints.
+                                    let last = self.env.len() - 1;
+                                    let new_x = match lock_endpoints && last > 0 {
+                                        true => clamp(new_x, self.env[0].get_x(), self.env[last].get_x()),
+                                        false => new_x,
+                                    };
                                     let new_point = EnvelopePoint::new(new_x, new_y);
-                                    self.env.push(new_point);
-                                    self.env.sort_by(|a, b| if a.get_x() > b.get_x() { Ordering::Greater }
-                                                            else if a.get_x() < b.get_x() { Ordering::Less }
-                                                            else { Ordering::Equal });
+                                    let idx = self.env.iter().take_while(|pt| pt.get_x() <= new_x).count();
+                                    let idx = if lock_endpoints && last > 0 && idx > last { last } else { idx };
+                                    self.env.insert(idx, new_point);
+                                    match self.maybe_callback {
+                                        Some(ref mut callback) => callback(self.env, Event::PointAdded(idx)),
+                                        None => (),
+                                    }
                                 }, _ => (),
                             }
                         }, _ => (),
@@ -586,6 +1083,46 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
 
         }
 
+        // Copy or paste the envelope while the mouse is over the editor. Copied
+        // values are stored relative to this editor's ranges so that pasting
+        // scales them to the target's ranges.
+        if new_state != State::Normal {
+            let (min_x_f, max_x_f) = (min_x.to_f64().unwrap(), max_x.to_f64().unwrap());
+            let (min_y_f, max_y_f) = (min_y.to_f64().unwrap(), max_y.to_f64().unwrap());
+            if uic.command_key_pressed(Key::C) {
+                let points = self.env.iter().map(|pt| {
+                    (normalise(pt.get_x().to_f64().unwrap(), min_x_f, max_x_f),
+                     normalise(pt.get_y().to_f64().unwrap(), min_y_f, max_y_f),
+                     pt.get_curve())
+                }).collect();
+                uic.set_envelope_clipboard(points);
+            }
+            else if uic.command_key_pressed(Key::V) && !self.read_only {
+                // The paste is refused if it would break the editor's restrictions.
+                let restrictions = Restrictions {
+                    lock_endpoints: lock_endpoints,
+                    allow_add: self.allow_add,
+                    allow_remove: self.allow_remove,
+                    maybe_max_points: self.maybe_max_points,
+                };
+                let pasted: Option<Vec<E>> = uic.envelope_clipboard().and_then(|points| {
+                    paste_points(self.env, points, (min_x_f, max_x_f), (min_y_f, max_y_f), restrictions)
+                });
+                if let Some(pasted) = pasted {
+                    uic.damage(self.pos, self.dim);
+                    *self.env = pasted;
+                    match self.maybe_callback {
+                        Some(ref mut callback) => callback(self.env, Event::Pasted),
+                        None => (),
+                    }
+                }
+            }
+        }
+
+        if self.assert_sorted {
+            assert!(is_x_sorted(self.env), "EnvelopeEditor {} left its envelope unsorted.", self.ui_id);
+        }
+
         // Set the new state.
         set_state(uic, self.ui_id, Widget::EnvelopeEditor(new_state), self.pos, self.dim);
 
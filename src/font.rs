@@ -0,0 +1,28 @@
+use piston::quack::{ Pair, Set, SetAt };
+
+/// Identifies a font registered with the UiContext via `add_font`.
+pub type FontId = usize;
+
+/// The font that the UiContext was constructed with.
+pub const DEFAULT: FontId = 0;
+
+/// Font property.
+#[derive(Copy)]
+pub struct Font(pub FontId);
+
+/// A trait for widgets whose text may be drawn in a font other than the
+/// UiContext's current font, i.e. headings, monospace value readouts or
+/// icons.
+pub trait Fontable {
+    fn font(self, font: FontId) -> Self;
+}
+
+impl<T> Fontable for T
+    where
+        (Font, T): Pair<Data = Font, Object = T> + SetAt
+{
+    #[inline]
+    fn font(self, font: FontId) -> Self {
+        self.set(Font(font))
+    }
+}
@@ -0,0 +1,201 @@
+use backend::{
+    Button,
+    Input,
+    Key,
+    MouseButton,
+};
+use dimensions::Dimensions;
+use envelope_editor::EnvelopePoint;
+use headless::{
+    FixedGlyphs,
+    Harness,
+    NullTexture,
+};
+use layer::Recorder;
+use point::Point;
+use rand::{
+    Rng,
+    SeedableRng,
+    XorShiftRng,
+};
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use widget::Widget;
+
+/// The keys that will be randomly pressed during fuzzing.
+static KEYS: [Key; 6] = [Key::Backspace, Key::Left, Key::Right, Key::Return, Key::Home, Key::End];
+
+/// The modifier keys that will be randomly held during fuzzing, so that
+/// the keys above are also pressed in combination with them.
+static MODIFIERS: [Key; 4] = [Key::LCtrl, Key::LShift, Key::LAlt, Key::LGui];
+
+/// The text that will be randomly entered during fuzzing. This
+/// deliberately contains multi-byte characters.
+static TEXT: [&'static str; 5] = ["a", "Z", " ", "é", "日本"];
+
+/// Parameters for a fuzzing run.
+#[derive(Copy, Clone, Debug)]
+pub struct Settings {
+    /// The seed used to generate the event sequence. Re-using the
+    /// seed of a failing run reproduces the same sequence.
+    pub seed: [u32; 4],
+    /// The number of frames to run.
+    pub frames: usize,
+    /// The maximum number of events generated between each frame.
+    pub events_per_frame: usize,
+    /// The area within which mouse events are generated. This should
+    /// cover the widgets being fuzzed.
+    pub pos: Point,
+    pub dim: Dimensions,
+}
+
+impl Settings {
+    /// Default fuzzing settings for the given area.
+    pub fn new(pos: Point, dim: Dimensions) -> Settings {
+        Settings {
+            seed: [1, 2, 3, 4],
+            frames: 10_000,
+            events_per_frame: 4,
+            pos: pos,
+            dim: dim,
+        }
+    }
+}
+
+/// Generates random input sequences that could have been produced
+/// by a real user, i.e. buttons are never pressed twice without a
+/// release, modifiers are held across other presses and the mouse
+/// tends to move in small steps.
+pub struct EventGenerator {
+    rng: XorShiftRng,
+    pos: Point,
+    dim: Dimensions,
+    mouse_pos: Point,
+    left_down: bool,
+    right_down: bool,
+    maybe_held_key: Option<Key>,
+    held_modifiers: Vec<Key>,
+}
+
+impl EventGenerator {
+
+    /// Construct a new EventGenerator.
+    pub fn new(settings: &Settings) -> EventGenerator {
+        EventGenerator {
+            rng: SeedableRng::from_seed(settings.seed),
+            pos: settings.pos,
+            dim: settings.dim,
+            mouse_pos: settings.pos,
+            left_down: false,
+            right_down: false,
+            maybe_held_key: None,
+            held_modifiers: Vec::new(),
+        }
+    }
+
+    /// Return the next random Input.
+    pub fn next_input(&mut self) -> Input {
+        match self.rng.gen_range(0, 11) {
+            0 => {
+                self.left_down = !self.left_down;
+                let button = Button::Mouse(MouseButton::Left);
+                if self.left_down { Input::Press(button) } else { Input::Release(button) }
+            },
+            1 => {
+                self.right_down = !self.right_down;
+                let button = Button::Mouse(MouseButton::Right);
+                if self.right_down { Input::Press(button) } else { Input::Release(button) }
+            },
+            2 => match self.maybe_held_key.take() {
+                Some(key) => Input::Release(Button::Keyboard(key)),
+                None => {
+                    let key = *self.rng.choose(&KEYS).unwrap();
+                    self.maybe_held_key = Some(key);
+                    Input::Press(Button::Keyboard(key))
+                },
+            },
+            3 => Input::Text(self.rng.choose(&TEXT).unwrap().to_string()),
+            4 => {
+                let key = *self.rng.choose(&MODIFIERS).unwrap();
+                match self.held_modifiers.iter().position(|&held| held == key) {
+                    Some(idx) => {
+                        self.held_modifiers.remove(idx);
+                        Input::Release(Button::Keyboard(key))
+                    },
+                    None => {
+                        self.held_modifiers.push(key);
+                        Input::Press(Button::Keyboard(key))
+                    },
+                }
+            },
+            5 => {
+                // Jump anywhere within the area.
+                self.mouse_pos = [self.pos[0] + self.rng.gen_range(0.0, self.dim[0]),
+                                  self.pos[1] + self.rng.gen_range(0.0, self.dim[1])];
+                Input::Move(self.mouse_pos[0], self.mouse_pos[1])
+            },
+            _ => {
+                // Drag a little way, sometimes leaving the area.
+                let (dx, dy) = (self.rng.gen_range(-24.0, 24.0), self.rng.gen_range(-24.0, 24.0));
+                self.mouse_pos = [self.mouse_pos[0] + dx, self.mouse_pos[1] + dy];
+                Input::Move(self.mouse_pos[0], self.mouse_pos[1])
+            },
+        }
+    }
+
+}
+
+/// Feed random event sequences into the Harness, rendering a frame
+/// with `frame` after each frame's events. `frame` should draw the
+/// widgets being fuzzed and then check any invariants, returning an
+/// Err describing the first invariant that was broken.
+pub fn run<F>(harness: &mut Harness, settings: Settings, mut frame: F) -> Result<(), String>
+    where
+        F: FnMut(&mut UiContext<FixedGlyphs>, &mut Recorder<NullTexture>) -> Result<(), String>
+{
+    let mut generator = EventGenerator::new(&settings);
+    let mut log: Vec<Input> = Vec::new();
+    for i in 0..settings.frames {
+        let num_events = generator.rng.gen_range(0, settings.events_per_frame + 1);
+        for _ in 0..num_events {
+            let input = generator.next_input();
+            harness.input(input.clone());
+            log.push(input);
+        }
+        let mut result = Ok(());
+        harness.frame(|uic, graphics| result = frame(uic, graphics));
+        if let Err(e) = result {
+            let recent = if log.len() > 16 { &log[log.len() - 16..] } else { &log[..] };
+            return Err(format!("Invariant broken on frame {} with seed {:?}: {}\nRecent events: {:?}",
+                               i, settings.seed, e, recent));
+        }
+    }
+    Ok(())
+}
+
+/// Check that the envelope's points are sorted by their X value.
+pub fn check_envelope_sorted<E: EnvelopePoint>(env: &[E]) -> Result<(), String> {
+    for i in 1..env.len() {
+        if env[i - 1].get_x() > env[i].get_x() {
+            return Err(format!("Envelope is not x-sorted at index {} ({} > {}).",
+                               i, env[i - 1].get_x().to_string(), env[i].get_x().to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Check that the cursor of the TextBox with the given UIID (if it
+/// is captured) lies within the text's grapheme clusters.
+pub fn check_text_box<C>(uic: &UiContext<C>, ui_id: UIID, text: &str) -> Result<(), String> {
+    match uic.get_widget_state(ui_id) {
+        Widget::TextBox(state) => match state.cursor_idx() {
+            Some(idx) if idx > text.graphemes(true).count() =>
+                Err(format!("TextBox {} cursor index {} is out of bounds for {:?}.", ui_id, idx, text)),
+            _ => Ok(()),
+        },
+        Widget::NoWidget => Ok(()),
+        _ => Err(format!("UIID {} is not a TextBox.", ui_id)),
+    }
+}
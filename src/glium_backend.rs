@@ -0,0 +1,304 @@
+//! A Renderer and CharacterCache built on glium, enabled by the `glium_backend`
+//! feature, for applications that don't use piston's graphics.
+//!
+//! Create the `Resources` once along with the `GlyphCache` for the UiContext,
+//! which borrows a `freetype::Library` that the application keeps alive, then
+//! each frame wrap the glium Frame in a `GliumRenderer`, pass it to
+//! `UiContext::render_to` and `finish` it before finishing the Frame.
+
+use backend::Renderer;
+use clip::Scissor;
+use freetype;
+use glium::{ self, Display, DrawError, DrawParameters, Frame, Program, Surface, VertexBuffer };
+use glium::texture::Texture2d;
+use graphics::ImageSize;
+use graphics::character::{ Character, CharacterCache };
+use label::FontSize;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::error::Error;
+use std::path::Path;
+use std::rc::Rc;
+
+/// A glium texture shared between the GlyphCache and the batches that
+/// draw it.
+#[derive(Clone)]
+pub struct Texture(pub Rc<Texture2d>);
+
+impl Texture {
+    /// Construct a Texture from rows of RGBA pixels, top row first.
+    pub fn from_rows(display: &Display, rows: Vec<Vec<(u8, u8, u8, u8)>>) -> Texture {
+        Texture(Rc::new(Texture2d::new(display, rows)))
+    }
+
+    /// Whether both are the same glium texture.
+    fn is(&self, other: &Texture) -> bool {
+        &*self.0 as *const Texture2d == &*other.0 as *const Texture2d
+    }
+}
+
+impl ImageSize for Texture {
+    fn get_size(&self) -> (u32, u32) {
+        (self.0.get_width(), self.0.get_height().unwrap_or(1))
+    }
+}
+
+/// A vertex as uploaded to glium. Untextured triangles sample a white
+/// texture so that everything is drawn with the same program.
+#[derive(Copy, Clone, Debug)]
+pub struct Vertex {
+    pub position: [f32; 2],
+    pub color: [f32; 4],
+    pub tex_coords: [f32; 2],
+}
+
+implement_vertex!(Vertex, position, color, tex_coords);
+
+const VERTEX_SHADER: &'static str = "
+    #version 140
+    in vec2 position;
+    in vec4 color;
+    in vec2 tex_coords;
+    out vec4 v_color;
+    out vec2 v_tex_coords;
+    void main() {
+        gl_Position = vec4(position, 0.0, 1.0);
+        v_color = color;
+        v_tex_coords = tex_coords;
+    }
+";
+
+const FRAGMENT_SHADER: &'static str = "
+    #version 140
+    uniform sampler2D tex;
+    in vec4 v_color;
+    in vec2 v_tex_coords;
+    out vec4 f_color;
+    void main() {
+        f_color = v_color * texture(tex, v_tex_coords);
+    }
+";
+
+/// The program and textures shared by every frame's GliumRenderer.
+pub struct Resources {
+    program: Program,
+    white: Texture,
+}
+
+impl Resources {
+    /// Compile the program and create the white texture for the display.
+    pub fn new(display: &Display) -> Result<Resources, String> {
+        let program = match Program::from_source(display, VERTEX_SHADER, FRAGMENT_SHADER, None) {
+            Ok(program) => program,
+            Err(e) => return Err(format!("Failed to compile the glium program: {:?}", e)),
+        };
+        Ok(Resources {
+            program: program,
+            white: Texture::from_rows(display, vec![vec![(255, 255, 255, 255)]]),
+        })
+    }
+}
+
+/// Draws to a glium Frame. Consecutive triangles that share a texture and
+/// scissor rectangle are collected into a single vertex buffer, so all of
+/// the untextured widget graphics between two glyphs are a single draw call.
+/// The last batch is drawn by `finish`, which returns the first error that
+/// occurred while drawing (if any), or otherwise when the renderer is dropped.
+pub struct GliumRenderer<'a> {
+    display: &'a Display,
+    frame: &'a mut Frame,
+    resources: &'a Resources,
+    vertices: Vec<Vertex>,
+    maybe_texture: Option<Texture>,
+    maybe_scissor: Option<Scissor>,
+    maybe_error: Option<DrawError>,
+}
+
+impl<'a> GliumRenderer<'a> {
+
+    /// Construct a GliumRenderer drawing to the frame.
+    pub fn new(display: &'a Display, frame: &'a mut Frame, resources: &'a Resources) -> GliumRenderer<'a> {
+        GliumRenderer {
+            display: display,
+            frame: frame,
+            resources: resources,
+            vertices: Vec::new(),
+            maybe_texture: None,
+            maybe_scissor: None,
+            maybe_error: None,
+        }
+    }
+
+    /// Draw the last batch and return the first error that occurred while
+    /// drawing (if any).
+    pub fn finish(mut self) -> Result<(), DrawError> {
+        self.flush_and_keep_error();
+        match self.maybe_error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Draw the current batch (if any).
+    pub fn flush(&mut self) -> Result<(), DrawError> {
+        if self.vertices.is_empty() { return Ok(()) }
+        let vertices = ::std::mem::replace(&mut self.vertices, Vec::new());
+        let vertex_buffer = VertexBuffer::new(self.display, vertices);
+        let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+        let texture = match self.maybe_texture {
+            Some(ref texture) => texture.clone(),
+            None => self.resources.white.clone(),
+        };
+        let uniforms = uniform! { tex: &*texture.0 };
+        let params = DrawParameters {
+            blending_function: Some(glium::BlendingFunction::Addition {
+                source: glium::LinearBlendingFactor::SourceAlpha,
+                destination: glium::LinearBlendingFactor::OneMinusSourceAlpha,
+            }),
+            scissor: self.maybe_scissor.map(|s| glium::Rect {
+                left: s[0] as u32,
+                bottom: s[1] as u32,
+                width: s[2] as u32,
+                height: s[3] as u32,
+            }),
+            .. ::std::default::Default::default()
+        };
+        self.frame.draw(&vertex_buffer, &indices, &self.resources.program, &uniforms, &params)
+    }
+
+    /// Draw the current batch, keeping the error (if any) to be returned
+    /// by `finish` unless an earlier one was kept.
+    fn flush_and_keep_error(&mut self) {
+        if let Err(e) = self.flush() {
+            if self.maybe_error.is_none() { self.maybe_error = Some(e) }
+        }
+    }
+
+    /// Begin a new batch unless the current one has the same texture and
+    /// scissor rectangle.
+    fn batch(&mut self, maybe_texture: Option<&Texture>, maybe_scissor: Option<Scissor>) {
+        let same_texture = match (&self.maybe_texture, maybe_texture) {
+            (&Some(ref a), Some(b)) => a.is(b),
+            (&None, None) => true,
+            _ => false,
+        };
+        if !same_texture || self.maybe_scissor != maybe_scissor {
+            self.flush_and_keep_error();
+            self.maybe_texture = maybe_texture.cloned();
+            self.maybe_scissor = maybe_scissor;
+        }
+    }
+
+}
+
+impl<'a> Drop for GliumRenderer<'a> {
+    fn drop(&mut self) {
+        self.flush_and_keep_error();
+    }
+}
+
+impl<'a> Renderer for GliumRenderer<'a> {
+    type Texture = Texture;
+
+    fn clear(&mut self, color: [f32; 4]) {
+        self.vertices.clear();
+        self.frame.clear_color(color[0], color[1], color[2], color[3]);
+    }
+
+    fn triangles(&mut self, maybe_scissor: Option<Scissor>, color: [f32; 4], vertices: &[f32]) {
+        self.batch(None, maybe_scissor);
+        self.vertices.extend(vertices.chunks(2).map(|xy| Vertex {
+            position: [xy[0], xy[1]],
+            color: color,
+            tex_coords: [0.5, 0.5],
+        }));
+    }
+
+    fn textured_triangles(&mut self,
+                          maybe_scissor: Option<Scissor>,
+                          color: [f32; 4],
+                          texture: &Texture,
+                          vertices: &[f32],
+                          uvs: &[f32]) {
+        self.batch(Some(texture), maybe_scissor);
+        self.vertices.extend(vertices.chunks(2).zip(uvs.chunks(2)).map(|(xy, uv)| Vertex {
+            position: [xy[0], xy[1]],
+            color: color,
+            tex_coords: [uv[0], uv[1]],
+        }));
+    }
+}
+
+/// A CharacterCache that rasterizes glyphs with FreeType into glium
+/// textures, each of which is created the first time its character is drawn
+/// or measured at a size. The face borrows the FreeType library, which is
+/// owned by the caller so that it outlives every GlyphCache loaded from it,
+/// i.e. `let freetype = freetype::Library::init().unwrap();`.
+pub struct GlyphCache<'a> {
+    display: Display,
+    face: freetype::Face<'a>,
+    characters: HashMap<(FontSize, char), Character<Texture>>,
+}
+
+impl<'a> GlyphCache<'a> {
+
+    /// Load the font at the given path with the given FreeType library.
+    pub fn new(freetype: &'a freetype::Library, font: &Path, display: &Display)
+        -> Result<GlyphCache<'a>, String>
+    {
+        let face = match freetype.new_face(font, 0) {
+            Ok(face) => face,
+            Err(e) => return Err(format!("Failed to load font: {}", Error::description(&e))),
+        };
+        Ok(GlyphCache {
+            display: display.clone(),
+            face: face,
+            characters: HashMap::new(),
+        })
+    }
+
+}
+
+/// Rasterize the character, or return None if the font can't render it.
+fn rasterize(display: &Display, face: &mut freetype::Face, size: FontSize, ch: char)
+    -> Option<Character<Texture>>
+{
+    if face.set_pixel_sizes(0, size).is_err() { return None }
+    if face.load_char(ch as usize, freetype::face::RENDER).is_err() { return None }
+    let glyph = face.glyph();
+    let bitmap = glyph.bitmap();
+    let (w, h) = (bitmap.width() as usize, bitmap.rows() as usize);
+    let buffer = bitmap.buffer();
+    // Glium can't create an empty texture, so blank glyphs such as spaces
+    // get a single transparent pixel.
+    let rows = if w == 0 || h == 0 {
+        vec![vec![(255, 255, 255, 0)]]
+    } else {
+        (0..h).map(|y| (0..w).map(|x| (255, 255, 255, buffer[y * w + x])).collect()).collect()
+    };
+    let advance = glyph.advance();
+    Some(Character {
+        offset: [glyph.bitmap_left() as f64, glyph.bitmap_top() as f64],
+        size: [(advance.x >> 6) as f64, (advance.y >> 6) as f64],
+        texture: Texture::from_rows(display, rows),
+    })
+}
+
+impl<'a> CharacterCache for GlyphCache<'a> {
+    type Texture = Texture;
+
+    fn character(&mut self, size: FontSize, ch: char) -> &Character<Texture> {
+        let GlyphCache { ref display, ref mut face, ref mut characters } = *self;
+        match characters.entry((size, ch)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let character = rasterize(display, face, size, ch).unwrap_or_else(|| Character {
+                    offset: [0.0, 0.0],
+                    size: [0.0, 0.0],
+                    texture: Texture::from_rows(display, vec![vec![(255, 255, 255, 0)]]),
+                });
+                entry.insert(character)
+            },
+        }
+    }
+}
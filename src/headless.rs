@@ -0,0 +1,367 @@
+//! Run a UiContext without a window or graphics context, so that widget
+//! logic can be driven by synthetic input and checked in automated tests.
+
+use backend::{ Button, Input, Key, MouseButton };
+use graphics::{ DrawState, Graphics, ImageSize };
+use graphics::character::{ Character, CharacterCache };
+use label::FontSize;
+use layer::Recorder;
+use point::Point;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use theme::Theme;
+use ui_context::{ UIID, UiContext };
+use widget::Widget;
+
+/// The width of every glyph as a fraction of the font size.
+pub const GLYPH_WIDTH: f64 = 0.5;
+
+/// A texture that holds nothing.
+#[derive(Copy, Clone, Debug)]
+pub struct NullTexture;
+
+impl ImageSize for NullTexture {
+    fn get_size(&self) -> (u32, u32) { (1, 1) }
+}
+
+/// A graphics backend that draws nothing.
+#[derive(Copy, Clone, Debug)]
+pub struct NullGraphics;
+
+impl Graphics for NullGraphics {
+    type Texture = NullTexture;
+
+    fn clear(&mut self, _color: [f32; 4]) {}
+
+    fn tri_list<F>(&mut self, _draw_state: &DrawState, _color: &[f32; 4], mut f: F)
+        where
+            F: FnMut(&mut FnMut(&[f32]))
+    {
+        f(&mut |_: &[f32]| {});
+    }
+
+    fn tri_list_uv<F>(&mut self, _draw_state: &DrawState, _color: &[f32; 4], _texture: &NullTexture, mut f: F)
+        where
+            F: FnMut(&mut FnMut(&[f32], &[f32]))
+    {
+        f(&mut |_: &[f32], _: &[f32]| {});
+    }
+}
+
+/// A CharacterCache in which every character is `GLYPH_WIDTH` times the
+/// font size wide, so that text layout is the same on every machine.
+pub struct FixedGlyphs {
+    characters: HashMap<FontSize, Character<NullTexture>>,
+}
+
+impl FixedGlyphs {
+    /// Construct a FixedGlyphs cache.
+    pub fn new() -> FixedGlyphs {
+        FixedGlyphs { characters: HashMap::new() }
+    }
+}
+
+impl CharacterCache for FixedGlyphs {
+    type Texture = NullTexture;
+
+    fn character(&mut self, size: FontSize, _ch: char) -> &Character<NullTexture> {
+        match self.characters.entry(size) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Character {
+                offset: [0.0, size as f64],
+                size: [size as f64 * GLYPH_WIDTH, size as f64],
+                texture: NullTexture,
+            }),
+        }
+    }
+}
+
+/// Records the values passed to a callback, i.e.
+/// `.callback(|value| calls.push(value))`, so that they can be checked
+/// once the frame has been drawn.
+pub struct Calls<T> {
+    values: RefCell<Vec<T>>,
+}
+
+impl<T> Calls<T> {
+
+    /// Construct an empty record of calls.
+    pub fn new() -> Calls<T> {
+        Calls { values: RefCell::new(Vec::new()) }
+    }
+
+    /// Record a call with the value.
+    pub fn push(&self, value: T) {
+        self.values.borrow_mut().push(value);
+    }
+
+    /// The number of calls recorded since they were last taken.
+    pub fn len(&self) -> usize {
+        self.values.borrow().len()
+    }
+
+    /// Take the values of the calls recorded so far.
+    pub fn take(&self) -> Vec<T> {
+        ::std::mem::replace(&mut *self.values.borrow_mut(), Vec::new())
+    }
+
+}
+
+/// A headless UiContext along with helpers for feeding it input. Input is
+/// given in UI coordinates (from the top left of the window) and is seen by
+/// widgets during the following `frame`.
+pub struct Harness {
+    pub uic: UiContext<FixedGlyphs>,
+}
+
+impl Harness {
+
+    /// Construct a Harness with a window of the given dimensions.
+    pub fn new(width: u32, height: u32) -> Harness {
+        let mut uic = UiContext::new(FixedGlyphs::new(), Theme::default());
+        uic.handle_input(Input::Resize(width, height));
+        Harness { uic: uic }
+    }
+
+    /// Draw a frame, as `UiContext::render`.
+    pub fn frame<F>(&mut self, draw: F)
+        where
+            F: FnOnce(&mut UiContext<FixedGlyphs>, &mut Recorder<NullTexture>)
+    {
+        self.uic.render(&mut NullGraphics, draw);
+    }
+
+    /// Pass the input to the UiContext.
+    pub fn input(&mut self, input: Input) {
+        self.uic.handle_input(input);
+    }
+
+    /// Move the mouse to the position.
+    pub fn move_mouse(&mut self, pos: Point) {
+        let scale = self.uic.scale_factor();
+        self.input(Input::Move(pos[0] * scale, pos[1] * scale));
+    }
+
+    /// Press the mouse button.
+    pub fn press_mouse(&mut self, button: MouseButton) {
+        self.input(Input::Press(Button::Mouse(button)));
+    }
+
+    /// Release the mouse button.
+    pub fn release_mouse(&mut self, button: MouseButton) {
+        self.input(Input::Release(Button::Mouse(button)));
+    }
+
+    /// Click the left mouse button at the position, drawing a frame with
+    /// the mouse over the position, another with the button down and
+    /// another once it's released.
+    pub fn click<F>(&mut self, pos: Point, mut draw: F)
+        where
+            F: FnMut(&mut UiContext<FixedGlyphs>, &mut Recorder<NullTexture>)
+    {
+        self.move_mouse(pos);
+        self.frame(|uic, graphics| draw(uic, graphics));
+        self.press_mouse(MouseButton::Left);
+        self.frame(|uic, graphics| draw(uic, graphics));
+        self.release_mouse(MouseButton::Left);
+        self.frame(|uic, graphics| draw(uic, graphics));
+    }
+
+    /// Drag with the left mouse button from `start` to `end` over the given
+    /// number of steps, drawing a frame after each step.
+    pub fn drag<F>(&mut self, start: Point, end: Point, steps: usize, mut draw: F)
+        where
+            F: FnMut(&mut UiContext<FixedGlyphs>, &mut Recorder<NullTexture>)
+    {
+        self.move_mouse(start);
+        self.frame(|uic, graphics| draw(uic, graphics));
+        self.press_mouse(MouseButton::Left);
+        self.frame(|uic, graphics| draw(uic, graphics));
+        let steps = ::std::cmp::max(steps, 1);
+        for i in 1..steps + 1 {
+            let t = i as f64 / steps as f64;
+            self.move_mouse([start[0] + (end[0] - start[0]) * t, start[1] + (end[1] - start[1]) * t]);
+            self.frame(|uic, graphics| draw(uic, graphics));
+        }
+        self.release_mouse(MouseButton::Left);
+        self.frame(|uic, graphics| draw(uic, graphics));
+    }
+
+    /// Press and release the key.
+    pub fn press_key(&mut self, key: Key) {
+        self.input(Input::Press(Button::Keyboard(key)));
+        self.input(Input::Release(Button::Keyboard(key)));
+    }
+
+    /// Enter the text.
+    pub fn type_text(&mut self, text: &str) {
+        self.input(Input::Text(text.to_string()));
+    }
+
+    /// The state of the widget with the given UIID.
+    pub fn widget(&self, ui_id: UIID) -> Widget {
+        self.uic.get_widget_state(ui_id)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use backend::{ Button as InputButton, Input, Key };
+    use button::{ Button, State as ButtonState };
+    use callback::Callable;
+    use draw::Drawable;
+    use fuzz::{ self, Settings };
+    use layer::Recorder;
+    use link::LinkMode;
+    use position::Positionable;
+    use shape::Shapeable;
+    use slider::Slider;
+    use text_box::TextBox;
+    use ui_context::UiContext;
+    use visibility::Hideable;
+    use widget::Widget;
+    use xy_pad::XYPad;
+    use super::{ Calls, FixedGlyphs, Harness, NullTexture };
+
+    #[test]
+    fn clicking_a_button_calls_its_callback() {
+        let mut harness = Harness::new(400, 300);
+        let calls = Calls::new();
+        harness.click([60.0, 30.0], |uic, graphics| {
+            Button::new(0)
+                .dimensions(100.0, 40.0)
+                .position(10.0, 10.0)
+                .callback(|| calls.push(()))
+                .draw(uic, graphics);
+        });
+        assert_eq!(calls.len(), 1);
+    }
+
+    #[test]
+    fn dragging_a_slider_calls_its_callback_with_the_new_value() {
+        let mut harness = Harness::new(400, 300);
+        let calls = Calls::new();
+        harness.drag([15.0, 30.0], [300.0, 30.0], 4, |uic, graphics| {
+            Slider::new(0, 0.0f32, 0.0, 1.0)
+                .dimensions(200.0, 40.0)
+                .position(10.0, 10.0)
+                .callback(|value| calls.push(value))
+                .draw(uic, graphics);
+        });
+        let values = calls.take();
+        assert!(values.len() > 1);
+        assert_eq!(*values.last().unwrap(), 1.0);
+    }
+
+    #[test]
+    fn typing_into_a_text_box_calls_its_callback_on_return() {
+        let mut harness = Harness::new(400, 300);
+        let calls = Calls::new();
+        let mut text = String::new();
+        {
+            let mut draw = |uic: &mut UiContext<FixedGlyphs>, graphics: &mut Recorder<NullTexture>| {
+                TextBox::new(0, &mut text)
+                    .dimensions(200.0, 40.0)
+                    .position(10.0, 10.0)
+                    .callback(|text: &mut String| calls.push(text.clone()))
+                    .draw(uic, graphics);
+            };
+            harness.click([50.0, 30.0], &mut draw);
+            harness.type_text("hi");
+            harness.frame(|uic, graphics| draw(uic, graphics));
+            harness.press_key(Key::Return);
+            harness.frame(|uic, graphics| draw(uic, graphics));
+        }
+        assert_eq!(text, "hi");
+        assert_eq!(calls.take(), vec!["hi".to_string()]);
+    }
+
+    #[test]
+    fn ctrl_clicking_an_xy_pad_resets_it_to_its_default_within_range() {
+        let mut harness = Harness::new(400, 300);
+        let calls = Calls::new();
+        harness.input(Input::Press(InputButton::Keyboard(Key::LCtrl)));
+        harness.click([60.0, 60.0], |uic, graphics| {
+            XYPad::new(0, 0.5f64, 0.0, 1.0, 0.5f64, 0.0, 1.0)
+                .dimensions(100.0, 100.0)
+                .position(10.0, 10.0)
+                .default_value(2.0, -1.0)
+                .callback(|x, y| calls.push((x, y)))
+                .draw(uic, graphics);
+        });
+        assert_eq!(calls.take().first(), Some(&(1.0, 0.0)));
+    }
+
+    #[test]
+    fn hiding_a_widget_keeps_its_state_beyond_the_state_lifetime() {
+        let mut harness = Harness::new(400, 300);
+        harness.uic.state_lifetime = Some(2);
+        harness.move_mouse([60.0, 30.0]);
+        harness.frame(|uic, graphics| {
+            Button::new(0)
+                .dimensions(100.0, 40.0)
+                .position(10.0, 10.0)
+                .callback(|| ())
+                .draw(uic, graphics);
+        });
+        for _ in 0..5 {
+            harness.frame(|uic, graphics| {
+                Button::new(0)
+                    .dimensions(100.0, 40.0)
+                    .position(10.0, 10.0)
+                    .callback(|| ())
+                    .hide()
+                    .draw(uic, graphics);
+            });
+        }
+        assert!(harness.widget(0) == Widget::Button(ButtonState::Highlighted));
+    }
+
+    #[test]
+    fn fuzzing_a_text_box_keeps_its_cursor_within_the_text() {
+        let mut harness = Harness::new(400, 300);
+        let mut settings = Settings::new([0.0, 0.0], [220.0, 60.0]);
+        settings.seed = [7, 11, 13, 17];
+        settings.frames = 500;
+        let mut text = String::new();
+        let result = fuzz::run(&mut harness, settings, |uic, graphics| {
+            TextBox::new(0, &mut text)
+                .dimensions(200.0, 40.0)
+                .position(10.0, 10.0)
+                .callback(|_: &mut String| {})
+                .draw(uic, graphics);
+            fuzz::check_text_box(uic, 0, &text)
+        });
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn dragging_a_relatively_linked_slider_moves_the_other_without_feedback() {
+        let mut harness = Harness::new(400, 300);
+        harness.uic.links().link(&[0, 1], LinkMode::Relative);
+        let (mut a, mut b) = (0.0f32, 0.0f32);
+        {
+            let mut draw = |uic: &mut UiContext<FixedGlyphs>, graphics: &mut Recorder<NullTexture>| {
+                Slider::new(0, a, 0.0, 1.0)
+                    .dimensions(200.0, 40.0)
+                    .position(10.0, 10.0)
+                    .callback(|value| a = value)
+                    .draw(uic, graphics);
+                Slider::new(1, b, 0.0, 1.0)
+                    .dimensions(200.0, 40.0)
+                    .position(10.0, 60.0)
+                    .callback(|value| b = value)
+                    .draw(uic, graphics);
+            };
+            harness.drag([15.0, 30.0], [300.0, 30.0], 4, &mut draw);
+            for _ in 0..3 {
+                harness.frame(|uic, graphics| draw(uic, graphics));
+            }
+        }
+        assert_eq!(a, 1.0);
+        assert!((a - b).abs() < 1.0e-4);
+    }
+
+}
@@ -0,0 +1,99 @@
+use ui_context::UIID;
+
+/// A single change made by the user to a value widget.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Change {
+    pub ui_id: UIID,
+    pub old: f64,
+    pub new: f64,
+    /// The time at which the change was made in seconds.
+    pub time: f64,
+    /// Whether or not the change is complete (i.e. the drag has ended).
+    finished: bool,
+}
+
+/// An optional, application-wide record of the changes the user makes to
+/// value widgets, allowing individual changes to be reviewed and reverted.
+#[derive(Clone, Debug)]
+pub struct History {
+    /// Whether or not changes are recorded.
+    pub enabled: bool,
+    /// The number of changes kept before the oldest are discarded.
+    pub max_len: usize,
+    changes: Vec<Change>,
+    reverts: Vec<(UIID, f64)>,
+    /// The row of the history panel that the mouse was pressed over.
+    pub maybe_pressed_row: Option<usize>,
+}
+
+impl History {
+
+    /// Construct an empty, disabled History.
+    pub fn new() -> History {
+        History {
+            enabled: false,
+            max_len: 100,
+            changes: Vec::new(),
+            reverts: Vec::new(),
+            maybe_pressed_row: None,
+        }
+    }
+
+    /// The recorded changes from oldest to newest.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Record a change to the given widget's value. Successive changes to
+    /// the same widget are merged into one until `finish` is called.
+    pub fn record(&mut self, ui_id: UIID, old: f64, new: f64, time: f64) {
+        if !self.enabled { return }
+        if let Some(last) = self.changes.last_mut() {
+            if last.ui_id == ui_id && !last.finished {
+                last.new = new;
+                last.time = time;
+                return;
+            }
+        }
+        self.changes.push(Change { ui_id: ui_id, old: old, new: new, time: time, finished: false });
+        while self.changes.len() > self.max_len {
+            self.changes.remove(0);
+        }
+    }
+
+    /// Mark the current change to the given widget as complete.
+    pub fn finish(&mut self, ui_id: UIID) {
+        for change in self.changes.iter_mut().filter(|change| change.ui_id == ui_id) {
+            change.finished = true;
+        }
+    }
+
+    /// Revert the change at the given index, restoring the widget's old value
+    /// the next time it is drawn. The revert is itself recorded as a change.
+    pub fn revert(&mut self, idx: usize, time: f64) {
+        let change = match self.changes.get(idx) {
+            Some(&change) => change,
+            None => return,
+        };
+        self.finish(change.ui_id);
+        self.reverts.retain(|&(id, _)| id != change.ui_id);
+        self.reverts.push((change.ui_id, change.old));
+        self.record(change.ui_id, change.new, change.old, time);
+        self.finish(change.ui_id);
+    }
+
+    /// Take the value that the given widget should be reverted to (if any).
+    pub fn take_revert(&mut self, ui_id: UIID) -> Option<f64> {
+        match self.reverts.iter().position(|&(id, _)| id == ui_id) {
+            Some(idx) => Some(self.reverts.remove(idx).1),
+            None => None,
+        }
+    }
+
+    /// Forget all recorded changes.
+    pub fn clear(&mut self) {
+        self.changes.clear();
+        self.reverts.clear();
+    }
+
+}
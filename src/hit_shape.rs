@@ -0,0 +1,50 @@
+use dimensions::Dimensions;
+use point::Point;
+use rectangle;
+
+/// The shape used to determine whether or not the mouse is over a
+/// widget. Shapes are fitted to the widget's rectangle.
+#[derive(Clone, Debug)]
+pub enum HitShape {
+    /// The widget's full rectangle (the default).
+    Rectangle,
+    /// The ellipse inscribed within the widget's rectangle. This is a
+    /// circle for square widgets.
+    Ellipse,
+    /// A polygon whose points are relative to the widget's position.
+    Polygon(Vec<Point>),
+}
+
+impl HitShape {
+
+    /// Return whether or not the point `p` lies within the shape for a
+    /// widget at the given position with the given dimensions.
+    pub fn is_over(&self, pos: Point, dim: Dimensions, p: Point) -> bool {
+        match *self {
+            HitShape::Rectangle => rectangle::is_over(pos, p, dim),
+            HitShape::Ellipse => {
+                let (rx, ry) = (dim[0] / 2.0, dim[1] / 2.0);
+                if rx <= 0.0 || ry <= 0.0 { return false }
+                let dx = (p[0] - (pos[0] + rx)) / rx;
+                let dy = (p[1] - (pos[1] + ry)) / ry;
+                dx * dx + dy * dy < 1.0
+            },
+            HitShape::Polygon(ref points) => {
+                // Even-odd rule ray casting.
+                let (x, y) = (p[0] - pos[0], p[1] - pos[1]);
+                let mut is_inside = false;
+                let mut j = points.len().wrapping_sub(1);
+                for i in 0..points.len() {
+                    let (a, b) = (points[i], points[j]);
+                    if (a[1] > y) != (b[1] > y)
+                    && x < (b[0] - a[0]) * (y - a[1]) / (b[1] - a[1]) + a[0] {
+                        is_inside = !is_inside;
+                    }
+                    j = i;
+                }
+                is_inside
+            },
+        }
+    }
+
+}
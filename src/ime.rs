@@ -0,0 +1,87 @@
+use dimensions::Dimensions;
+use point::Point;
+
+/// The state of an input method (IME) composition. Piston doesn't report
+/// compositions, so the backend feeds them in via `set_preedit` and
+/// `UiContext::commit_composition`. While composing, the preedit text is
+/// displayed at the cursor of the captured TextBox.
+#[derive(Clone, Debug)]
+pub struct Ime {
+    preedit: String,
+    /// The grapheme index of the cursor within the preedit text.
+    cursor: usize,
+    /// Whether a composition was committed since input was last flushed.
+    committed: bool,
+    maybe_cursor_area: Option<(Point, Dimensions)>,
+}
+
+impl Ime {
+
+    /// Construct an Ime with no composition in progress.
+    pub fn new() -> Ime {
+        Ime {
+            preedit: String::new(),
+            cursor: 0,
+            committed: false,
+            maybe_cursor_area: None,
+        }
+    }
+
+    /// Update the composition's preedit text and the cursor's grapheme
+    /// index within it. Empty text cancels the composition.
+    pub fn set_preedit(&mut self, text: &str, cursor: usize) {
+        self.preedit = text.to_string();
+        self.cursor = cursor;
+    }
+
+    /// End the composition without committing it.
+    pub fn cancel(&mut self) {
+        self.preedit.clear();
+        self.cursor = 0;
+    }
+
+    /// Whether or not a composition is in progress.
+    pub fn is_composing(&self) -> bool {
+        self.preedit.len() > 0
+    }
+
+    /// The preedit text and cursor index, if composing.
+    pub fn preedit(&self) -> Option<(&str, usize)> {
+        match self.is_composing() {
+            true => Some((&self.preedit, self.cursor)),
+            false => None,
+        }
+    }
+
+    /// Whether a composition was committed since input was last flushed.
+    /// The key confirming the composition (i.e. Return) should be ignored
+    /// by text widgets if so.
+    pub fn was_committed(&self) -> bool {
+        self.committed
+    }
+
+    /// Set the area of the text cursor that is receiving the composition.
+    pub fn set_cursor_area(&mut self, pos: Point, dim: Dimensions) {
+        self.maybe_cursor_area = Some((pos, dim));
+    }
+
+    /// The area of the text cursor receiving the composition (if any), in
+    /// UI coordinates. Backends should place the IME's candidate window
+    /// beneath it (multiplying by `UiContext::scale_factor` for pixels).
+    pub fn cursor_area(&self) -> Option<(Point, Dimensions)> {
+        self.maybe_cursor_area
+    }
+
+    /// Called by `UiContext::commit_composition`.
+    pub fn commit(&mut self) {
+        self.cancel();
+        self.committed = true;
+    }
+
+    /// Called when input is flushed at the start of each frame.
+    pub fn flush(&mut self) {
+        self.committed = false;
+        self.maybe_cursor_area = None;
+    }
+
+}
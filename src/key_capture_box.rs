@@ -0,0 +1,236 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use keyboard::{ self, KeyCombo, ModifierKeys };
+use mouse::Mouse;
+use piston::input::keyboard::Key;
+use point::Point;
+use rectangle;
+use ui_context::{
+    Id,
+    UIID,
+    UiContext,
+};
+use widget::{ DefaultWidgetState, Widget };
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use Callback;
+use FrameColor;
+use FrameWidth;
+use LabelColor;
+use LabelFontSize;
+use Position;
+use Size;
+use Visible;
+
+/// Represents the state of the KeyCaptureBox widget.
+#[derive(PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted,
+    Clicked,
+    /// Waiting for the user to press a key chord.
+    Capturing,
+}
+
+impl State {
+    /// Return the associated Rectangle state.
+    fn as_rectangle_state(&self) -> rectangle::State {
+        match self {
+            &State::Normal => rectangle::State::Normal,
+            &State::Highlighted => rectangle::State::Highlighted,
+            &State::Clicked | &State::Capturing => rectangle::State::Clicked,
+        }
+    }
+}
+
+widget_fns!(KeyCaptureBox, State, Widget::KeyCaptureBox(State::Normal));
+
+/// Check the current state of the box, ignoring keys.
+fn get_new_state(is_over: bool,
+                 prev: State,
+                 mouse: Mouse) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Clicked, Capturing};
+    match (is_over, prev, mouse.left) {
+        (_,     Capturing, Up)   => Capturing,
+        (true,  Capturing, Down) => Capturing,
+        (true,  Clicked,   Up)   => Capturing,
+        (true,  Normal,    Down) => Normal,
+        (true,  _,         Down) => Clicked,
+        (true,  _,         Up)   => Highlighted,
+        (false, Clicked,   Down) => Clicked,
+        _                        => Normal,
+    }
+}
+
+/// Whether or not the given key is a modifier, which alone doesn't
+/// complete a chord.
+fn is_modifier(key: Key) -> bool {
+    ModifierKeys::from_key(key) != keyboard::NO_MODIFIER
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct KeyCaptureBox<'a, F> {
+    ui_id: UIID,
+    maybe_combo: Option<KeyCombo>,
+    pos: Point,
+    visible: bool,
+    dim: Dimensions,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+    maybe_callback: Option<F>,
+}
+
+impl<'a, F> KeyCaptureBox<'a, F> {
+
+    /// Create a key capture box context displaying the currently bound
+    /// combo (if any).
+    pub fn new(ui_id: UIID, maybe_combo: Option<KeyCombo>) -> KeyCaptureBox<'a, F> {
+        KeyCaptureBox {
+            ui_id: ui_id,
+            maybe_combo: maybe_combo,
+            pos: [0.0, 0.0],
+            visible: true,
+            dim: [192.0, 48.0],
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+            maybe_callback: None,
+        }
+    }
+
+}
+
+quack! {
+    kcb: KeyCaptureBox['a, F]
+    get:
+        fn () -> Size [] { Size(kcb.dim) }
+        fn () -> Position [] { Position(kcb.pos) }
+        fn () -> Visible [] { Visible(kcb.visible) }
+        fn () -> DefaultWidgetState [] {
+            DefaultWidgetState(Widget::KeyCaptureBox(State::Normal))
+        }
+        fn () -> Id [] { Id(kcb.ui_id) }
+    set:
+        fn (val: Color) [] { kcb.maybe_color = Some(val) }
+        fn (val: Callback<F>) [where F: FnMut(KeyCombo) + 'a] {
+            kcb.maybe_callback = Some(val.0)
+        }
+        fn (val: FrameColor) [] { kcb.maybe_frame_color = Some(val.0) }
+        fn (val: FrameWidth) [] { kcb.maybe_frame = Some(val.0) }
+        fn (val: LabelColor) [] { kcb.maybe_label_color = Some(val.0) }
+        fn (val: LabelFontSize) [] { kcb.maybe_label_font_size = Some(val.0) }
+        fn (val: Position) [] { kcb.pos = val.0 }
+        fn (val: Visible) [] { kcb.visible = val.0 }
+        fn (val: Size) [] { kcb.dim = val.0 }
+    action:
+}
+
+impl<'a, F> KeyCaptureBox<'a, F>
+    where
+        F: FnMut(KeyCombo) + 'a
+{
+
+    /// Update the box's state, calling the callback (if any) and drawing it.
+    /// Return the captured combo once a chord has been pressed.
+    fn update<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B) -> Option<KeyCombo>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if !self.visible { uic.keep_state(self.ui_id); return None }
+        let state = *get_state(uic, self.ui_id);
+        let mouse = uic.get_mouse_state();
+        let is_over = uic.is_over(self.ui_id, self.pos, self.dim, mouse.pos);
+        let new_state = get_new_state(is_over, state, mouse);
+        let modifiers = uic.get_modifier_keys();
+        let platform = uic.conventions().platform;
+
+        // While capturing, the first non-modifier key completes the chord.
+        // Escape cancels the capture.
+        let mut maybe_captured = None;
+        let new_state = match new_state {
+            State::Capturing => {
+                let pressed_keys = uic.get_pressed_keys();
+                match pressed_keys.iter().find(|&&key| !is_modifier(key)) {
+                    Some(&Key::Escape) => State::Normal,
+                    Some(&key) => {
+                        maybe_captured = Some(KeyCombo::new(key, modifiers));
+                        State::Normal
+                    },
+                    None => State::Capturing,
+                }
+            },
+            _ => new_state,
+        };
+        if let Some(combo) = maybe_captured {
+            self.maybe_combo = Some(combo);
+            match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(combo), None => (),
+            }
+        }
+
+        // Draw. While capturing, the held modifiers are displayed live.
+        let text = match new_state {
+            State::Capturing => format!("{}...", keyboard::modifiers_to_string(modifiers, platform)),
+            _ => match self.maybe_combo {
+                Some(combo) => combo.describe(platform),
+                None => "None".to_string(),
+            },
+        };
+        let rect_state = new_state.as_rectangle_state();
+        let color = uic.flash_color(self.ui_id, self.maybe_color.unwrap_or(uic.theme.shape_color));
+        let frame_w = self.maybe_frame.unwrap_or(uic.theme.frame_width);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(uic.theme.frame_color))),
+            false => None,
+        };
+        let text_color = self.maybe_label_color.unwrap_or(uic.theme.label_color);
+        let size = self.maybe_label_font_size.unwrap_or(uic.theme.font_size_medium);
+        rectangle::draw_with_centered_label(
+            uic.win_w, uic.win_h, graphics, uic, rect_state,
+            self.pos, self.dim, maybe_frame, color,
+            &text, size, text_color
+        );
+
+        set_state(uic, self.ui_id, Widget::KeyCaptureBox(new_state), self.pos, self.dim);
+
+        maybe_captured
+    }
+
+}
+
+impl<'a> KeyCaptureBox<'a, fn(KeyCombo)> {
+
+    /// Draw the box and return the captured combo once a chord has been
+    /// pressed. This is an alternative to providing a callback.
+    pub fn captured<B, C>(mut self, uic: &mut UiContext<C>, graphics: &mut B) -> Option<KeyCombo>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics)
+    }
+
+}
+
+impl<'a, F> ::draw::Drawable for KeyCaptureBox<'a, F>
+    where
+        F: FnMut(KeyCombo) + 'a
+{
+
+    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics);
+    }
+
+}
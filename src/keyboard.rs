@@ -0,0 +1,80 @@
+use piston::input::keyboard::Key;
+use platform::Platform;
+use std::fmt::{ Display, Formatter, Error };
+
+bitflags! {
+    /// The modifier keys that are currently held.
+    flags ModifierKeys: u8 {
+        const NO_MODIFIER = 0b0000,
+        const CTRL        = 0b0001,
+        const SHIFT       = 0b0010,
+        const ALT         = 0b0100,
+        /// The Cmd key on OS X or the Windows key elsewhere.
+        const SUPER       = 0b1000,
+    }
+}
+
+impl ModifierKeys {
+    /// The modifier represented by the given key (if any).
+    pub fn from_key(key: Key) -> ModifierKeys {
+        match key {
+            Key::LCtrl | Key::RCtrl => CTRL,
+            Key::LShift | Key::RShift => SHIFT,
+            Key::LAlt | Key::RAlt => ALT,
+            Key::LGui | Key::RGui => SUPER,
+            _ => NO_MODIFIER,
+        }
+    }
+}
+
+/// A key pressed along with the modifiers that were held at the time,
+/// i.e. Ctrl+Shift+S.
+#[derive(Copy, Clone, PartialEq)]
+pub struct KeyCombo {
+    pub key: Key,
+    pub modifiers: ModifierKeys,
+}
+
+impl KeyCombo {
+
+    /// Construct a KeyCombo.
+    pub fn new(key: Key, modifiers: ModifierKeys) -> KeyCombo {
+        KeyCombo { key: key, modifiers: modifiers }
+    }
+
+    /// A readable description of the combo using the given platform's key
+    /// names, i.e. "Ctrl+Shift+S" or "Cmd+Shift+S". This is suitable for
+    /// registering as a widget's shortcut.
+    pub fn describe(&self, platform: Platform) -> String {
+        let mut s = modifiers_to_string(self.modifiers, platform);
+        s.push_str(&format!("{:?}", self.key));
+        s
+    }
+
+}
+
+/// A readable description of the combo using the target platform's key names.
+impl Display for KeyCombo {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
+        write!(fmt, "{}", self.describe(Platform::current()))
+    }
+}
+
+/// A readable prefix for the given modifiers using the given platform's
+/// key names, i.e. "Ctrl+Shift+".
+pub fn modifiers_to_string(modifiers: ModifierKeys, platform: Platform) -> String {
+    let mut s = String::new();
+    if modifiers.contains(CTRL) { s.push_str("Ctrl+") }
+    if modifiers.contains(ALT) {
+        s.push_str(if platform == Platform::MacOs { "Option+" } else { "Alt+" })
+    }
+    if modifiers.contains(SHIFT) { s.push_str("Shift+") }
+    if modifiers.contains(SUPER) {
+        s.push_str(match platform {
+            Platform::MacOs => "Cmd+",
+            Platform::Windows => "Win+",
+            Platform::Linux => "Super+",
+        })
+    }
+    s
+}
@@ -2,9 +2,12 @@ use piston::quack::{ Pair, Set, SetAt };
 use graphics::Graphics;
 use graphics::character::CharacterCache;
 use color::Color;
+use font::FontId;
 use point::Point;
 use ui_context::UiContext;
+use Font;
 use Position;
+use Visible;
 
 pub type FontSize = u32;
 
@@ -17,10 +20,121 @@ pub enum Labeling<'a> {
 /// Determine the pixel width of the final text bitmap.
 #[inline]
 pub fn width<C: CharacterCache>(uic: &mut UiContext<C>, size: FontSize, text: &str) -> f64 {
-    text.chars().fold(0u32, |a, ch| {
-        let character = uic.get_character(size, ch);
-        a + character.width() as u32
-    }) as f64
+    text.chars().fold(0u32, |a, ch| a + uic.get_character_w(size, ch) as u32) as f64
+}
+
+/// The width given to every digit when drawing tabular figures, which is
+/// that of the widest digit.
+pub fn tabular_digit_width<C: CharacterCache>(uic: &mut UiContext<C>, size: FontSize) -> f64 {
+    "0123456789".chars().fold(0.0, |max, ch| {
+        let w = uic.get_character_w(size, ch);
+        if w > max { w } else { max }
+    })
+}
+
+/// Determine the pixel width of a value readout, which uses tabular figures
+/// if the theme enables them.
+pub fn value_width<C: CharacterCache>(uic: &mut UiContext<C>, size: FontSize, text: &str) -> f64 {
+    if !uic.theme.tabular_figures.unwrap_or(false) { return width(uic, size, text) }
+    let digit_w = tabular_digit_width(uic, size);
+    text.chars().fold(0.0, |a, ch| {
+        if ch.is_digit(10) { a + digit_w } else { a + uic.get_character_w(size, ch) }
+    })
+}
+
+/// The horizontal alignment of text.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    /// The offset from the left of an area of the given width at which
+    /// text of the given width begins.
+    pub fn offset(&self, text_w: f64, w: f64) -> f64 {
+        match *self {
+            Align::Left => 0.0,
+            Align::Center => (w - text_w) / 2.0,
+            Align::Right => w - text_w,
+        }
+    }
+}
+
+/// Additional space inserted between characters and between words.
+/// Spaced text is laid out from each character's advance width alone, as
+/// `CharacterCache` exposes no kerning pairs, so it isn't kerned.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Spacing {
+    /// Added after every character but the last.
+    pub letter: f64,
+    /// Added after every space, in addition to the letter spacing.
+    pub word: f64,
+}
+
+impl Spacing {
+    /// No additional spacing.
+    pub fn none() -> Spacing {
+        Spacing { letter: 0.0, word: 0.0 }
+    }
+
+    /// The space to add after the given character.
+    pub fn after(&self, ch: char) -> f64 {
+        if ch == ' ' { self.letter + self.word } else { self.letter }
+    }
+}
+
+/// Determine the pixel width of the text with the given spacing applied.
+#[inline]
+pub fn spaced_width<C: CharacterCache>(uic: &mut UiContext<C>,
+                                       size: FontSize,
+                                       text: &str,
+                                       spacing: Spacing) -> f64 {
+    let num_chars = text.chars().count();
+    text.chars().enumerate().fold(0.0, |a, (i, ch)| {
+        let w = uic.get_character_w(size, ch);
+        if i + 1 < num_chars { a + w + spacing.after(ch) } else { a + w }
+    })
+}
+
+/// Break the text into lines no wider than `max_w`, at spaces where
+/// possible. Words wider than `max_w` are broken between characters.
+/// Newlines in the text always begin a new line.
+pub fn wrap<C: CharacterCache>(uic: &mut UiContext<C>,
+                               size: FontSize,
+                               text: &str,
+                               spacing: Spacing,
+                               max_w: f64) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let candidate = match line.len() {
+                0 => word.to_string(),
+                _ => format!("{} {}", line, word),
+            };
+            if spaced_width(uic, size, &candidate, spacing) <= max_w {
+                line = candidate;
+                continue;
+            }
+            if line.len() > 0 { lines.push(line) }
+            line = word.to_string();
+            while line.chars().count() > 1 && spaced_width(uic, size, &line, spacing) > max_w {
+                let mut end = line.char_indices().nth(1).map(|(i, _)| i).unwrap_or(line.len());
+                for (i, _) in line.char_indices().skip(2) {
+                    if spaced_width(uic, size, &line[..i], spacing) > max_w { break }
+                    end = i;
+                }
+                let rest = line[end..].to_string();
+                line.truncate(end);
+                lines.push(line);
+                line = rest;
+            }
+        }
+        lines.push(line);
+    }
+    lines
 }
 
 /// Determine a suitable FontSize from a given rectangle height.
@@ -93,8 +207,14 @@ impl<'a, T: 'a> Labelable<'a> for T
 pub struct Label<'a> {
     text: &'a str,
     pos: Point,
+    visible: bool,
     size: FontSize,
     maybe_color: Option<Color>,
+    maybe_font: Option<FontId>,
+    spacing: Spacing,
+    align: Align,
+    maybe_wrap: Option<f64>,
+    line_spacing: f64,
 }
 
 impl<'a> Label<'a> {
@@ -102,6 +222,58 @@ impl<'a> Label<'a> {
     pub fn size(self, size: FontSize) -> Label<'a> {
         Label { size: size, ..self }
     }
+
+    /// A builder method for specifying the space added between characters.
+    /// Spaced text isn't kerned (see `Spacing`).
+    pub fn letter_spacing(self, letter: f64) -> Label<'a> {
+        Label { spacing: Spacing { letter: letter, ..self.spacing }, ..self }
+    }
+
+    /// A builder method for specifying the space added between words.
+    pub fn word_spacing(self, word: f64) -> Label<'a> {
+        Label { spacing: Spacing { word: word, ..self.spacing }, ..self }
+    }
+
+    /// A builder method for aligning the text relative to the label's
+    /// position, i.e. `Align::Right` ends the text at the position.
+    pub fn align(self, align: Align) -> Label<'a> {
+        Label { align: align, ..self }
+    }
+
+    /// A builder method for wrapping the text into lines no wider than
+    /// the given width.
+    pub fn wrap(self, width: f64) -> Label<'a> {
+        Label { maybe_wrap: Some(width), ..self }
+    }
+
+    /// A builder method for specifying the distance between the tops of
+    /// consecutive lines as a multiple of the font size.
+    pub fn line_spacing(self, line_spacing: f64) -> Label<'a> {
+        Label { line_spacing: line_spacing, ..self }
+    }
+
+    /// The lines of the label's text, wrapped if a wrap width was given.
+    pub fn lines<C: CharacterCache>(&self, uic: &mut UiContext<C>) -> Vec<String> {
+        match self.maybe_wrap {
+            Some(max_w) => wrap(uic, self.size, self.text, self.spacing, max_w),
+            None => self.text.split('\n').map(|line| line.to_string()).collect(),
+        }
+    }
+
+    /// The width of the label's widest line with its spacing applied.
+    pub fn width<C: CharacterCache>(&self, uic: &mut UiContext<C>) -> f64 {
+        let (size, spacing) = (self.size, self.spacing);
+        self.lines(uic).iter().fold(0.0, |max, line| {
+            let w = spaced_width(uic, size, line, spacing);
+            if w > max { w } else { max }
+        })
+    }
+
+    /// The height of the label's lines.
+    pub fn height<C: CharacterCache>(&self, uic: &mut UiContext<C>) -> f64 {
+        let num_lines = self.lines(uic).len();
+        self.size as f64 * (1.0 + (num_lines - 1) as f64 * self.line_spacing)
+    }
 }
 
 impl<'a> Label<'a> {
@@ -111,8 +283,14 @@ impl<'a> Label<'a> {
         Label {
             text: text,
             pos: [0.0, 0.0],
+            visible: true,
             size: 24u32,
             maybe_color: None,
+            maybe_font: None,
+            spacing: Spacing::none(),
+            align: Align::Left,
+            maybe_wrap: None,
+            line_spacing: 1.2,
         }
     }
 
@@ -121,9 +299,13 @@ impl<'a> Label<'a> {
 quack! {
     label: Label['a]
     get:
+        fn () -> Position [] { Position(label.pos) }
+        fn () -> Visible [] { Visible(label.visible) }
     set:
         fn (val: Color) [] { label.maybe_color = Some(val) }
+        fn (val: Font) [] { label.maybe_font = Some(val.0) }
         fn (val: Position) [] { label.pos = val.0 }
+        fn (val: Visible) [] { label.visible = val.0 }
     action:
 }
 
@@ -133,7 +315,22 @@ impl<'a> ::draw::Drawable for Label<'a> {
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        if !self.visible { return }
+        let maybe_prev_font = self.maybe_font.map(|font| uic.set_font(font));
         let color = self.maybe_color.unwrap_or(Color::black());
-        uic.draw_text(graphics, self.pos, self.size, color, self.text);
+        let line_h = self.size as f64 * self.line_spacing;
+        for (i, line) in self.lines(uic).iter().enumerate() {
+            let x = match self.align {
+                Align::Left => self.pos[0],
+                align => self.pos[0] + align.offset(spaced_width(uic, self.size, line, self.spacing), 0.0),
+            };
+            let pos = [x, self.pos[1] + i as f64 * line_h];
+            if self.spacing == Spacing::none() {
+                uic.draw_text(graphics, pos, self.size, color, line);
+            } else {
+                uic.draw_spaced_text(graphics, pos, self.size, color, line, self.spacing);
+            }
+        }
+        if let Some(font) = maybe_prev_font { uic.set_font(font); }
     }
 }
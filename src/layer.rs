@@ -0,0 +1,160 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use clip::{ self, Scissor };
+use color::Color;
+use font::FontId;
+use graphics::{ DrawState, Graphics, ImageSize };
+use label::FontSize;
+use point::Point;
+
+/// The depth at which something is drawn. Layers of greater depth are
+/// drawn over those of lesser depth.
+pub type Depth = f32;
+
+/// The depth of widgets drawn directly to the graphics backend.
+pub const DEFAULT: Depth = 0.0;
+
+/// The depth of overlays such as open DropDownLists and the NumPad, so
+/// that they're drawn over widgets declared later in the frame.
+pub const OVERLAY: Depth = 1000.0;
+
+/// The depth of tooltips, which are drawn over everything else.
+pub const TOOLTIP: Depth = 2000.0;
+
+/// A drawing operation recorded for a layer.
+#[derive(Clone, Debug)]
+pub enum Command {
+    /// Clear the screen to the color, i.e. to draw a Background.
+    Clear([f32; 4]),
+    /// Colored triangles, as the chunks of already transformed vertex
+    /// coordinates that were passed to the backend.
+    Triangles(DrawState, [f32; 4], Vec<Vec<f32>>),
+    /// Text drawn via `UiContext::draw_text` in the given font, clipped
+    /// to the scissor rectangle (if any).
+    Text(Point, FontSize, Color, FontId, String, Option<Scissor>),
+    /// Textured triangles, as the chunks of vertex and texture coordinates,
+    /// drawn with the texture at the given index of the Recorder's Textures.
+    Image(DrawState, [f32; 4], usize, Vec<(Vec<f32>, Vec<f32>)>),
+}
+
+/// The commands recorded for all layers, in the order in which they were
+/// recorded, along with the current clip rectangle.
+#[derive(Clone, Debug)]
+pub struct CommandList {
+    pub commands: Vec<(Depth, Command)>,
+    pub maybe_clip: Option<Scissor>,
+}
+
+/// The CommandList shared between the UiContext (which records text and
+/// sets the clip rectangle) and the Recorder (which records triangles) so
+/// that the order of their commands is kept.
+pub type Commands = Rc<RefCell<CommandList>>;
+
+/// The textures of the images recorded by a Recorder, indexed by its
+/// `Command::Image`s.
+pub type Textures<T> = Rc<RefCell<Vec<T>>>;
+
+/// Construct an empty list of Commands.
+pub fn new_commands() -> Commands {
+    Rc::new(RefCell::new(CommandList { commands: Vec::new(), maybe_clip: None }))
+}
+
+/// Take the recorded commands sorted by depth. Commands of equal depth
+/// remain in the order in which they were recorded.
+pub fn take_sorted(commands: &Commands) -> Vec<(Depth, Command)> {
+    let mut commands = ::std::mem::replace(&mut commands.borrow_mut().commands, Vec::new());
+    commands.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(::std::cmp::Ordering::Equal));
+    commands
+}
+
+/// Merge each run of triangles with the same draw state and color into a
+/// single command, so that they're drawn with a single call to the backend.
+pub fn batch(commands: Vec<(Depth, Command)>) -> Vec<Command> {
+    let mut batched: Vec<Command> = Vec::with_capacity(commands.len());
+    for (_, command) in commands.into_iter() {
+        if let Command::Triangles(ref draw_state, color, ref chunks) = command {
+            if let Some(&mut Command::Triangles(ref last_draw_state, last_color, ref mut last_chunks)) = batched.last_mut() {
+                if *last_draw_state == *draw_state && last_color == color {
+                    last_chunks.push_all(&chunks[..]);
+                    continue
+                }
+            }
+        }
+        batched.push(command);
+    }
+    batched
+}
+
+/// A graphics backend that records everything drawn into it at a depth,
+/// to be drawn to the real backend once the frame is complete. Widgets
+/// drawn via `UiContext::render` are drawn into a Recorder.
+///
+/// Textured triangles are only recorded by a Recorder constructed `with_textures`,
+/// which retains a clone of each texture. Text is recorded by the UiContext.
+pub struct Recorder<T> {
+    depth: Depth,
+    commands: Commands,
+    maybe_textures: Option<(Textures<T>, fn(&T) -> T)>,
+}
+
+impl<T> Recorder<T> {
+    /// Construct a Recorder adding to the given commands at the depth.
+    pub fn new(depth: Depth, commands: Commands) -> Recorder<T> {
+        Recorder { depth: depth, commands: commands, maybe_textures: None }
+    }
+
+    /// The depth at which the Recorder records.
+    pub fn depth(&self) -> Depth {
+        self.depth
+    }
+}
+
+impl<T: Clone> Recorder<T> {
+    /// Construct a Recorder that also records textured triangles, adding a
+    /// clone of each of their textures to the given Textures.
+    pub fn with_textures(depth: Depth, commands: Commands, textures: Textures<T>) -> Recorder<T> {
+        Recorder {
+            depth: depth,
+            commands: commands,
+            maybe_textures: Some((textures, <T as Clone>::clone)),
+        }
+    }
+}
+
+impl<T: ImageSize> Graphics for Recorder<T> {
+    type Texture = T;
+
+    fn clear(&mut self, color: [f32; 4]) {
+        self.commands.borrow_mut().commands.push((self.depth, Command::Clear(color)));
+    }
+
+    fn tri_list<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], mut f: F)
+        where
+            F: FnMut(&mut FnMut(&[f32]))
+    {
+        let mut chunks = Vec::new();
+        f(&mut |chunk: &[f32]| chunks.push(chunk.to_vec()));
+        let mut list = self.commands.borrow_mut();
+        let draw_state = clip::apply(draw_state, list.maybe_clip);
+        list.commands.push((self.depth, Command::Triangles(draw_state, *color, chunks)));
+    }
+
+    fn tri_list_uv<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], texture: &T, mut f: F)
+        where
+            F: FnMut(&mut FnMut(&[f32], &[f32]))
+    {
+        let idx = match self.maybe_textures {
+            Some((ref textures, clone_texture)) => {
+                let mut textures = textures.borrow_mut();
+                textures.push(clone_texture(texture));
+                textures.len() - 1
+            },
+            None => return,
+        };
+        let mut chunks = Vec::new();
+        f(&mut |xy: &[f32], uv: &[f32]| chunks.push((xy.to_vec(), uv.to_vec())));
+        let mut list = self.commands.borrow_mut();
+        let draw_state = clip::apply(draw_state, list.maybe_clip);
+        list.commands.push((self.depth, Command::Image(draw_state, *color, idx, chunks)));
+    }
+}
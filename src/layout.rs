@@ -0,0 +1,192 @@
+use piston::quack::{ Get, GetFrom, Pair, Set, SetAt };
+use dimensions::Dimensions;
+use point::Point;
+use position::Position;
+use shape::Size;
+use ui_context::UiContext;
+use visibility::Visible;
+
+/// The direction in which widgets are laid out by a Flow.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Direction {
+    /// Left to right, wrapping downwards onto a new row.
+    Row,
+    /// Top to bottom, wrapping rightwards onto a new column.
+    Column,
+}
+
+/// Assigns positions to widgets one after another in rows or columns,
+/// wrapping when the available length is exceeded.
+#[derive(Copy, Clone, Debug)]
+pub struct Flow {
+    direction: Direction,
+    pos: Point,
+    /// The height of each row or width of each column.
+    thickness: f64,
+    /// The length after which widgets wrap onto a new row or column.
+    wrap: f64,
+    spacing: f64,
+    /// The offset of the next widget along the current row or column.
+    offset: f64,
+    /// The index of the current row or column.
+    line: usize,
+    /// The furthest extent reached along any row or column.
+    max_offset: f64,
+    /// Whether or not hidden widgets are skipped rather than leaving a gap.
+    collapse_hidden: bool,
+}
+
+impl Flow {
+
+    /// Construct a Flow laying out rows of the given height starting at
+    /// `pos` and wrapping after `wrap_width`.
+    pub fn row(pos: Point, height: f64, wrap_width: f64, spacing: f64) -> Flow {
+        Flow::new(Direction::Row, pos, height, wrap_width, spacing)
+    }
+
+    /// Construct a Flow laying out columns of the given width starting at
+    /// `pos` and wrapping after `wrap_height`.
+    pub fn column(pos: Point, width: f64, wrap_height: f64, spacing: f64) -> Flow {
+        Flow::new(Direction::Column, pos, width, wrap_height, spacing)
+    }
+
+    fn new(direction: Direction, pos: Point, thickness: f64, wrap: f64, spacing: f64) -> Flow {
+        Flow {
+            direction: direction,
+            pos: pos,
+            thickness: thickness,
+            wrap: wrap,
+            spacing: spacing,
+            offset: 0.0,
+            line: 0,
+            max_offset: 0.0,
+            collapse_hidden: false,
+        }
+    }
+
+    /// Skip hidden widgets entirely so that they take up no space, rather
+    /// than leaving a gap where they would be.
+    pub fn collapse_hidden(self, collapse: bool) -> Flow {
+        Flow { collapse_hidden: collapse, ..self }
+    }
+
+    /// The height of each row or width of each column.
+    pub fn thickness(&self) -> f64 {
+        self.thickness
+    }
+
+    /// Return the position for the next widget with the given dimensions
+    /// and advance past it.
+    pub fn next(&mut self, dim: Dimensions) -> Point {
+        let len = match self.direction { Direction::Row => dim[0], Direction::Column => dim[1] };
+        if self.offset > 0.0 && self.offset + len > self.wrap {
+            self.offset = 0.0;
+            self.line += 1;
+        }
+        let across = self.line as f64 * (self.thickness + self.spacing);
+        let pos = match self.direction {
+            Direction::Row => [self.pos[0] + self.offset, self.pos[1] + across],
+            Direction::Column => [self.pos[0] + across, self.pos[1] + self.offset],
+        };
+        self.offset += len;
+        if self.offset > self.max_offset { self.max_offset = self.offset }
+        self.offset += self.spacing;
+        pos
+    }
+
+    /// The dimensions of the area occupied by the widgets laid out so far.
+    pub fn dim(&self) -> Dimensions {
+        let lines = if self.max_offset > 0.0 { self.line + 1 } else { 0 };
+        let across = lines as f64 * self.thickness
+                   + if lines > 1 { (lines - 1) as f64 * self.spacing } else { 0.0 };
+        match self.direction {
+            Direction::Row => [self.max_offset, across],
+            Direction::Column => [across, self.max_offset],
+        }
+    }
+
+}
+
+/// A trait for widgets whose position may be assigned by a Flow.
+pub trait Flowable {
+    fn flow(self, flow: &mut Flow) -> Self;
+}
+
+impl<T> Flowable for T
+    where
+        (Position, T): Pair<Data = Position, Object = T> + SetAt,
+        (Size, T): Pair<Data = Size, Object = T> + GetFrom,
+        (Visible, T): Pair<Data = Visible, Object = T> + GetFrom
+{
+    /// Position the widget at the next free spot in the given Flow. If the
+    /// Flow collapses hidden widgets, `visible` must be set beforehand.
+    #[inline]
+    fn flow(self, flow: &mut Flow) -> Self {
+        let Visible(visible) = self.get();
+        if !visible && flow.collapse_hidden { return self }
+        let Size(dim) = self.get();
+        let pos = flow.next(dim);
+        self.set(Position(pos))
+    }
+}
+
+/// A range of window widths within which a set of attributes applies,
+/// allowing one UI definition to adapt between small and large windows.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub enum Breakpoint {
+    Small,
+    Medium,
+    Large,
+}
+
+/// The window widths at which each Breakpoint begins.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Breakpoints {
+    pub medium: f64,
+    pub large: f64,
+}
+
+impl Breakpoints {
+
+    /// Construct Breakpoints beginning at the given window widths.
+    pub fn new(medium: f64, large: f64) -> Breakpoints {
+        Breakpoints { medium: medium, large: large }
+    }
+
+    /// The Breakpoint that the given window width falls within.
+    pub fn resolve(&self, win_w: f64) -> Breakpoint {
+        if win_w >= self.large { Breakpoint::Large }
+        else if win_w >= self.medium { Breakpoint::Medium }
+        else { Breakpoint::Small }
+    }
+
+}
+
+/// Conditionally apply attributes depending on the window's current
+/// Breakpoint. As this is resolved every frame, attributes are re-resolved
+/// automatically when the window is resized, i.e.
+///
+/// slider.dimensions(200.0, 30.0)
+///     .from_breakpoint(uic, Breakpoint::Large, |s| s.dimensions(400.0, 40.0))
+pub trait Responsive: Sized {
+
+    /// Apply `f` only while the window is within the given Breakpoint.
+    fn at_breakpoint<C, F>(self, uic: &UiContext<C>, breakpoint: Breakpoint, f: F) -> Self
+        where
+            F: FnOnce(Self) -> Self
+    {
+        if uic.breakpoint() == breakpoint { f(self) } else { self }
+    }
+
+    /// Apply `f` only while the window is within the given Breakpoint or a
+    /// larger one.
+    fn from_breakpoint<C, F>(self, uic: &UiContext<C>, breakpoint: Breakpoint, f: F) -> Self
+        where
+            F: FnOnce(Self) -> Self
+    {
+        if uic.breakpoint() >= breakpoint { f(self) } else { self }
+    }
+
+}
+
+impl<T> Responsive for T {}
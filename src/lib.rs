@@ -1,65 +1,133 @@
 #![deny(missing_copy_implementations)]
-#![feature(core, str_char)]
+#![feature(core, str_char, unicode)]
 
 #[macro_use] extern crate bitflags;
 extern crate clock_ticks;
+#[cfg(feature = "glium_backend")] extern crate freetype;
+#[cfg(feature = "glium_backend")] #[macro_use] extern crate glium;
 extern crate graphics;
 #[macro_use] extern crate piston;
 extern crate rand;
 extern crate rustc_serialize;
+#[cfg(feature = "sdl2_backend")] extern crate sdl2;
 extern crate vecmath;
 
 pub use background::Background;
 pub use button::Button;
+pub use custom_widget::CustomWidget;
 pub use drop_down_list::DropDownList;
 pub use envelope_editor::EnvelopeEditor;
 pub use envelope_editor::EnvelopePoint;
+pub use key_capture_box::KeyCaptureBox;
 pub use label::Label;
+pub use number_box::NumberBox;
 pub use number_dialer::NumberDialer;
+pub use plot::Plot;
+pub use segmented_control::SegmentedControl;
 pub use slider::Slider;
+pub use tabs::Tabs;
 pub use text_box::TextBox;
 pub use toggle::Toggle;
+pub use toggle_matrix::ToggleMatrix;
 pub use widget_matrix::WidgetMatrix;
 pub use xy_pad::XYPad;
 
+pub use animation::Easing;
 pub use callback::{ Callable, Callback };
+pub use canvas::{ CanvasId, Dock };
 pub use color::{Color, Colorable};
+pub use density::DisplayDensity;
 pub use dimensions::Dimensions;
 pub use draw::Drawable;
+pub use font::{ Font, FontId, Fontable };
 pub use frame::{Framing, Frameable, FrameColor, FrameWidth};
-pub use label::{Labelable, LabelText, LabelColor, LabelFontSize};
+pub use hit_shape::HitShape;
+pub use keyboard::{ KeyCombo, ModifierKeys };
+pub use label::{Align, Labelable, LabelText, LabelColor, LabelFontSize};
+pub use layer::Depth;
+pub use layout::{ Breakpoint, Flow, Flowable, Responsive };
+pub use link::LinkMode;
 pub use point::Point;
-pub use position::{Positionable, Position};
+pub use position::{Alignable, Positionable, Position};
+pub use primitive::LineStyle;
+pub use rectangle::Gradient;
 pub use shape::{Shapeable, Size};
+pub use shared::Shared;
+pub use smoothing::Smoothing;
 pub use theme::Theme;
 pub use ui_context::UiContext;
+pub use visibility::{ Hideable, Visible };
 pub use widget::Widget;
 
 #[macro_use]
 pub mod macros;
 
+pub mod animation;
+pub mod autosave;
+pub mod axis;
+pub mod backend;
 pub mod background;
 pub mod button;
 pub mod callback;
+pub mod canvas;
+pub mod clip;
 pub mod color;
+pub mod custom_widget;
+pub mod density;
 pub mod dimensions;
 pub mod draw;
 pub mod drop_down_list;
 pub mod envelope_editor;
 pub mod frame;
+pub mod font;
+pub mod fuzz;
+#[cfg(feature = "glium_backend")]
+pub mod glium_backend;
+pub mod headless;
+pub mod hit_shape;
+pub mod history;
+pub mod ime;
+pub mod key_capture_box;
+pub mod keyboard;
 pub mod label;
+pub mod layer;
+pub mod layout;
+pub mod link;
 pub mod mouse;
+pub mod num_pad;
+pub mod number_box;
 pub mod number_dialer;
+pub mod overlay;
+pub mod persist;
+pub mod platform;
+pub mod plot;
 pub mod point;
 pub mod position;
+pub mod primitive;
+pub mod quality;
+pub mod reactive;
 pub mod rectangle;
+pub mod resize;
+#[cfg(feature = "sdl2_backend")]
+pub mod sdl2_backend;
+pub mod segmented_control;
+pub mod selection;
 pub mod shape;
+pub mod shared;
 pub mod slider;
+pub mod smoothing;
+pub mod snapshot;
+pub mod tabs;
 pub mod text_box;
 pub mod theme;
+pub mod timer;
 pub mod toggle;
+pub mod toggle_matrix;
+pub mod tooltip;
 pub mod ui_context;
 pub mod utils;
+pub mod visibility;
 pub mod widget;
 pub mod widget_matrix;
+pub mod width_cache;
 pub mod xy_pad;
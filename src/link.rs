@@ -0,0 +1,104 @@
+use selection::BatchEdit;
+use ui_context::UIID;
+
+/// The relationship between the values of linked widgets.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LinkMode {
+    /// Linked widgets are set to the same value.
+    Absolute,
+    /// Linked widgets are offset by the same amount.
+    Relative,
+    /// Linked widgets mirror the value (i.e. 0.25 of the range becomes 0.75).
+    Inverse,
+}
+
+/// Groups of value widgets whose changes are propagated to one another,
+/// i.e. the left and right channels of a stereo control. Only Sliders
+/// currently apply and propagate linked changes.
+#[derive(Clone, Debug)]
+pub struct Links {
+    groups: Vec<(Vec<UIID>, LinkMode)>,
+    edits: Vec<(UIID, BatchEdit)>,
+}
+
+impl Links {
+
+    /// Construct an empty set of Links.
+    pub fn new() -> Links {
+        Links {
+            groups: Vec::new(),
+            edits: Vec::new(),
+        }
+    }
+
+    /// Link the given widgets with the given mode. Any of the widgets that
+    /// are already linked are first removed from their previous group.
+    /// Linking a group that already exists only updates its mode, so this
+    /// may be called every frame without dropping the pending changes.
+    pub fn link(&mut self, ids: &[UIID], mode: LinkMode) {
+        let is_linked = match self.groups.iter_mut().find(|&&mut (ref group, _)| {
+            group.len() == ids.len() && ids.iter().all(|id| group.contains(id))
+        }) {
+            Some(&mut (_, ref mut group_mode)) => { *group_mode = mode; true },
+            None => false,
+        };
+        if is_linked { return }
+        for &ui_id in ids.iter() {
+            self.unlink(ui_id);
+        }
+        if ids.len() > 1 {
+            self.groups.push((ids.to_vec(), mode));
+        }
+    }
+
+    /// Remove the given widget from its group (if any).
+    pub fn unlink(&mut self, ui_id: UIID) {
+        for &mut (ref mut ids, _) in self.groups.iter_mut() {
+            ids.retain(|&id| id != ui_id);
+        }
+        self.groups.retain(|&(ref ids, _)| ids.len() > 1);
+        self.edits.retain(|&(id, _)| id != ui_id);
+    }
+
+    /// The mode of the group that the given widget belongs to (if any).
+    pub fn mode(&self, ui_id: UIID) -> Option<LinkMode> {
+        self.groups.iter().find(|&&(ref ids, _)| ids.contains(&ui_id)).map(|&(_, mode)| mode)
+    }
+
+    /// Report that the user changed the given widget's value from one
+    /// percentage of its range to another. If the widget is linked the
+    /// change is queued for every other widget in its group.
+    pub fn propagate(&mut self, ui_id: UIID, old_perc: f32, new_perc: f32) {
+        let (others, mode) = match self.groups.iter().find(|&&(ref ids, _)| ids.contains(&ui_id)) {
+            Some(&(ref ids, mode)) => (ids.iter().cloned().filter(|&id| id != ui_id).collect::<Vec<_>>(), mode),
+            None => return,
+        };
+        let edit = match mode {
+            LinkMode::Absolute => BatchEdit::Set(new_perc),
+            LinkMode::Relative => BatchEdit::Offset(new_perc - old_perc),
+            LinkMode::Inverse => BatchEdit::Set(1.0 - new_perc),
+        };
+        for other in others.into_iter() {
+            match self.edits.iter_mut().find(|&&mut (id, _)| id == other) {
+                Some(&mut (_, ref mut pending)) => {
+                    *pending = match (*pending, edit) {
+                        (BatchEdit::Offset(a), BatchEdit::Offset(b)) => BatchEdit::Offset(a + b),
+                        _ => edit,
+                    };
+                    continue;
+                },
+                None => (),
+            }
+            self.edits.push((other, edit));
+        }
+    }
+
+    /// Take the edit queued for the given widget (if any).
+    pub fn take_edit(&mut self, ui_id: UIID) -> Option<BatchEdit> {
+        match self.edits.iter().position(|&(id, _)| id == ui_id) {
+            Some(idx) => Some(self.edits.remove(idx).1),
+            None => None,
+        }
+    }
+
+}
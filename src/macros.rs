@@ -34,17 +34,27 @@ macro_rules! widget_fns(
             pos: ::point::Point,
             dim: ::dimensions::Dimensions
         ) {
-            match *get_widget(uic, ui_id) {
+            let changed = match *get_widget(uic, ui_id) {
                 ref mut state => {
                     if !state.matches(&new_state) {
                         panic!("The Widget variant returned by UiContext is different to that which \
                                    was requested (Check that there are no UIID conflicts).");
                     }
+                    let changed = *state != new_state;
                     *state = new_state;
+                    changed
                 }
-            }
+            };
+            if changed { uic.damage(pos, dim) }
             uic.set_place(ui_id, pos, dim);
         }
 
     )
 );
+
+/// Generate a stable UIID from a name, i.e. `widget_id!("volume_slider")`,
+/// so that a widget's identity doesn't depend on manually assigned integers.
+#[macro_export]
+macro_rules! widget_id(
+    ($name:expr) => ($crate::ui_context::named_id($name))
+);
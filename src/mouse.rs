@@ -8,6 +8,13 @@ pub enum ButtonState {
     Down,
 }
 
+/// The start or end of a drag on a widget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Drag {
+    Began,
+    Ended,
+}
+
 /// Represents the current state of the Mouse.
 #[derive(Copy)]
 pub struct Mouse {
@@ -15,6 +22,16 @@ pub struct Mouse {
     pub left: ButtonState,
     pub middle: ButtonState,
     pub right: ButtonState,
+    /// The first extra button, commonly "back".
+    pub x1: ButtonState,
+    /// The second extra button, commonly "forward".
+    pub x2: ButtonState,
+    /// The horizontal and vertical scroll received since the last frame.
+    pub scroll: [f64; 2],
+    /// The number of successive left clicks made in quick succession at
+    /// the same place, i.e. 2 for a double-click. This is updated on each
+    /// press of the left button.
+    pub clicks: u32,
 }
 
 impl Mouse {
@@ -23,6 +40,15 @@ impl Mouse {
                left: ButtonState,
                middle: ButtonState,
                right: ButtonState) -> Mouse {
-        Mouse { pos: pos, left: left, middle: middle, right: right }
+        Mouse {
+            pos: pos,
+            left: left,
+            middle: middle,
+            right: right,
+            x1: ButtonState::Up,
+            x2: ButtonState::Up,
+            scroll: [0.0, 0.0],
+            clicks: 0,
+        }
     }
 }
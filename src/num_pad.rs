@@ -0,0 +1,230 @@
+use std::num::Float;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label;
+use mouse::ButtonState;
+use point::Point;
+use rectangle;
+use ui_context::{ UIID, UiContext };
+
+/// The width and height of each key.
+const KEY_SIZE: f64 = 40.0;
+
+/// The distance the mouse may move during a long-press before it is
+/// considered a drag instead.
+const LONG_PRESS_DISTANCE: f64 = 4.0;
+
+/// The maximum number of characters that may be entered.
+const MAX_ENTRY_LEN: usize = 16;
+
+/// The keys of a NumPad.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PadKey {
+    Digit(u8),
+    Sign,
+    Decimal,
+    Clear,
+    Enter,
+}
+
+/// Each key along with its label, column, row and column span.
+const KEYS: &'static [(PadKey, &'static str, usize, usize, usize)] = &[
+    (PadKey::Digit(7), "7", 0, 0, 1),
+    (PadKey::Digit(8), "8", 1, 0, 1),
+    (PadKey::Digit(9), "9", 2, 0, 1),
+    (PadKey::Clear, "C", 3, 0, 1),
+    (PadKey::Digit(4), "4", 0, 1, 1),
+    (PadKey::Digit(5), "5", 1, 1, 1),
+    (PadKey::Digit(6), "6", 2, 1, 1),
+    (PadKey::Sign, "+/-", 3, 1, 1),
+    (PadKey::Digit(1), "1", 0, 2, 1),
+    (PadKey::Digit(2), "2", 1, 2, 1),
+    (PadKey::Digit(3), "3", 2, 2, 1),
+    (PadKey::Decimal, ".", 3, 2, 1),
+    (PadKey::Digit(0), "0", 0, 3, 2),
+    (PadKey::Enter, "Enter", 2, 3, 2),
+];
+
+/// An on-screen numeric keypad for entering values on touch screens and
+/// kiosks. It is opened by long-pressing a NumberDialer and drawn beneath it.
+/// Only one NumPad may be open at a time.
+#[derive(Clone, Debug)]
+pub struct NumPad {
+    /// The time in seconds for which a widget must be pressed before the
+    /// NumPad opens.
+    pub long_press: f64,
+    /// The pressed widget, when and where it was pressed and whether the
+    /// press has already been handled (or became a drag).
+    maybe_press: Option<(UIID, f64, Point, bool)>,
+    maybe_open: Option<UIID>,
+    entry: String,
+    maybe_pressed_key: Option<PadKey>,
+}
+
+impl NumPad {
+
+    /// Construct a closed NumPad.
+    pub fn new() -> NumPad {
+        NumPad {
+            long_press: 0.6,
+            maybe_press: None,
+            maybe_open: None,
+            entry: String::new(),
+            maybe_pressed_key: None,
+        }
+    }
+
+    /// Report whether the given widget is pressed at the given mouse
+    /// position. Returns true once the press has been held in place for
+    /// longer than `long_press`.
+    pub fn long_press(&mut self, ui_id: UIID, maybe_pressed_at: Option<Point>, now: f64) -> bool {
+        let p = match maybe_pressed_at {
+            Some(p) => p,
+            None => {
+                if let Some((id, _, _, _)) = self.maybe_press {
+                    if id == ui_id { self.maybe_press = None }
+                }
+                return false;
+            },
+        };
+        match self.maybe_press {
+            Some((id, start, start_p, ref mut handled)) if id == ui_id => {
+                if *handled { return false }
+                if (p[0] - start_p[0]).abs() > LONG_PRESS_DISTANCE
+                || (p[1] - start_p[1]).abs() > LONG_PRESS_DISTANCE {
+                    *handled = true;
+                    return false;
+                }
+                if now - start >= self.long_press {
+                    *handled = true;
+                    return true;
+                }
+                false
+            },
+            _ => {
+                self.maybe_press = Some((ui_id, now, p, false));
+                false
+            },
+        }
+    }
+
+    /// Open the NumPad for the given widget with an empty entry.
+    pub fn open(&mut self, ui_id: UIID) {
+        self.maybe_open = Some(ui_id);
+        self.entry.clear();
+        self.maybe_pressed_key = None;
+    }
+
+    /// Close the NumPad.
+    pub fn close(&mut self) {
+        self.maybe_open = None;
+        self.entry.clear();
+        self.maybe_pressed_key = None;
+    }
+
+    /// Whether or not the NumPad is open for the given widget.
+    pub fn is_open_for(&self, ui_id: UIID) -> bool {
+        self.maybe_open == Some(ui_id)
+    }
+
+    /// The text entered so far.
+    pub fn entry(&self) -> &str {
+        &self.entry
+    }
+
+    /// Apply a key to the entry. Returns the entry when Enter is pressed.
+    pub fn input(&mut self, key: PadKey) -> Option<String> {
+        match key {
+            PadKey::Digit(d) => if self.entry.len() < MAX_ENTRY_LEN {
+                self.entry.push((b'0' + d) as char);
+            },
+            PadKey::Sign => {
+                if self.entry.starts_with("-") { self.entry.remove(0); }
+                else { self.entry.insert(0, '-'); }
+            },
+            PadKey::Decimal => if !self.entry.contains(".") && self.entry.len() < MAX_ENTRY_LEN {
+                if self.entry.len() == 0 || self.entry == "-" { self.entry.push('0') }
+                self.entry.push('.');
+            },
+            PadKey::Clear => self.entry.clear(),
+            PadKey::Enter => return Some(self.entry.clone()),
+        }
+        None
+    }
+
+}
+
+/// The dimensions of the NumPad, including the entry display.
+pub fn dim() -> Dimensions {
+    [KEY_SIZE * 4.0, KEY_SIZE * 5.0]
+}
+
+/// The position and dimensions of each key for a NumPad at `pos`.
+fn key_rects(pos: Point) -> Vec<(PadKey, &'static str, Point, Dimensions)> {
+    KEYS.iter().map(|&(key, text, col, row, span)| {
+        let key_pos = [pos[0] + col as f64 * KEY_SIZE, pos[1] + (row + 1) as f64 * KEY_SIZE];
+        (key, text, key_pos, [span as f64 * KEY_SIZE, KEY_SIZE])
+    }).collect()
+}
+
+/// Draw the NumPad at the given position and return the key that was
+/// clicked (if any).
+pub fn draw<B, C>(uic: &mut UiContext<C>, graphics: &mut B, pos: Point) -> Option<PadKey>
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    let size = uic.theme.font_size_medium;
+    let frame = Some((uic.theme.frame_width, uic.theme.frame_color));
+    let color = uic.theme.shape_color;
+    let text_color = uic.theme.label_color;
+    rectangle::draw(uic.win_w, uic.win_h, graphics, rectangle::State::Normal,
+                    pos, dim(), frame, uic.theme.background_color);
+
+    // Draw the entry, right-aligned within the display.
+    let entry = match uic.num_pad.entry() {
+        "" => "0".to_string(),
+        entry => entry.to_string(),
+    };
+    let entry_w = label::value_width(uic, size, &entry);
+    let entry_pos = [pos[0] + KEY_SIZE * 4.0 - entry_w - 8.0, pos[1] + (KEY_SIZE - size as f64) / 2.0];
+    uic.draw_value_text(graphics, entry_pos, size, text_color, &entry);
+
+    // Find the key under the mouse and handle clicks.
+    let mouse = uic.get_mouse_state();
+    let keys = key_rects(pos);
+    let maybe_hovered = keys.iter().find(|&&(_, _, key_pos, key_dim)| {
+        rectangle::is_over(key_pos, mouse.pos, key_dim)
+    }).map(|&(key, _, _, _)| key);
+    let mut maybe_clicked = None;
+    match mouse.left {
+        ButtonState::Down => if uic.num_pad.maybe_pressed_key.is_none() {
+            uic.num_pad.maybe_pressed_key = maybe_hovered;
+        },
+        ButtonState::Up => {
+            if let (Some(pressed), Some(hovered)) = (uic.num_pad.maybe_pressed_key, maybe_hovered) {
+                if pressed == hovered { maybe_clicked = Some(hovered) }
+            }
+            uic.num_pad.maybe_pressed_key = None;
+        },
+    }
+
+    // Draw the keys.
+    for &(key, text, key_pos, key_dim) in keys.iter() {
+        let state = if Some(key) == uic.num_pad.maybe_pressed_key && Some(key) == maybe_hovered {
+            rectangle::State::Clicked
+        } else if Some(key) == maybe_hovered {
+            rectangle::State::Highlighted
+        } else {
+            rectangle::State::Normal
+        };
+        rectangle::draw(uic.win_w, uic.win_h, graphics, state, key_pos, key_dim, frame, color);
+        let text_w = label::width(uic, size, text);
+        let text_pos = [key_pos[0] + (key_dim[0] - text_w) / 2.0,
+                        key_pos[1] + (key_dim[1] - size as f64) / 2.0];
+        uic.draw_text(graphics, text_pos, size, text_color, text);
+    }
+
+    maybe_clicked
+}
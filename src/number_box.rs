@@ -0,0 +1,261 @@
+use std::num::Float;
+use std::num::ToPrimitive;
+use std::num::FromPrimitive;
+use callback::Callable;
+use color::{ Color, Colorable };
+use dimensions::Dimensions;
+use font::FontId;
+use draw::Drawable;
+use frame::Frameable;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label;
+use mouse::Mouse;
+use number_dialer::parse_value;
+use point::Point;
+use position::Positionable;
+use rectangle;
+use shape::Shapeable;
+use text_box::TextBox;
+use ui_context::{
+    self,
+    Id,
+    UIID,
+    UiContext,
+};
+use utils::clamp;
+use widget::{ DefaultWidgetState, Widget };
+use Callback;
+use Font;
+use FrameColor;
+use FrameWidth;
+use LabelText;
+use LabelColor;
+use LabelFontSize;
+use Position;
+use Size;
+use Visible;
+
+/// The space between the label and the text field.
+const LABEL_PADDING: f64 = 8.0;
+
+/// Represents the elements of the NumberBox that may be interacted with.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Element {
+    /// The label, along with the mouse.pos.x and value at which
+    /// scrubbing began.
+    Label(f64, f64),
+    Field,
+}
+
+/// Represents the state of the NumberBox widget. The text field's state
+/// is stored separately by its TextBox.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(Element),
+    Clicked(Element),
+}
+
+widget_fns!(NumberBox, State, Widget::NumberBox(State::Normal));
+
+/// Check and return the current state of the NumberBox.
+fn get_new_state(is_over_elem: Option<Element>, prev: State, mouse: Mouse) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Clicked};
+    match (is_over_elem, prev, mouse.left) {
+        (_,          Clicked(elem @ Element::Label(_, _)), Down) => Clicked(elem),
+        (Some(_),    Normal,                               Down) => Normal,
+        (Some(elem), _,                                    Up)   => Highlighted(elem),
+        (Some(elem), Highlighted(_),                       Down) => Clicked(elem),
+        (Some(_),    Clicked(elem),                        Down) => Clicked(elem),
+        _                                                        => Normal,
+    }
+}
+
+/// Format the value with the given number of decimal places.
+fn format_value<T: ToPrimitive>(value: T, precision: u8) -> String {
+    format!("{:.*}", precision as usize, value.to_f64().unwrap())
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct NumberBox<'a, T, F> {
+    ui_id: UIID,
+    value: T,
+    min: T,
+    max: T,
+    precision: u8,
+    pos: Point,
+    visible: bool,
+    dim: Dimensions,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label: Option<&'a str>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+    maybe_font: Option<FontId>,
+    maybe_callback: Option<F>,
+}
+
+impl<'a, T, F> NumberBox<'a, T, F> {
+    /// A number_box builder method to be implemented by the UiContext.
+    pub fn new(ui_id: UIID, value: T, min: T, max: T, precision: u8) -> NumberBox<'a, T, F> {
+        NumberBox {
+            ui_id: ui_id,
+            value: value,
+            min: min,
+            max: max,
+            precision: precision,
+            pos: [0.0, 0.0],
+            visible: true,
+            dim: [192.0, 48.0],
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+            maybe_font: None,
+            maybe_callback: None,
+        }
+    }
+}
+
+quack! {
+    nb: NumberBox['a, T, F]
+    get:
+        fn () -> Size [] { Size(nb.dim) }
+        fn () -> Position [] { Position(nb.pos) }
+        fn () -> Visible [] { Visible(nb.visible) }
+        fn () -> DefaultWidgetState [] {
+            DefaultWidgetState(Widget::NumberBox(State::Normal))
+        }
+        fn () -> Id [] { Id(nb.ui_id) }
+    set:
+        fn (val: Color) [] { nb.maybe_color = Some(val) }
+        fn (val: Font) [] { nb.maybe_font = Some(val.0) }
+        fn (val: Callback<F>) [where F: FnMut(T) + 'a] {
+            nb.maybe_callback = Some(val.0)
+        }
+        fn (val: FrameColor) [] { nb.maybe_frame_color = Some(val.0) }
+        fn (val: FrameWidth) [] { nb.maybe_frame = Some(val.0) }
+        fn (val: LabelText<'a>) [] { nb.maybe_label = Some(val.0) }
+        fn (val: LabelColor) [] { nb.maybe_label_color = Some(val.0) }
+        fn (val: LabelFontSize) [] { nb.maybe_label_font_size = Some(val.0) }
+        fn (val: Position) [] { nb.pos = val.0 }
+        fn (val: Visible) [] { nb.visible = val.0 }
+        fn (val: Size) [] { nb.dim = val.0 }
+    action:
+}
+
+impl<'a, T, F> ::draw::Drawable for NumberBox<'a, T, F>
+    where
+        T: Copy + PartialEq + FromPrimitive + ToPrimitive + 'a,
+        F: FnMut(T) + 'a
+{
+    /// Draw the number_box. The `callback` is called with the new value
+    /// when it is scrubbed via the label, or when valid text is entered.
+    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if !self.visible { uic.keep_state(self.ui_id); return }
+        let maybe_prev_font = self.maybe_font.map(|font| uic.set_font(font));
+
+        let state = *get_state(uic, self.ui_id);
+        let mouse = uic.get_mouse_state();
+        let font_size = self.maybe_label_font_size.unwrap_or(uic.theme.font_size_medium);
+        let label_w = match self.maybe_label {
+            Some(text) => label::width(uic, font_size, text) + LABEL_PADDING,
+            None => 0.0,
+        };
+        let label_dim = [label_w, self.dim[1]];
+        let field_pos = [self.pos[0] + label_w, self.pos[1]];
+        let field_dim = [self.dim[0] - label_w, self.dim[1]];
+        let is_over_elem = if rectangle::is_over(self.pos, mouse.pos, label_dim) {
+            Some(Element::Label(mouse.pos[0], self.value.to_f64().unwrap()))
+        } else if rectangle::is_over(field_pos, mouse.pos, field_dim) {
+            Some(Element::Field)
+        } else {
+            None
+        };
+        let new_state = get_new_state(is_over_elem, state, mouse);
+        let (min_f, max_f) = (self.min.to_f64().unwrap(), self.max.to_f64().unwrap());
+
+        // Dragging the label horizontally scrubs the value across its range
+        // over the width of the widget. Holding Shift scrubs finely.
+        let mut new_val = self.value;
+        match new_state {
+            State::Clicked(Element::Label(start_x, start_val)) => {
+                let x = uic.fine_pointer(self.ui_id, mouse.pos)[0];
+                let scrubbed = start_val + (x - start_x) * (max_f - min_f) / self.dim[0];
+                let scale = (10.0f64).powi(self.precision as i32);
+                let scrubbed = clamp((scrubbed * scale).round() / scale, min_f, max_f);
+                new_val = FromPrimitive::from_f64(scrubbed).unwrap_or(self.value);
+            },
+            _ => uic.reset_pointer_smoothing(self.ui_id),
+        }
+
+        // Draw the label.
+        if let Some(text) = self.maybe_label {
+            let color = self.maybe_label_color.unwrap_or(uic.theme.label_color);
+            let l_pos = [self.pos[0], self.pos[1] + (self.dim[1] - font_size as f64) / 2.0];
+            uic.draw_text(graphics, l_pos, font_size, color, text);
+        }
+
+        // While the field isn't being edited it displays the current value.
+        // Entered text only replaces the value if it parses.
+        let text_id = ui_context::sub_id(self.ui_id, "text");
+        let is_editing = match uic.get_widget_state(text_id) {
+            Widget::TextBox(text_state) => text_state.cursor_idx().is_some(),
+            _ => false,
+        };
+        let mut text = match uic.take_text_buffer(text_id) {
+            Some(text) if is_editing => text,
+            _ => format_value(new_val, self.precision),
+        };
+        let mut maybe_entered = None;
+        {
+            let (min, max, precision) = (self.min, self.max, self.precision);
+            let color = self.maybe_color.unwrap_or(uic.theme.shape_color);
+            let frame_w = self.maybe_frame.unwrap_or(uic.theme.frame_width);
+            let frame_color = self.maybe_frame_color.unwrap_or(uic.theme.frame_color);
+            TextBox::new(text_id, &mut text)
+                .font_size(font_size)
+                .point(field_pos)
+                .dim(field_dim)
+                .color(color)
+                .frame(frame_w)
+                .frame_color(frame_color)
+                .filter(|ch| ch.is_digit(10) || ch == '.' || ch == '-')
+                .validator(move |text| match parse_value::<T>(text, min, max, precision) {
+                    Some(_) => Ok(()),
+                    None => Err(format!("{} is not a number", text)),
+                })
+                .callback(|text: &mut String| maybe_entered = Some(text.clone()))
+                .draw(uic, graphics);
+        }
+        if let Some(entered) = maybe_entered {
+            if let Some(val) = parse_value(&entered, self.min, self.max, self.precision) {
+                new_val = val;
+                text = format_value(new_val, self.precision);
+            }
+        }
+        uic.set_text_buffer(text_id, text);
+
+        if new_val != self.value {
+            uic.damage(self.pos, self.dim);
+            self.value = new_val;
+            match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(new_val),
+                None => (),
+            }
+        }
+
+        uic.tooltips.set_value(self.ui_id, format_value(new_val, self.precision));
+        set_state(uic, self.ui_id, Widget::NumberBox(new_state), self.pos, self.dim);
+        if let Some(font) = maybe_prev_font { uic.set_font(font); }
+    }
+}
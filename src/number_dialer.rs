@@ -1,26 +1,36 @@
+use std::cmp;
 use std::cmp::Ordering;
-use std::num::Float;
 use std::num::ToPrimitive;
 use std::num::FromPrimitive;
 use std::iter::repeat;
 use color::Color;
 use dimensions::Dimensions;
+use font::FontId;
 use graphics;
 use graphics::{
     Graphics,
     RelativeTransform,
 };
 use graphics::character::CharacterCache;
+use keyboard;
 use label;
 use label::FontSize;
-use mouse::Mouse;
+use layer;
+use mouse::{ ButtonState, Mouse };
+use num_pad;
+use overlay;
+use overlay::Side;
+use piston::input::keyboard::Key;
 use point::Point;
 use rectangle;
+use text_box;
 use utils::{
     clamp,
     compare_f64s,
+    ValueFormatter,
 };
 use ui_context::{
+    self,
     Id,
     UIID,
     UiContext,
@@ -28,6 +38,7 @@ use ui_context::{
 use vecmath::vec2_add;
 use widget::{ DefaultWidgetState, Widget };
 use Callback;
+use Font;
 use FrameColor;
 use FrameWidth;
 use LabelText;
@@ -35,6 +46,7 @@ use LabelColor;
 use LabelFontSize;
 use Position;
 use Size;
+use Visible;
 
 /// Represents the specific elements that the
 /// NumberDialer is made up of. This is used to
@@ -175,7 +187,7 @@ fn get_new_state(is_over_elem: Option<Element>, prev: State, mouse: Mouse) -> St
 #[inline]
 fn get_new_value<T>(val: T, min: T, max: T, idx: usize, y_ord: Ordering, val_string: &String) -> T
     where
-        T: Float + FromPrimitive + ToPrimitive + ToString
+        T: Copy + FromPrimitive + ToPrimitive + ToString
 {
     match y_ord {
         Ordering::Equal => val,
@@ -188,8 +200,8 @@ fn get_new_value<T>(val: T, min: T, max: T, idx: usize, y_ord: Ordering, val_str
                 None => {
                     let power = val_string.len() - idx - 1;
                     match y_ord {
-                        Ordering::Less => clamp(val_f + (10.0).powf(power as f32) as f64, min_f, max_f),
-                        Ordering::Greater => clamp(val_f - (10.0).powf(power as f32) as f64, min_f, max_f),
+                        Ordering::Less => clamp(val_f + 10.0f32.powf(power as f32) as f64, min_f, max_f),
+                        Ordering::Greater => clamp(val_f - 10.0f32.powf(power as f32) as f64, min_f, max_f),
                         _ => val_f,
                     }
                 },
@@ -197,8 +209,8 @@ fn get_new_value<T>(val: T, min: T, max: T, idx: usize, y_ord: Ordering, val_str
                     let mut power = dec_idx as isize - idx as isize - 1;
                     if power < -1 { power += 1; }
                     match y_ord {
-                        Ordering::Less => clamp(val_f + (10.0).powf(power as f32) as f64, min_f, max_f),
-                        Ordering::Greater => clamp(val_f - (10.0).powf(power as f32) as f64, min_f, max_f),
+                        Ordering::Less => clamp(val_f + 10.0f32.powf(power as f32) as f64, min_f, max_f),
+                        Ordering::Greater => clamp(val_f - 10.0f32.powf(power as f32) as f64, min_f, max_f),
                         _ => val_f,
                     }
                 },
@@ -209,6 +221,36 @@ fn get_new_value<T>(val: T, min: T, max: T, idx: usize, y_ord: Ordering, val_str
 
 }
 
+/// Whether `T` only holds whole numbers, i.e. i32, u32 or usize.
+fn is_integer<T: FromPrimitive + ToPrimitive>() -> bool {
+    let half: Option<T> = FromPrimitive::from_f64(0.5);
+    half.and_then(|half| half.to_f64()) != Some(0.5)
+}
+
+/// Parse entered text into a value, rounding it to the given precision and
+/// clamping it to the range. Returns None if the text isn't a number.
+pub fn parse_value<T>(text: &str, min: T, max: T, precision: u8) -> Option<T>
+    where
+        T: FromPrimitive + ToPrimitive
+{
+    let val_f: f64 = match text.trim().parse() {
+        Ok(val_f) => val_f,
+        Err(_) => return None,
+    };
+    if val_f.is_nan() { return None }
+    let scale = (10.0f64).powi(precision as i32);
+    let val_f = (val_f * scale).round() / scale;
+    FromPrimitive::from_f64(clamp(val_f, min.to_f64().unwrap(), max.to_f64().unwrap()))
+}
+
+/// The characters that may be typed into the dialer's entry field.
+fn is_entry_char(ch: char) -> bool {
+    ch.is_digit(10) || ch == '.' || ch == '-'
+}
+
+/// The frame color flashed when an entered value fails to parse.
+const INVALID_COLOR: Color = Color([0.9, 0.2, 0.2, 1.0]);
+
 /// Draw the value string glyphs.
 #[inline]
 fn draw_value_string<B, C: CharacterCache>(
@@ -288,6 +330,7 @@ pub struct NumberDialer<'a, T, F> {
     min: T,
     max: T,
     pos: Point,
+    visible: bool,
     dim: Dimensions,
     precision: u8,
     maybe_color: Option<Color>,
@@ -296,11 +339,19 @@ pub struct NumberDialer<'a, T, F> {
     maybe_label: Option<&'a str>,
     maybe_label_color: Option<Color>,
     maybe_label_font_size: Option<u32>,
+    maybe_font: Option<FontId>,
     maybe_callback: Option<F>,
+    maybe_value_formatter: Option<ValueFormatter<'a>>,
+    maybe_default_value: Option<T>,
 }
 
-impl<'a, T: Float, F> NumberDialer<'a, T, F> {
+impl<'a, T, F> NumberDialer<'a, T, F>
+    where
+        T: Copy + PartialOrd + FromPrimitive + ToPrimitive
+{
     /// A number_dialer builder method to be implemented by the UiContext.
+    /// Integer types (i.e. i32, u32 or usize) are supported, in which case
+    /// the precision is always 0.
     pub fn new(ui_id: UIID, value: T, min: T, max: T, precision: u8) -> NumberDialer<'a, T, F> {
         NumberDialer {
             ui_id: ui_id,
@@ -308,29 +359,52 @@ impl<'a, T: Float, F> NumberDialer<'a, T, F> {
             min: min,
             max: max,
             pos: [0.0, 0.0],
+            visible: true,
             dim: [128.0, 48.0],
-            precision: precision,
+            precision: if is_integer::<T>() { 0 } else { precision },
             maybe_color: None,
             maybe_frame: None,
             maybe_frame_color: None,
             maybe_label: None,
             maybe_label_color: None,
             maybe_label_font_size: None,
+            maybe_font: None,
             maybe_callback: None,
+            maybe_value_formatter: None,
+            maybe_default_value: None,
         }
     }
+
+    /// Format the value shown in the dialer's hover readout. The dialed
+    /// digits are always shown in full.
+    #[inline]
+    pub fn value_formatter<G>(self, formatter: G) -> NumberDialer<'a, T, F>
+        where G: Fn(f64) -> String + 'a
+    {
+        NumberDialer { maybe_value_formatter: Some(Box::new(formatter)), ..self }
+    }
+
+    /// Set the value to which the dialer is reset when Ctrl-clicked or
+    /// double-clicked.
+    #[inline]
+    pub fn default_value(self, value: T) -> NumberDialer<'a, T, F> {
+        NumberDialer { maybe_default_value: Some(value), ..self }
+    }
 }
 
 quack! {
     nd: NumberDialer['a, T, F]
     get:
         fn () -> Size [] { Size(nd.dim) }
+        fn () -> Position [] { Position(nd.pos) }
+        fn () -> Visible [] { Visible(nd.visible) }
         fn () -> DefaultWidgetState [] {
             DefaultWidgetState(Widget::NumberDialer(State::Normal))
         }
         fn () -> Id [] { Id(nd.ui_id) }
     set:
         fn (val: Color) [] { nd.maybe_color = Some(val) }
+        fn (val: Font) [] { nd.maybe_font = Some(val.0) }
         fn (val: Callback<F>) [where F: FnMut(T) + 'a] {
             nd.maybe_callback = Some(val.0)
         }
@@ -340,13 +414,14 @@ quack! {
         fn (val: LabelColor) [] { nd.maybe_label_color = Some(val.0) }
         fn (val: LabelFontSize) [] { nd.maybe_label_font_size = Some(val.0) }
         fn (val: Position) [] { nd.pos = val.0 }
+        fn (val: Visible) [] { nd.visible = val.0 }
         fn (val: Size) [] { nd.dim = val.0 }
     action:
 }
 
 impl<'a, T, F> ::draw::Drawable for NumberDialer<'a, T, F>
     where
-        T: Float + FromPrimitive + ToPrimitive + ToString,
+        T: Copy + PartialOrd + FromPrimitive + ToPrimitive + ToString,
         F: FnMut(T) + 'a
 {
     #[inline]
@@ -358,6 +433,8 @@ impl<'a, T, F> ::draw::Drawable for NumberDialer<'a, T, F>
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        if !self.visible { uic.keep_state(self.ui_id); return }
+        let maybe_prev_font = self.maybe_font.map(|font| uic.set_font(font));
 
         let state = *get_state(uic, self.ui_id);
         let mouse = uic.get_mouse_state();
@@ -388,7 +465,7 @@ impl<'a, T, F> ::draw::Drawable for NumberDialer<'a, T, F>
                                    label_pos, label_dim, val_string_w, val_string_h,
                                    val_string.len());
         let new_state = get_new_state(is_over_elem, state, mouse);
-        let color = self.maybe_color.unwrap_or(uic.theme.shape_color);
+        let color = uic.flash_color(self.ui_id, self.maybe_color.unwrap_or(uic.theme.shape_color));
 
         // Draw the widget rectangle.
         rectangle::draw(uic.win_w, uic.win_h, graphics, rectangle::State::Normal,
@@ -400,8 +477,35 @@ impl<'a, T, F> ::draw::Drawable for NumberDialer<'a, T, F>
             uic.draw_text(graphics, label_pos, font_size, val_string_color, &label_string);
         };
 
+        // Clicking the dialer focuses it until the mouse is pressed elsewhere,
+        // so that key presses meant for another widget aren't taken by it.
+        match (state, new_state) {
+            (State::Highlighted(_), State::Clicked(_)) => uic.focus(self.ui_id),
+            (_, State::Normal) if mouse.left == ButtonState::Down => uic.unfocus(self.ui_id),
+            _ => (),
+        }
+        let is_focused = uic.is_focused(self.ui_id);
+
         // Determine new value from the initial state and the new state.
-        let new_val = match (state, new_state) {
+        // Scrolling over a value glyph steps it once per unit scrolled as
+        // though it were dragged, as does pressing Up/Down while focused.
+        // Held arrow keys repeat.
+        let key_steps = match is_focused {
+            true => uic.get_pressed_keys().iter().fold(0.0, |steps, key| match *key {
+                Key::Up => steps + 1.0,
+                Key::Down => steps - 1.0,
+                _ => steps,
+            }),
+            false => 0.0,
+        };
+        let steps = mouse.scroll[1] + key_steps;
+        let mut new_val = match (state, new_state) {
+            (_, State::Highlighted(Element::ValueGlyph(idx, _))) if steps != 0.0 => {
+                let num_steps = cmp::max(steps.abs().round() as usize, 1);
+                (0..num_steps).fold(self.value, |val, _| {
+                    get_new_value(val, self.min, self.max, idx, compare_f64s(0.0, steps), &val_string)
+                })
+            },
             (State::Clicked(elem), State::Clicked(new_elem)) => {
                 match (elem, new_elem) {
                     (Element::ValueGlyph(idx, y), Element::ValueGlyph(_, new_y)) => {
@@ -412,20 +516,117 @@ impl<'a, T, F> ::draw::Drawable for NumberDialer<'a, T, F>
             }, _ => self.value,
         };
 
+        // Ctrl-clicking or double-clicking resets the value to its default (if any).
+        let is_resetting = self.maybe_default_value.is_some()
+            && (uic.get_modifier_keys().contains(keyboard::CTRL) || mouse.clicks >= 2);
+        let has_reset = match (state, new_state) {
+            (State::Highlighted(_), State::Clicked(_)) => is_resetting,
+            _ => false,
+        };
+        if has_reset {
+            new_val = clamp(self.maybe_default_value.unwrap(), self.min, self.max);
+        }
+
+        // Clicking the label or the space around the digits, or pressing Return
+        // while hovering the focused dialer, opens a field for typing an exact
+        // value. Return commits the value while Escape, clicking elsewhere or
+        // resetting the value cancels. Clicks that reset the value don't open
+        // the field.
+        let entry_id = ui_context::sub_id(self.ui_id, "entry");
+        let pressed_keys = uic.get_pressed_keys();
+        let maybe_entry = match uic.take_text_buffer(entry_id) {
+            Some(_) if has_reset => None,
+            Some(mut entry) => {
+                new_val = self.value;
+                for text in uic.get_entered_text().iter() {
+                    entry.extend(text.chars().filter(|&ch| is_entry_char(ch)));
+                }
+                let mut is_open = !(mouse.left == ButtonState::Down && is_over_elem.is_none());
+                for key in pressed_keys.iter() {
+                    match *key {
+                        Key::Backspace => { entry.pop(); },
+                        Key::Escape => is_open = false,
+                        Key::Return => match parse_value(&entry, self.min, self.max, self.precision) {
+                            Some(val) => { new_val = val; is_open = false },
+                            None => uic.flash_widget(self.ui_id, INVALID_COLOR, 0.3),
+                        },
+                        _ => (),
+                    }
+                }
+                if is_open { Some(entry) } else { None }
+            },
+            None if uic.num_pad.is_open_for(self.ui_id) => None,
+            None => match (state, new_state) {
+                (State::Clicked(Element::LabelGlyphs), State::Highlighted(_)) |
+                (State::Clicked(Element::Rect), State::Highlighted(_)) if !is_resetting =>
+                    Some(format!("{:.*}", self.precision as usize, self.value.to_f64().unwrap())),
+                (_, State::Highlighted(_)) if is_focused && pressed_keys.iter().any(|&key| key == Key::Return) =>
+                    Some(format!("{:.*}", self.precision as usize, self.value.to_f64().unwrap())),
+                _ => None,
+            },
+        };
+
+        // Long-pressing the dialer opens the NumPad beneath it for touch
+        // entry. Click outside of it or press Escape to cancel.
+        let maybe_pressed_at = match new_state {
+            State::Clicked(_) => Some(mouse.pos),
+            _ => None,
+        };
+        if uic.num_pad.long_press(self.ui_id, maybe_pressed_at, uic.time()) {
+            uic.num_pad.open(self.ui_id);
+        }
+        if uic.num_pad.is_open_for(self.ui_id) {
+            // The NumPad's key states aren't tracked per frame, so the
+            // whole pad is redrawn while it's open and once it closes.
+            let pad_dim = num_pad::dim();
+            let pad_pos = overlay::place(self.pos, self.dim, pad_dim, Side::Below, uic.win_w, uic.win_h);
+            uic.damage(pad_pos, pad_dim);
+            if uic.take_dismissed(self.ui_id) {
+                uic.num_pad.close();
+            } else {
+                uic.open_overlay(self.ui_id, pad_pos, pad_dim);
+                let mut maybe_key = None;
+                uic.draw_layer(layer::OVERLAY, |uic, graphics| {
+                    maybe_key = num_pad::draw(uic, graphics, pad_pos);
+                });
+                if let Some(key) = maybe_key {
+                    if let Some(entry) = uic.num_pad.input(key) {
+                        if let Some(val) = parse_value(&entry, self.min, self.max, self.precision) {
+                            new_val = val;
+                        }
+                        uic.num_pad.close();
+                        uic.close_overlay(self.ui_id);
+                    }
+                }
+            }
+        }
+
         // If the value has changed, create a new string for val_string.
         if self.value != new_val {
             val_string = create_val_string(new_val, val_string_len, self.precision)
         }
 
-        // Draw the value string.
+        // Draw the value string, or the text being entered.
         let val_string_pos = vec2_add(label_pos, [label_dim[0], 0.0]);
-        draw_value_string(uic.win_w, uic.win_h, graphics, uic, new_state,
-                          self.pos[1] + frame_w, color,
-                          value_glyph_slot_width(font_size), pad_h,
-                          val_string_pos,
-                          font_size,
-                          val_string_color,
-                          &val_string);
+        match maybe_entry {
+            Some(entry) => {
+                uic.draw_text(graphics, val_string_pos, font_size, val_string_color, &entry);
+                let cursor_x = val_string_pos[0] + label::width(uic, font_size, &entry);
+                text_box::draw_cursor(uic.win_w, uic.win_h, graphics, color,
+                                      cursor_x, self.pos[1] + frame_w, pad_h, uic.time());
+                uic.set_text_buffer(entry_id, entry);
+            },
+            None => draw_value_string(uic.win_w, uic.win_h, graphics, uic, new_state,
+                                      self.pos[1] + frame_w, color,
+                                      value_glyph_slot_width(font_size), pad_h,
+                                      val_string_pos,
+                                      font_size,
+                                      val_string_color,
+                                      &val_string),
+        }
+
+        // A change of value doesn't change the state, so damage the dialer here.
+        if self.value != new_val { uic.damage(self.pos, self.dim) }
 
         // Call the `callback` with the new value if the mouse is pressed/released
         // on the widget or if the value has changed.
@@ -439,8 +640,13 @@ impl<'a, T, F> ::draw::Drawable for NumberDialer<'a, T, F>
             }
         }
 
+        let val_string = match self.maybe_value_formatter {
+            Some(ref formatter) => formatter(new_val.to_f64().unwrap()),
+            None => val_string,
+        };
+        uic.tooltips.set_value(self.ui_id, val_string);
         set_state(uic, self.ui_id, Widget::NumberDialer(new_state), self.pos, self.dim);
-
+        if let Some(font) = maybe_prev_font { uic.set_font(font); }
     }
 
 }
@@ -0,0 +1,138 @@
+use dimensions::Dimensions;
+use point::Point;
+
+/// The side of an anchoring rectangle on which an overlay is placed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Side {
+    Below,
+    Above,
+    Right,
+    Left,
+}
+
+impl Side {
+    /// The opposite side.
+    pub fn opposite(&self) -> Side {
+        match *self {
+            Side::Below => Side::Above,
+            Side::Above => Side::Below,
+            Side::Right => Side::Left,
+            Side::Left => Side::Right,
+        }
+    }
+}
+
+/// Return the position of an overlay placed against the given side of
+/// the anchor, aligned with the anchor's top left.
+fn against(anchor_pos: Point, anchor_dim: Dimensions, dim: Dimensions, side: Side) -> Point {
+    match side {
+        Side::Below => [anchor_pos[0], anchor_pos[1] + anchor_dim[1]],
+        Side::Above => [anchor_pos[0], anchor_pos[1] - dim[1]],
+        Side::Right => [anchor_pos[0] + anchor_dim[0], anchor_pos[1]],
+        Side::Left => [anchor_pos[0] - dim[0], anchor_pos[1]],
+    }
+}
+
+/// The amount by which a rectangle at `pos` with `dim` falls outside of
+/// the window along the axis that the side is concerned with.
+fn overflow(pos: Point, dim: Dimensions, side: Side, win_w: f64, win_h: f64) -> f64 {
+    let (start, len, max) = match side {
+        Side::Below | Side::Above => (pos[1], dim[1], win_h),
+        Side::Right | Side::Left => (pos[0], dim[0], win_w),
+    };
+    let before = if start < 0.0 { -start } else { 0.0 };
+    let after = if start + len > max { start + len - max } else { 0.0 };
+    before + after
+}
+
+/// Slide a rectangle along both axes so that it remains within the
+/// window. Rectangles larger than the window are pinned to its top left.
+pub fn slide(pos: Point, dim: Dimensions, win_w: f64, win_h: f64) -> Point {
+    let slide_axis = |start: f64, len: f64, max: f64| {
+        let start = if start + len > max { max - len } else { start };
+        if start < 0.0 { 0.0 } else { start }
+    };
+    [slide_axis(pos[0], dim[0], win_w), slide_axis(pos[1], dim[1], win_h)]
+}
+
+/// Return the position for an overlay with the given dimensions placed
+/// against the preferred side of an anchoring rectangle (i.e. a menu
+/// below its button). If the overlay doesn't fit on that side it is
+/// flipped to the opposite side when that fits better, then slid so that
+/// it remains within the window.
+pub fn place(anchor_pos: Point,
+             anchor_dim: Dimensions,
+             dim: Dimensions,
+             side: Side,
+             win_w: f64,
+             win_h: f64) -> Point {
+    let preferred = against(anchor_pos, anchor_dim, dim, side);
+    let preferred_overflow = overflow(preferred, dim, side, win_w, win_h);
+    let pos = if preferred_overflow > 0.0 {
+        let flipped = against(anchor_pos, anchor_dim, dim, side.opposite());
+        if overflow(flipped, dim, side.opposite(), win_w, win_h) < preferred_overflow { flipped }
+        else { preferred }
+    } else {
+        preferred
+    };
+    slide(pos, dim, win_w, win_h)
+}
+
+#[cfg(test)]
+mod tests {
+    use dimensions::Dimensions;
+    use point::Point;
+    use super::{ place, Side };
+
+    const WIN_W: f64 = 800.0;
+    const WIN_H: f64 = 600.0;
+    const DIM: Dimensions = [200.0, 150.0];
+
+    fn is_inside_window(pos: Point, dim: Dimensions) -> bool {
+        pos[0] >= 0.0 && pos[1] >= 0.0 && pos[0] + dim[0] <= WIN_W && pos[1] + dim[1] <= WIN_H
+    }
+
+    #[test]
+    fn flips_above_at_the_bottom_edge() {
+        let pos = place([100.0, 550.0], [100.0, 30.0], DIM, Side::Below, WIN_W, WIN_H);
+        assert_eq!(pos, [100.0, 400.0]);
+        assert!(is_inside_window(pos, DIM));
+    }
+
+    #[test]
+    fn flips_below_at_the_top_edge() {
+        let pos = place([100.0, 10.0], [100.0, 30.0], DIM, Side::Above, WIN_W, WIN_H);
+        assert_eq!(pos, [100.0, 40.0]);
+        assert!(is_inside_window(pos, DIM));
+    }
+
+    #[test]
+    fn flips_left_at_the_right_edge() {
+        let pos = place([750.0, 100.0], [40.0, 30.0], DIM, Side::Right, WIN_W, WIN_H);
+        assert_eq!(pos, [550.0, 100.0]);
+        assert!(is_inside_window(pos, DIM));
+    }
+
+    #[test]
+    fn flips_right_at_the_left_edge() {
+        let pos = place([10.0, 100.0], [40.0, 30.0], DIM, Side::Left, WIN_W, WIN_H);
+        assert_eq!(pos, [50.0, 100.0]);
+        assert!(is_inside_window(pos, DIM));
+    }
+
+    #[test]
+    fn slides_along_the_edge_it_is_placed_against() {
+        let below = place([700.0, 100.0], [100.0, 30.0], DIM, Side::Below, WIN_W, WIN_H);
+        assert_eq!(below, [600.0, 130.0]);
+        let right = place([100.0, 500.0], [40.0, 30.0], DIM, Side::Right, WIN_W, WIN_H);
+        assert_eq!(right, [140.0, 450.0]);
+        assert!(is_inside_window(below, DIM) && is_inside_window(right, DIM));
+    }
+
+    #[test]
+    fn pins_overlays_larger_than_the_window_to_its_top_left() {
+        let pos = place([100.0, 100.0], [40.0, 30.0], [1000.0, 700.0], Side::Below, WIN_W, WIN_H);
+        assert_eq!(pos, [0.0, 0.0]);
+    }
+
+}
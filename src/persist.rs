@@ -0,0 +1,114 @@
+use rustc_serialize::{
+    json,
+    Encodable,
+    Decodable,
+};
+use std::collections::{ BTreeMap, HashMap };
+use std::error::Error;
+use std::fs;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::str;
+use ui_context::UIID;
+
+/// A persisted value, which is only encoded when the state is saved.
+trait Value {
+    fn to_json(&self) -> Option<json::Json>;
+}
+
+impl<T: Encodable> Value for T {
+    fn to_json(&self) -> Option<json::Json> {
+        json::encode(self).ok().and_then(|s| json::Json::from_str(&s).ok())
+    }
+}
+
+/// Widget state that is saved to a file so that it survives restarts,
+/// i.e. the selected tabs and the contents of text boxes, along with any
+/// state the application persists by UIID (i.e. split ratios).
+pub struct Persisted {
+    /// The values loaded from the file that are yet to be restored.
+    restored: HashMap<UIID, json::Json>,
+    /// The current values of all persisted state.
+    current: HashMap<UIID, Box<Value>>,
+}
+
+impl Persisted {
+
+    /// Construct an empty set of persisted state.
+    pub fn new() -> Persisted {
+        Persisted {
+            restored: HashMap::new(),
+            current: HashMap::new(),
+        }
+    }
+
+    /// Load the state saved at the given path. A missing file is not an
+    /// error, as there is no state to restore the first time.
+    pub fn load(path: &str) -> Result<Persisted, String> {
+        let mut file = match File::open(&Path::new(path)) {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(Persisted::new()),
+            Err(e) => return Err(format!("Failed to open file for persisted state: {}", Error::description(&e))),
+        };
+        let mut contents = Vec::new();
+        if let Err(e) = ::std::io::Read::read_to_end(&mut file, &mut contents) {
+            return Err(format!("Failed to load persisted state correctly: {}", Error::description(&e)));
+        }
+        let contents = match str::from_utf8(&contents[..]) {
+            Ok(contents) => contents,
+            Err(e) => return Err(format!("Persisted state is not valid UTF-8: {}", Error::description(&e))),
+        };
+        let object = match json::Json::from_str(contents) {
+            Ok(json::Json::Object(object)) => object,
+            Ok(_) => return Err("Persisted state is not a json object".to_string()),
+            Err(e) => return Err(format!("Failed to construct json_object from str: {}", Error::description(&e))),
+        };
+        let mut persisted = Persisted::new();
+        for (key, value) in object.into_iter() {
+            if let Ok(ui_id) = key.parse::<UIID>() {
+                persisted.restored.insert(ui_id, value);
+            }
+        }
+        Ok(persisted)
+    }
+
+    /// Persist the given value. The first time this is called for a UIID,
+    /// the value is replaced by the one that was loaded (if any). The value
+    /// is kept as it is and only encoded by `save`.
+    pub fn value<T>(&mut self, ui_id: UIID, value: &mut T)
+        where
+            T: Encodable + Decodable + Clone + 'static
+    {
+        if let Some(restored) = self.restored.remove(&ui_id) {
+            let mut decoder = json::Decoder::new(restored);
+            if let Ok(restored) = Decodable::decode(&mut decoder) {
+                *value = restored;
+            }
+        }
+        self.current.insert(ui_id, Box::new(value.clone()));
+    }
+
+    /// Save the current values to the given path. The state is first written
+    /// to a temporary file which then replaces the previous save, so that a
+    /// crash mid-write never leaves a partial file behind.
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let object: BTreeMap<String, json::Json> = self.current.iter()
+            .filter_map(|(ui_id, value)| value.to_json().map(|json| (ui_id.to_string(), json)))
+            .collect();
+        let json_string = format!("{}", json::as_pretty_json(&json::Json::Object(object)));
+        let tmp_path = format!("{}.tmp", path);
+        let mut file = match File::create(&Path::new(&tmp_path)) {
+            Ok(file) => file,
+            Err(e) => return Err(format!("Failed to create a File at the given path: {}", Error::description(&e))),
+        };
+        if let Err(e) = ::std::io::Write::write_all(&mut file, json_string.as_bytes()) {
+            return Err(format!("Persisted state failed to save correctly: {}", Error::description(&e)));
+        }
+        match fs::rename(&Path::new(&tmp_path), &Path::new(path)) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(format!("Failed to replace the previous persisted state: {}", Error::description(&e))),
+        }
+    }
+
+}
@@ -0,0 +1,67 @@
+use keyboard::{ self, ModifierKeys };
+
+/// The operating systems whose interaction conventions are followed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Platform {
+    MacOs,
+    Windows,
+    Linux,
+}
+
+impl Platform {
+    /// The platform being targeted.
+    pub fn current() -> Platform {
+        if cfg!(target_os = "macos") { Platform::MacOs }
+        else if cfg!(target_os = "windows") { Platform::Windows }
+        else { Platform::Linux }
+    }
+}
+
+/// Interaction defaults that differ between platforms. These are selected
+/// for the target OS by default and may be overridden at runtime via
+/// `UiContext::set_conventions`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Conventions {
+    /// The platform whose naming is used, i.e. "Cmd" rather than "Ctrl".
+    pub platform: Platform,
+    /// The modifier used for application shortcuts (Cmd on OS X, otherwise Ctrl).
+    pub command_modifier: ModifierKeys,
+    /// The modifier used for moving and deleting text by whole words (Alt
+    /// on OS X, otherwise Ctrl).
+    pub word_modifier: ModifierKeys,
+    /// The longest time in seconds between clicks of a double-click.
+    pub double_click_threshold: f64,
+}
+
+impl Conventions {
+
+    /// The conventions of the given platform.
+    pub fn for_platform(platform: Platform) -> Conventions {
+        match platform {
+            Platform::MacOs => Conventions {
+                platform: platform,
+                command_modifier: keyboard::SUPER,
+                word_modifier: keyboard::ALT,
+                double_click_threshold: 0.5,
+            },
+            Platform::Windows => Conventions {
+                platform: platform,
+                command_modifier: keyboard::CTRL,
+                word_modifier: keyboard::CTRL,
+                double_click_threshold: 0.5,
+            },
+            Platform::Linux => Conventions {
+                platform: platform,
+                command_modifier: keyboard::CTRL,
+                word_modifier: keyboard::CTRL,
+                double_click_threshold: 0.4,
+            },
+        }
+    }
+
+    /// The conventions of the target platform.
+    pub fn current() -> Conventions {
+        Conventions::for_platform(Platform::current())
+    }
+
+}
@@ -0,0 +1,396 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label;
+use label::FontSize;
+use point::Point;
+use primitive;
+use primitive::LineStyle;
+use quality::Tier;
+use rectangle;
+use rectangle::{
+    Corner
+};
+use ui_context::{
+    Id,
+    UIID,
+    UiContext,
+};
+use utils::{
+    clamp,
+    map_range,
+    val_to_string,
+};
+use vecmath::{
+    vec2_add,
+    vec2_sub,
+};
+use widget::{ DefaultWidgetState, Widget };
+use FrameColor;
+use FrameWidth;
+use LabelText;
+use LabelColor;
+use LabelFontSize;
+use Position;
+use Size;
+use Visible;
+
+/// Represents the state of the Plot widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted,
+}
+
+widget_fns!(Plot, State, Widget::Plot(State::Normal));
+
+/// Check the current state of the plot.
+fn get_new_state(is_over: bool) -> State {
+    match is_over {
+        true => State::Highlighted,
+        false => State::Normal,
+    }
+}
+
+/// The padding between the tick labels and the plot area.
+static TICK_PADDING: f64 = 4.0;
+
+/// Determine the min and max y values over all series.
+fn auto_y_range(series: &[Vec<f64>]) -> (f64, f64) {
+    let mut min = ::std::f64::MAX;
+    let mut max = ::std::f64::MIN;
+    for s in series.iter() {
+        for &y in s.iter() {
+            if y < min { min = y }
+            if y > max { max = y }
+        }
+    }
+    if min > max { (0.0, 1.0) }
+    else if min == max { (min - 1.0, max + 1.0) }
+    else { (min, max) }
+}
+
+/// Return `n` evenly spaced values from `min` to `max` inclusive.
+fn tick_values(min: f64, max: f64, n: usize) -> Vec<f64> {
+    match n {
+        0 => Vec::new(),
+        1 => vec![min],
+        _ => (0..n).map(|i| min + (max - min) * i as f64 / (n - 1) as f64).collect(),
+    }
+}
+
+/// Find the datapoint closest to the mouse, returning the series
+/// index, the sample index and the position of the point on screen.
+fn closest_point(series: &[Vec<f64>],
+                 mouse_pos: Point,
+                 area_pos: Point,
+                 area_dim: Dimensions,
+                 max_len: usize,
+                 min_y: f64,
+                 max_y: f64) -> Option<(usize, usize, Point)> {
+    if max_len == 0 { return None }
+    let last_idx = if max_len > 1 { (max_len - 1) as f64 } else { 1.0 };
+    let mouse_x = clamp(mouse_pos[0], area_pos[0], area_pos[0] + area_dim[0]);
+    let idx = map_range(mouse_x, area_pos[0], area_pos[0] + area_dim[0], 0.0, last_idx).round() as usize;
+    let mut closest = None;
+    let mut closest_distance = ::std::f64::MAX;
+    for (i, s) in series.iter().enumerate() {
+        if idx >= s.len() { continue }
+        let y = clamp(s[idx], min_y, max_y);
+        let p = [map_range(idx as f64, 0.0, last_idx, area_pos[0], area_pos[0] + area_dim[0]),
+                 map_range(y, min_y, max_y, area_pos[1] + area_dim[1], area_pos[1])];
+        let distance = (mouse_pos[1] - p[1]).abs();
+        if distance < closest_distance {
+            closest_distance = distance;
+            closest = Some((i, idx, p));
+        }
+    }
+    closest
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct Plot<'a> {
+    ui_id: UIID,
+    series: &'a [Vec<f64>],
+    maybe_series_colors: Option<&'a [Color]>,
+    maybe_x_range: Option<(f64, f64)>,
+    maybe_y_range: Option<(f64, f64)>,
+    x_ticks: usize,
+    y_ticks: usize,
+    fill: bool,
+    line_width: f64,
+    maybe_line_style: Option<LineStyle>,
+    font_size: FontSize,
+    pos: Point,
+    visible: bool,
+    dim: Dimensions,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label: Option<&'a str>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+}
+
+impl<'a> Plot<'a> {
+    /// The values that the sample indices of each series map to for
+    /// the x tick labels and readout. By default this is `0..len-1`.
+    #[inline]
+    pub fn x_range(self, min: f64, max: f64) -> Plot<'a> {
+        Plot { maybe_x_range: Some((min, max)), ..self }
+    }
+    /// Use a fixed y range rather than scaling to the series values.
+    #[inline]
+    pub fn y_range(self, min: f64, max: f64) -> Plot<'a> {
+        Plot { maybe_y_range: Some((min, max)), ..self }
+    }
+    /// The number of ticks along the x and y axes.
+    #[inline]
+    pub fn ticks(self, x_ticks: usize, y_ticks: usize) -> Plot<'a> {
+        Plot { x_ticks: x_ticks, y_ticks: y_ticks, ..self }
+    }
+    /// Fill the area between each series and the bottom of the plot.
+    #[inline]
+    pub fn fill(self, fill: bool) -> Plot<'a> {
+        Plot { fill: fill, ..self }
+    }
+    /// The color used for each series. Series without a color use
+    /// the plain contrast of the plot color.
+    #[inline]
+    pub fn series_colors(self, colors: &'a [Color]) -> Plot<'a> {
+        Plot { maybe_series_colors: Some(colors), ..self }
+    }
+    #[inline]
+    pub fn line_width(self, width: f64) -> Plot<'a> {
+        Plot { line_width: width, ..self }
+    }
+    #[inline]
+    pub fn line_style(self, style: LineStyle) -> Plot<'a> {
+        Plot { maybe_line_style: Some(style), ..self }
+    }
+    #[inline]
+    pub fn value_font_size(self, size: FontSize) -> Plot<'a> {
+        Plot { font_size: size, ..self }
+    }
+}
+
+impl<'a> Plot<'a> {
+    /// A plot builder method to be implemented by the UiContext.
+    pub fn new(ui_id: UIID, series: &'a [Vec<f64>]) -> Plot<'a> {
+        Plot {
+            ui_id: ui_id,
+            series: series,
+            maybe_series_colors: None,
+            maybe_x_range: None,
+            maybe_y_range: None,
+            x_ticks: 5,
+            y_ticks: 5,
+            fill: false,
+            line_width: 2.0,
+            maybe_line_style: None,
+            font_size: 12u32,
+            pos: [0.0, 0.0],
+            visible: true,
+            dim: [256.0, 128.0],
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+        }
+    }
+}
+
+quack! {
+    plot: Plot['a]
+    get:
+        fn () -> Size [] { Size(plot.dim) }
+        fn () -> Position [] { Position(plot.pos) }
+        fn () -> Visible [] { Visible(plot.visible) }
+        fn () -> DefaultWidgetState [] {
+            DefaultWidgetState(Widget::Plot(State::Normal))
+        }
+        fn () -> Id [] { Id(plot.ui_id) }
+    set:
+        fn (val: Color) [] { plot.maybe_color = Some(val) }
+        fn (val: FrameColor) [] { plot.maybe_frame_color = Some(val.0) }
+        fn (val: FrameWidth) [] { plot.maybe_frame = Some(val.0) }
+        fn (val: LabelText<'a>) [] { plot.maybe_label = Some(val.0) }
+        fn (val: LabelColor) [] { plot.maybe_label_color = Some(val.0) }
+        fn (val: LabelFontSize) [] { plot.maybe_label_font_size = Some(val.0) }
+        fn (val: Position) [] { plot.pos = val.0 }
+        fn (val: Visible) [] { plot.visible = val.0 }
+        fn (val: Size) [] { plot.dim = val.0 }
+    action:
+}
+
+impl<'a> ::draw::Drawable for Plot<'a> {
+
+    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if !self.visible { uic.keep_state(self.ui_id); return }
+
+        // Init.
+        let mouse = uic.get_mouse_state();
+        let frame_w = self.maybe_frame.unwrap_or(uic.theme.frame_width);
+        let frame_w2 = frame_w * 2.0;
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(uic.theme.frame_color))),
+            false => None,
+        };
+        let pad_dim = vec2_sub(self.dim, [frame_w2; 2]);
+        let pad_pos = vec2_add(self.pos, [frame_w, frame_w]);
+        let is_over = rectangle::is_over(pad_pos, mouse.pos, pad_dim);
+        let new_state = get_new_state(is_over);
+        let color = uic.flash_color(self.ui_id, self.maybe_color.unwrap_or(uic.theme.shape_color));
+        let contrast = color.plain_contrast();
+        let font_size = self.font_size;
+
+        // Determine the ranges.
+        let max_len = self.series.iter().fold(0, |max, s| if s.len() > max { s.len() } else { max });
+        let last_idx = if max_len > 1 { (max_len - 1) as f64 } else { 1.0 };
+        let (min_x, max_x) = self.maybe_x_range.unwrap_or((0.0, last_idx));
+        let (min_y, max_y) = self.maybe_y_range.unwrap_or_else(|| auto_y_range(self.series));
+
+        // Make room for the tick labels.
+        let y_tick_strings: Vec<String> = tick_values(min_y, max_y, self.y_ticks).into_iter()
+            .map(|y| val_to_string(y, max_y, max_y - min_y, pad_dim[1] as usize))
+            .collect();
+        let y_tick_w = y_tick_strings.iter()
+            .fold(0.0, |max, s| { let w = label::width(uic, font_size, s); if w > max { w } else { max } });
+        let left_margin = if self.y_ticks > 0 { y_tick_w + TICK_PADDING * 2.0 } else { 0.0 };
+        let bottom_margin = if self.x_ticks > 0 { font_size as f64 + TICK_PADDING * 2.0 } else { 0.0 };
+        let area_pos = vec2_add(pad_pos, [left_margin, TICK_PADDING]);
+        let area_dim = [pad_dim[0] - left_margin - TICK_PADDING,
+                        pad_dim[1] - bottom_margin - TICK_PADDING];
+
+        // Draw rect.
+        rectangle::draw(uic.win_w, uic.win_h, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, color);
+
+        // If there's a label, draw it.
+        if let Some(l_text) = self.maybe_label {
+            let l_size = self.maybe_label_font_size.unwrap_or(uic.theme.font_size_medium);
+            let l_color = self.maybe_label_color.unwrap_or(uic.theme.label_color);
+            let l_w = label::width(uic, l_size, l_text);
+            let l_pos = [area_pos[0] + (area_dim[0] - l_w) / 2.0,
+                         area_pos[1] + (area_dim[1] - l_size as f64) / 2.0];
+            uic.draw_text(graphics, l_pos, l_size, l_color, l_text);
+        }
+
+        let draw_state = graphics::default_draw_state();
+        let transform = graphics::abs_transform(uic.win_w, uic.win_h);
+
+        // Draw the axes.
+        let Color(axis_col) = contrast;
+        let axis_line = graphics::Line::new(axis_col, 0.5);
+        let area_bottom = area_pos[1] + area_dim[1];
+        let area_right = area_pos[0] + area_dim[0];
+        axis_line.draw([area_pos[0], area_pos[1], area_pos[0], area_bottom],
+                       draw_state, transform, graphics);
+        axis_line.draw([area_pos[0], area_bottom, area_right, area_bottom],
+                       draw_state, transform, graphics);
+
+        // Draw the y ticks and their labels.
+        for (y, y_string) in tick_values(min_y, max_y, self.y_ticks).into_iter().zip(y_tick_strings.iter()) {
+            let tick_y = map_range(y, min_y, max_y, area_bottom, area_pos[1]);
+            axis_line.draw([area_pos[0] - TICK_PADDING, tick_y, area_pos[0], tick_y],
+                           draw_state, transform, graphics);
+            let w = label::width(uic, font_size, y_string);
+            let l_pos = [area_pos[0] - TICK_PADDING * 2.0 - w, tick_y - font_size as f64 / 2.0];
+            uic.draw_text(graphics, l_pos, font_size, contrast, y_string);
+        }
+
+        // Draw the x ticks and their labels.
+        for x in tick_values(min_x, max_x, self.x_ticks).into_iter() {
+            let tick_x = map_range(x, min_x, max_x, area_pos[0], area_right);
+            axis_line.draw([tick_x, area_bottom, tick_x, area_bottom + TICK_PADDING],
+                           draw_state, transform, graphics);
+            let x_string = val_to_string(x, max_x, max_x - min_x, area_dim[0] as usize);
+            let w = label::width(uic, font_size, &x_string);
+            let l_pos = [tick_x - w / 2.0, area_bottom + TICK_PADDING];
+            uic.draw_text(graphics, l_pos, font_size, contrast, &x_string);
+        }
+
+        // Draw each series.
+        let to_screen = |idx: usize, y: f64| -> Point {
+            [map_range(idx as f64, 0.0, last_idx, area_pos[0], area_right),
+             map_range(clamp(y, min_y, max_y), min_y, max_y, area_bottom, area_pos[1])]
+        };
+        let series_color = |i: usize| -> Color {
+            match self.maybe_series_colors {
+                Some(colors) if i < colors.len() => colors[i],
+                _ => contrast,
+            }
+        };
+        let line_style = self.maybe_line_style.or(uic.theme.line_style).unwrap_or(LineStyle::solid());
+        // Skip datapoints that won't be visible at lower quality tiers.
+        let points_per_px = match uic.quality_tier() {
+            Tier::High => None,
+            Tier::Medium => Some(1.0),
+            Tier::Low => Some(0.5),
+        };
+        let stride = match points_per_px {
+            Some(ppp) if area_dim[0] > 0.0 => {
+                let max_points = (area_dim[0] * ppp).max(2.0) as usize;
+                if max_len > max_points { max_len / max_points } else { 1 }
+            },
+            _ => 1,
+        };
+        for (i, s) in self.series.iter().enumerate() {
+            if s.len() < 2 { continue }
+            let s_color = series_color(i);
+            let last = s.len() - 1;
+            let points: Vec<Point> = s.iter().enumerate()
+                .filter(|&(j, _)| j % stride == 0 || j == last)
+                .map(|(j, &y)| to_screen(j, y))
+                .collect();
+            if self.fill {
+                let mut fill_color = s_color;
+                fill_color.set_a(s_color.a() * 0.35);
+                let mut area = points.clone();
+                area.push([points[points.len() - 1][0], area_bottom]);
+                area.push([points[0][0], area_bottom]);
+                primitive::polygon(uic.win_w, uic.win_h, graphics, &area, fill_color);
+            }
+            primitive::styled_polyline(uic.win_w, uic.win_h, graphics, &points,
+                                       self.line_width, &[s_color], line_style);
+        }
+
+        // Inspect the datapoint closest to the mouse.
+        if let State::Highlighted = new_state {
+            match closest_point(self.series, mouse.pos, area_pos, area_dim, max_len, min_y, max_y) {
+                Some((i, idx, p_pos)) => {
+                    let s_color = series_color(i);
+                    let quality = uic.circle_quality(None);
+                    primitive::circle_with_quality(uic.win_w, uic.win_h, graphics, p_pos,
+                                                   self.line_width * 2.0, s_color, quality);
+                    let x = map_range(idx as f64, 0.0, last_idx, min_x, max_x);
+                    let x_string = val_to_string(x, max_x, max_x - min_x, area_dim[0] as usize);
+                    let y_string = val_to_string(self.series[i][idx], max_y, max_y - min_y,
+                                                 area_dim[1] as usize);
+                    let xy_string = format!("{}, {}", x_string, y_string);
+                    let xy_string_w = label::value_width(uic, font_size, &xy_string);
+                    let xy_string_pos = match rectangle::corner(area_pos, p_pos, area_dim) {
+                        Corner::TopLeft => [p_pos[0], p_pos[1]],
+                        Corner::TopRight => [p_pos[0] - xy_string_w, p_pos[1]],
+                        Corner::BottomLeft => [p_pos[0], p_pos[1] - font_size as f64],
+                        Corner::BottomRight => [p_pos[0] - xy_string_w, p_pos[1] - font_size as f64],
+                    };
+                    uic.draw_value_text(graphics, xy_string_pos, font_size, contrast, &xy_string);
+                },
+                None => (),
+            }
+        }
+
+        set_state(uic, self.ui_id, Widget::Plot(new_state), self.pos, self.dim);
+
+    }
+}
@@ -1,7 +1,10 @@
-use piston::quack::{ Pair, Set, SetAt };
+use piston::quack::{ Get, GetFrom, Pair, Set, SetAt };
+use canvas::CanvasId;
 use point::Point;
 use ui_context::UIID;
+use shape::Size;
 use UiContext;
+use widget::Placing;
 use graphics::vecmath::Scalar;
 
 /// A trait that indicates whether or not a widget
@@ -17,15 +20,48 @@ pub trait Positionable {
     fn up_from<C>(self, ui_id: UIID, padding: f64, uic: &UiContext<C>) -> Self;
     fn left_from<C>(self, ui_id: UIID, padding: f64, uic: &UiContext<C>) -> Self;
     fn right_from<C>(self, ui_id: UIID, padding: f64, uic: &UiContext<C>) -> Self;
+    fn canvas<C>(self, canvas_id: CanvasId, offset: Point, uic: &UiContext<C>) -> Self;
+    fn right_of<C>(self, ui_id: UIID, padding: f64, uic: &UiContext<C>) -> Self;
+    fn below<C>(self, ui_id: UIID, padding: f64, uic: &UiContext<C>) -> Self;
+    fn align_left_with<C>(self, ui_id: UIID, uic: &UiContext<C>) -> Self;
+    fn align_top_with<C>(self, ui_id: UIID, uic: &UiContext<C>) -> Self;
+    fn pos_relative<C>(self, fraction: [f64; 2], uic: &UiContext<C>) -> Self;
+    fn pos_relative_to_canvas<C>(self, canvas_id: CanvasId, fraction: [f64; 2], uic: &UiContext<C>) -> Self;
+    fn anchor_left(self, margin: f64) -> Self;
+    fn anchor_top(self, margin: f64) -> Self;
+}
+
+/// A trait for positioning widgets relative to others where the widget's
+/// own dimensions must also be known.
+pub trait Alignable {
+    fn left_of<C>(self, ui_id: UIID, padding: f64, uic: &UiContext<C>) -> Self;
+    fn above<C>(self, ui_id: UIID, padding: f64, uic: &UiContext<C>) -> Self;
+    fn align_right_with<C>(self, ui_id: UIID, uic: &UiContext<C>) -> Self;
+    fn align_bottom_with<C>(self, ui_id: UIID, uic: &UiContext<C>) -> Self;
+    fn align_middle_x_with<C>(self, ui_id: UIID, uic: &UiContext<C>) -> Self;
+    fn align_middle_y_with<C>(self, ui_id: UIID, uic: &UiContext<C>) -> Self;
+    fn anchor_right<C>(self, margin: f64, uic: &UiContext<C>) -> Self;
+    fn anchor_bottom<C>(self, margin: f64, uic: &UiContext<C>) -> Self;
+    fn anchor_middle_x<C>(self, uic: &UiContext<C>) -> Self;
+    fn anchor_middle_y<C>(self, uic: &UiContext<C>) -> Self;
+    fn anchor_middle<C>(self, uic: &UiContext<C>) -> Self;
 }
 
 /// Position property.
 #[derive(Copy)]
 pub struct Position(pub [Scalar; 2]);
 
+/// Return the (x, y, w, h) of the widget with the given UIID.
+fn placed_rect<C>(ui_id: UIID, uic: &UiContext<C>) -> (f64, f64, f64, f64) {
+    match uic.get_placing(ui_id) {
+        Placing::Place(x, y, w, h) => (x, y, w, h),
+        Placing::NoPlace => (0.0, 0.0, 0.0, 0.0),
+    }
+}
+
 impl<T> Positionable for T
     where
-        (Position, T): Pair<Data = Position, Object = T> + SetAt
+        (Position, T): Pair<Data = Position, Object = T> + SetAt + GetFrom
 {
 
     #[inline]
@@ -79,4 +115,156 @@ impl<T> Positionable for T
         let (x, y) = uic.get_placing(uiid).right(padding);
         self.set(Position([x, y]))
     }
+
+    /// Position the widget at an offset relative to the top left of a canvas.
+    #[inline]
+    fn canvas<C>(self, canvas_id: CanvasId, offset: Point, uic: &UiContext<C>) -> Self {
+        match uic.get_canvas(canvas_id) {
+            Some(rect) => self.set(Position(rect.point(offset))),
+            None => self.set(Position(offset)),
+        }
+    }
+
+    /// Place the widget to the right of the given widget, aligned with its top.
+    #[inline]
+    fn right_of<C>(self, ui_id: UIID, padding: f64, uic: &UiContext<C>) -> Self {
+        self.right_from(ui_id, padding, uic)
+    }
+    /// Place the widget below the given widget, aligned with its left edge.
+    #[inline]
+    fn below<C>(self, ui_id: UIID, padding: f64, uic: &UiContext<C>) -> Self {
+        self.down_from(ui_id, padding, uic)
+    }
+    /// Align the widget's left edge with that of the given widget.
+    #[inline]
+    fn align_left_with<C>(self, ui_id: UIID, uic: &UiContext<C>) -> Self {
+        let (x, _, _, _) = placed_rect(ui_id, uic);
+        let Position(pos) = self.get();
+        self.set(Position([x, pos[1]]))
+    }
+    /// Align the widget's top edge with that of the given widget.
+    #[inline]
+    fn align_top_with<C>(self, ui_id: UIID, uic: &UiContext<C>) -> Self {
+        let (_, y, _, _) = placed_rect(ui_id, uic);
+        let Position(pos) = self.get();
+        self.set(Position([pos[0], y]))
+    }
+    /// Position the widget at a fraction of the window's dimensions.
+    #[inline]
+    fn pos_relative<C>(self, fraction: [f64; 2], uic: &UiContext<C>) -> Self {
+        self.set(Position([uic.win_w * fraction[0], uic.win_h * fraction[1]]))
+    }
+    /// Position the widget at a fraction of the given canvas' dimensions.
+    #[inline]
+    fn pos_relative_to_canvas<C>(self, canvas_id: CanvasId, fraction: [f64; 2], uic: &UiContext<C>) -> Self {
+        match uic.get_canvas(canvas_id) {
+            Some(rect) => self.set(Position(rect.point([rect.dim[0] * fraction[0],
+                                                         rect.dim[1] * fraction[1]]))),
+            None => self.pos_relative(fraction, uic),
+        }
+    }
+    /// Anchor the widget's left edge to the left of the window.
+    #[inline]
+    fn anchor_left(self, margin: f64) -> Self {
+        let Position(pos) = self.get();
+        self.set(Position([margin, pos[1]]))
+    }
+    /// Anchor the widget's top edge to the top of the window.
+    #[inline]
+    fn anchor_top(self, margin: f64) -> Self {
+        let Position(pos) = self.get();
+        self.set(Position([pos[0], margin]))
+    }
+}
+
+impl<T> Alignable for T
+    where
+        (Position, T): Pair<Data = Position, Object = T> + SetAt + GetFrom,
+        (Size, T): Pair<Data = Size, Object = T> + GetFrom
+{
+    /// Place the widget to the left of the given widget, aligned with its top.
+    #[inline]
+    fn left_of<C>(self, ui_id: UIID, padding: f64, uic: &UiContext<C>) -> Self {
+        let (x, y, _, _) = placed_rect(ui_id, uic);
+        let Size(dim) = self.get();
+        self.set(Position([x - padding - dim[0], y]))
+    }
+    /// Place the widget above the given widget, aligned with its left edge.
+    #[inline]
+    fn above<C>(self, ui_id: UIID, padding: f64, uic: &UiContext<C>) -> Self {
+        let (x, y, _, _) = placed_rect(ui_id, uic);
+        let Size(dim) = self.get();
+        self.set(Position([x, y - padding - dim[1]]))
+    }
+    /// Align the widget's right edge with that of the given widget.
+    #[inline]
+    fn align_right_with<C>(self, ui_id: UIID, uic: &UiContext<C>) -> Self {
+        let (x, _, w, _) = placed_rect(ui_id, uic);
+        let Size(dim) = self.get();
+        let Position(pos) = self.get();
+        self.set(Position([x + w - dim[0], pos[1]]))
+    }
+    /// Align the widget's bottom edge with that of the given widget.
+    #[inline]
+    fn align_bottom_with<C>(self, ui_id: UIID, uic: &UiContext<C>) -> Self {
+        let (_, y, _, h) = placed_rect(ui_id, uic);
+        let Size(dim) = self.get();
+        let Position(pos) = self.get();
+        self.set(Position([pos[0], y + h - dim[1]]))
+    }
+    /// Center the widget horizontally with the given widget.
+    #[inline]
+    fn align_middle_x_with<C>(self, ui_id: UIID, uic: &UiContext<C>) -> Self {
+        let (x, _, w, _) = placed_rect(ui_id, uic);
+        let Size(dim) = self.get();
+        let Position(pos) = self.get();
+        self.set(Position([x + (w - dim[0]) / 2.0, pos[1]]))
+    }
+    /// Center the widget vertically with the given widget.
+    #[inline]
+    fn align_middle_y_with<C>(self, ui_id: UIID, uic: &UiContext<C>) -> Self {
+        let (_, y, _, h) = placed_rect(ui_id, uic);
+        let Size(dim) = self.get();
+        let Position(pos) = self.get();
+        self.set(Position([pos[0], y + (h - dim[1]) / 2.0]))
+    }
+
+    // The anchors below are resolved against the window's current dimensions
+    // each time the widget is declared, so anchored widgets follow the window
+    // as it's resized. The widget's dimensions must be set first.
+
+    /// Anchor the widget's right edge to the right of the window.
+    #[inline]
+    fn anchor_right<C>(self, margin: f64, uic: &UiContext<C>) -> Self {
+        let Size(dim) = self.get();
+        let Position(pos) = self.get();
+        self.set(Position([uic.win_w - margin - dim[0], pos[1]]))
+    }
+    /// Anchor the widget's bottom edge to the bottom of the window, i.e. for
+    /// a status bar.
+    #[inline]
+    fn anchor_bottom<C>(self, margin: f64, uic: &UiContext<C>) -> Self {
+        let Size(dim) = self.get();
+        let Position(pos) = self.get();
+        self.set(Position([pos[0], uic.win_h - margin - dim[1]]))
+    }
+    /// Center the widget horizontally within the window.
+    #[inline]
+    fn anchor_middle_x<C>(self, uic: &UiContext<C>) -> Self {
+        let Size(dim) = self.get();
+        let Position(pos) = self.get();
+        self.set(Position([(uic.win_w - dim[0]) / 2.0, pos[1]]))
+    }
+    /// Center the widget vertically within the window.
+    #[inline]
+    fn anchor_middle_y<C>(self, uic: &UiContext<C>) -> Self {
+        let Size(dim) = self.get();
+        let Position(pos) = self.get();
+        self.set(Position([pos[0], (uic.win_h - dim[1]) / 2.0]))
+    }
+    /// Center the widget within the window.
+    #[inline]
+    fn anchor_middle<C>(self, uic: &UiContext<C>) -> Self {
+        self.anchor_middle_x(uic).anchor_middle_y(uic)
+    }
 }
@@ -0,0 +1,399 @@
+use std::f64::consts::PI;
+use std::num::Float;
+use color::Color;
+use graphics;
+use graphics::Graphics;
+use point::Point;
+
+/// The default number of segments used to approximate curves.
+pub static DEFAULT_SEGMENTS: usize = 32;
+
+/// The pattern with which a line is drawn.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum Pattern {
+    Solid,
+    /// Dashes of the first length separated by gaps of the second.
+    Dashed(f64, f64),
+}
+
+/// The way in which the end of a line is drawn.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum Cap {
+    Round,
+    /// An arrow head of the given length.
+    Arrow(f64),
+}
+
+/// Describes how a line should be drawn.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct LineStyle {
+    pub pattern: Pattern,
+    pub start_cap: Cap,
+    pub end_cap: Cap,
+}
+
+impl LineStyle {
+
+    /// A solid line with round caps.
+    pub fn solid() -> LineStyle {
+        LineStyle { pattern: Pattern::Solid, start_cap: Cap::Round, end_cap: Cap::Round }
+    }
+
+    /// A dashed line with round caps.
+    pub fn dashed(dash: f64, gap: f64) -> LineStyle {
+        LineStyle { pattern: Pattern::Dashed(dash, gap), ..LineStyle::solid() }
+    }
+
+    /// A builder method for specifying the start cap.
+    pub fn start_cap(self, cap: Cap) -> LineStyle {
+        LineStyle { start_cap: cap, ..self }
+    }
+
+    /// A builder method for specifying the end cap.
+    pub fn end_cap(self, cap: Cap) -> LineStyle {
+        LineStyle { end_cap: cap, ..self }
+    }
+
+}
+
+/// Draw a straight line of the given width between two points.
+pub fn line<B: Graphics>(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut B,
+    a: Point,
+    b: Point,
+    width: f64,
+    color: Color
+) {
+    graphics::Line::round(color.0, 0.5 * width)
+        .draw(
+            [a[0], a[1], b[0], b[1]],
+            graphics::default_draw_state(),
+            graphics::abs_transform(win_w, win_h),
+            graphics
+        );
+}
+
+/// Draw a series of connected lines through the given points.
+pub fn polyline<B: Graphics>(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut B,
+    points: &[Point],
+    width: f64,
+    color: Color
+) {
+    let line = graphics::Line::round(color.0, 0.5 * width);
+    let draw_state = graphics::default_draw_state();
+    let transform = graphics::abs_transform(win_w, win_h);
+    for i in 1..points.len() {
+        let (a, b) = (points[i - 1], points[i]);
+        line.draw([a[0], a[1], b[0], b[1]], draw_state, transform, graphics);
+    }
+}
+
+/// Draw a series of connected lines through the given points with the
+/// given style. Each segment is drawn with the next color in `colors`,
+/// cycling back to the first color if there are fewer colors than
+/// segments.
+pub fn styled_polyline<B: Graphics>(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut B,
+    points: &[Point],
+    width: f64,
+    colors: &[Color],
+    style: LineStyle
+) {
+    if points.len() < 2 || colors.len() == 0 { return }
+    let draw_state = graphics::default_draw_state();
+    let transform = graphics::abs_transform(win_w, win_h);
+    let (dash, gap) = match style.pattern {
+        Pattern::Dashed(dash, gap) if dash > 0.0 && gap > 0.0 => (dash, gap),
+        _ => (::std::f64::MAX, 0.0),
+    };
+    // The distance travelled into the current dash period.
+    let mut phase = 0.0;
+    for i in 1..points.len() {
+        let (a, b) = (points[i - 1], points[i]);
+        let line = graphics::Line::round(colors[(i - 1) % colors.len()].0, 0.5 * width);
+        let len = distance(a, b);
+        if len == 0.0 { continue }
+        let dir = [(b[0] - a[0]) / len, (b[1] - a[1]) / len];
+        let mut t = 0.0;
+        while t < len {
+            let is_dash = phase < dash;
+            let step = if is_dash { (dash - phase).min(len - t) }
+                       else { (dash + gap - phase).min(len - t) };
+            if is_dash {
+                line.draw([a[0] + dir[0] * t, a[1] + dir[1] * t,
+                           a[0] + dir[0] * (t + step), a[1] + dir[1] * (t + step)],
+                          draw_state, transform, graphics);
+            }
+            t += step;
+            phase += step;
+            if phase >= dash + gap { phase -= dash + gap }
+        }
+    }
+    let last = points.len() - 1;
+    draw_cap(win_w, win_h, graphics, points[1], points[0], width, colors[0], style.start_cap);
+    draw_cap(win_w, win_h, graphics, points[last - 1], points[last], width,
+             colors[(last - 1) % colors.len()], style.end_cap);
+}
+
+/// Draw the cap at the point `b` of the line from `a` to `b`.
+fn draw_cap<B: Graphics>(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut B,
+    a: Point,
+    b: Point,
+    width: f64,
+    color: Color,
+    cap: Cap
+) {
+    match cap {
+        // Round caps are drawn by the line itself.
+        Cap::Round => (),
+        Cap::Arrow(size) => {
+            let len = distance(a, b);
+            if len == 0.0 { return }
+            let dir = [(b[0] - a[0]) / len, (b[1] - a[1]) / len];
+            let normal = [-dir[1], dir[0]];
+            let half_w = (size + width) / 2.0;
+            let base = [b[0] - dir[0] * size, b[1] - dir[1] * size];
+            polygon(win_w, win_h, graphics, &[
+                b,
+                [base[0] + normal[0] * half_w, base[1] + normal[1] * half_w],
+                [base[0] - normal[0] * half_w, base[1] - normal[1] * half_w],
+            ], color);
+        },
+    }
+}
+
+/// The distance between two points.
+fn distance(a: Point, b: Point) -> f64 {
+    ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt()
+}
+
+/// Draw the outline of a closed polygon.
+pub fn polygon_outline<B: Graphics>(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut B,
+    points: &[Point],
+    width: f64,
+    color: Color
+) {
+    if points.len() < 2 { return }
+    polyline(win_w, win_h, graphics, points, width, color);
+    line(win_w, win_h, graphics, points[points.len() - 1], points[0], width, color);
+}
+
+/// Draw a filled polygon. The polygon may be concave but must not
+/// intersect itself.
+pub fn polygon<B: Graphics>(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut B,
+    points: &[Point],
+    color: Color
+) {
+    let polygon = graphics::Polygon::new(color.0);
+    let draw_state = graphics::default_draw_state();
+    let transform = graphics::abs_transform(win_w, win_h);
+    for triangle in tessellate(points).iter() {
+        polygon.draw(&triangle[..], draw_state, transform, graphics);
+    }
+}
+
+/// Describes how smoothly a circle should be drawn.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct CircleQuality {
+    /// The number of segments used to approximate the circle. If `None`,
+    /// the resolution is chosen from the circle's radius.
+    pub resolution: Option<u32>,
+    /// The number of half-pixel wide, increasingly transparent rings
+    /// drawn around the edge of the circle to smooth it.
+    pub smoothing: u32,
+}
+
+impl CircleQuality {
+
+    /// The default quality used for circles.
+    pub fn default() -> CircleQuality {
+        CircleQuality { resolution: None, smoothing: 1 }
+    }
+
+    /// The resolution to use for a circle of the given radius.
+    pub fn resolution_for(&self, radius: f64) -> u32 {
+        match self.resolution {
+            Some(resolution) => resolution,
+            None => {
+                let resolution = (radius * 2.0) as u32;
+                if resolution < 16 { 16 } else if resolution > 128 { 128 } else { resolution }
+            },
+        }
+    }
+
+}
+
+/// Draw a filled circle with the given center and radius.
+pub fn circle<B: Graphics>(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut B,
+    center: Point,
+    radius: f64,
+    color: Color
+) {
+    circle_with_quality(win_w, win_h, graphics, center, radius, color, CircleQuality::default());
+}
+
+/// Draw a filled circle with the given center, radius and quality.
+pub fn circle_with_quality<B: Graphics>(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut B,
+    center: Point,
+    radius: f64,
+    color: Color,
+    quality: CircleQuality
+) {
+    let draw_state = graphics::default_draw_state();
+    let transform = graphics::abs_transform(win_w, win_h);
+    let resolution = quality.resolution_for(radius);
+    let ellipse = |r: f64, color: Color, graphics: &mut B| {
+        graphics::Ellipse::new(color.0)
+            .resolution(resolution)
+            .draw([center[0] - r, center[1] - r, 2.0 * r, 2.0 * r],
+                  draw_state, transform, graphics);
+    };
+    // Draw the feathered edge from the outside in, then the opaque
+    // core on top.
+    for i in (0..quality.smoothing).rev() {
+        let step = (i + 1) as f64;
+        let mut feather_color = color;
+        feather_color.set_a(color.a() / (quality.smoothing + 1) as f32);
+        ellipse(radius + step * 0.5, feather_color, graphics);
+    }
+    ellipse(radius, color, graphics);
+}
+
+/// Draw an arc from the `start` angle to the `end` angle (in radians,
+/// clockwise from the positive x axis).
+pub fn arc<B: Graphics>(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut B,
+    center: Point,
+    radius: f64,
+    start: f64,
+    end: f64,
+    width: f64,
+    color: Color
+) {
+    let points = arc_points(center, radius, start, end, DEFAULT_SEGMENTS);
+    polyline(win_w, win_h, graphics, &points, width, color);
+}
+
+/// Draw a cubic bezier curve from `ctrl[0]` to `ctrl[3]`, using
+/// `ctrl[1]` and `ctrl[2]` as control points.
+pub fn bezier<B: Graphics>(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut B,
+    ctrl: [Point; 4],
+    width: f64,
+    color: Color
+) {
+    let points = bezier_points(ctrl, DEFAULT_SEGMENTS);
+    polyline(win_w, win_h, graphics, &points, width, color);
+}
+
+/// Return the points along an arc, approximated by the given number
+/// of segments.
+pub fn arc_points(center: Point, radius: f64, start: f64, end: f64, segments: usize) -> Vec<Point> {
+    let segments = if segments == 0 { 1 } else { segments };
+    (0..segments + 1).map(|i| {
+        let angle = start + (end - start) * i as f64 / segments as f64;
+        [center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]
+    }).collect()
+}
+
+/// Return the points along a full circle.
+pub fn circle_points(center: Point, radius: f64, segments: usize) -> Vec<Point> {
+    let mut points = arc_points(center, radius, 0.0, 2.0 * PI, segments);
+    points.pop();
+    points
+}
+
+/// Return the points along a cubic bezier curve, approximated by the
+/// given number of segments.
+pub fn bezier_points(ctrl: [Point; 4], segments: usize) -> Vec<Point> {
+    let segments = if segments == 0 { 1 } else { segments };
+    (0..segments + 1).map(|i| {
+        let t = i as f64 / segments as f64;
+        let u = 1.0 - t;
+        let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+        [a * ctrl[0][0] + b * ctrl[1][0] + c * ctrl[2][0] + d * ctrl[3][0],
+         a * ctrl[0][1] + b * ctrl[1][1] + c * ctrl[2][1] + d * ctrl[3][1]]
+    }).collect()
+}
+
+/// Twice the signed area of the given polygon.
+fn signed_area(points: &[Point]) -> f64 {
+    let n = points.len();
+    (0..n).fold(0.0, |acc, i| {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        acc + a[0] * b[1] - b[0] * a[1]
+    })
+}
+
+/// The z component of the cross product of `ab` and `bc`.
+fn cross(a: Point, b: Point, c: Point) -> f64 {
+    (b[0] - a[0]) * (c[1] - b[1]) - (b[1] - a[1]) * (c[0] - b[0])
+}
+
+/// Whether or not `p` lies within the triangle `abc`.
+fn is_in_triangle(p: Point, a: Point, b: Point, c: Point) -> bool {
+    let (d1, d2, d3) = (cross(a, b, p), cross(b, c, p), cross(c, a, p));
+    let has_neg = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+    let has_pos = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+    !(has_neg && has_pos)
+}
+
+/// Split a simple (non self-intersecting) polygon into triangles
+/// using ear clipping, so that it may be drawn by back ends that
+/// only support convex polygons.
+pub fn tessellate(points: &[Point]) -> Vec<[Point; 3]> {
+    let n = points.len();
+    if n < 3 { return Vec::new() }
+    let mut idxs: Vec<usize> = (0..n).collect();
+    if signed_area(points) < 0.0 { idxs.reverse() }
+    let mut triangles = Vec::with_capacity(n - 2);
+    while idxs.len() > 3 {
+        let len = idxs.len();
+        let maybe_ear = (0..len).find(|&i| {
+            let (ia, ib, ic) = (idxs[(i + len - 1) % len], idxs[i], idxs[(i + 1) % len]);
+            let (a, b, c) = (points[ia], points[ib], points[ic]);
+            cross(a, b, c) > 0.0 && !idxs.iter().any(|&j| {
+                j != ia && j != ib && j != ic && is_in_triangle(points[j], a, b, c)
+            })
+        });
+        match maybe_ear {
+            Some(i) => {
+                let (ia, ib, ic) = (idxs[(i + len - 1) % len], idxs[i], idxs[(i + 1) % len]);
+                triangles.push([points[ia], points[ib], points[ic]]);
+                idxs.remove(i);
+            },
+            // The polygon is degenerate (i.e. collinear points).
+            None => break,
+        }
+    }
+    if idxs.len() == 3 {
+        triangles.push([points[idxs[0]], points[idxs[1]], points[idxs[2]]]);
+    }
+    triangles
+}
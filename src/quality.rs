@@ -0,0 +1,109 @@
+
+/// The level of optional visual effects that should be drawn.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub enum Tier {
+    /// Optional effects are disabled (i.e. circle smoothing) and
+    /// large datasets are heavily decimated.
+    Low,
+    /// Large datasets are decimated to the available pixels.
+    Medium,
+    /// Everything is drawn in full.
+    High,
+}
+
+impl Tier {
+    /// The next lowest tier.
+    fn lower(&self) -> Tier {
+        match *self { Tier::High => Tier::Medium, _ => Tier::Low }
+    }
+    /// The next highest tier.
+    fn higher(&self) -> Tier {
+        match *self { Tier::Low => Tier::Medium, _ => Tier::High }
+    }
+}
+
+/// The weight given to the latest frame time in the running average.
+static SMOOTHING: f64 = 0.1;
+/// The number of consecutive frames over budget before lowering the tier.
+static DOWNGRADE_FRAMES: u32 = 30;
+/// The number of consecutive frames well under budget before raising the tier.
+static UPGRADE_FRAMES: u32 = 120;
+/// The fraction of the budget that the average frame time must fall
+/// below before the tier may be raised.
+static UPGRADE_THRESHOLD: f64 = 0.75;
+
+/// Monitors the time between frames and lowers the quality Tier when
+/// the frame budget is exceeded, raising it again once there is room.
+/// The tier only changes after the frame time has stayed over (or well
+/// under) budget for a number of frames, so that it doesn't flicker.
+#[derive(Copy, Clone, Debug)]
+pub struct Governor {
+    /// The target duration of a frame in seconds.
+    pub budget: f64,
+    /// Whether or not the tier should be adapted automatically.
+    pub enabled: bool,
+    tier: Tier,
+    avg_frame_time: f64,
+    frames_over: u32,
+    frames_under: u32,
+    maybe_last_frame: Option<f64>,
+}
+
+impl Governor {
+
+    /// Construct a Governor with the given frame budget in seconds.
+    pub fn new(budget: f64) -> Governor {
+        Governor {
+            budget: budget,
+            enabled: true,
+            tier: Tier::High,
+            avg_frame_time: budget,
+            frames_over: 0,
+            frames_under: 0,
+            maybe_last_frame: None,
+        }
+    }
+
+    /// The current quality tier.
+    pub fn tier(&self) -> Tier {
+        self.tier
+    }
+
+    /// Force the current quality tier.
+    pub fn set_tier(&mut self, tier: Tier) {
+        self.tier = tier;
+        self.frames_over = 0;
+        self.frames_under = 0;
+    }
+
+    /// The running average of the frame time in seconds.
+    pub fn avg_frame_time(&self) -> f64 {
+        self.avg_frame_time
+    }
+
+    /// Register the start of a new frame at the given time in seconds.
+    pub fn frame(&mut self, now: f64) {
+        if let Some(last) = self.maybe_last_frame {
+            let frame_time = now - last;
+            self.avg_frame_time += (frame_time - self.avg_frame_time) * SMOOTHING;
+        }
+        self.maybe_last_frame = Some(now);
+        if !self.enabled { return }
+        if self.avg_frame_time > self.budget {
+            self.frames_over += 1;
+            self.frames_under = 0;
+        } else if self.avg_frame_time < self.budget * UPGRADE_THRESHOLD {
+            self.frames_under += 1;
+            self.frames_over = 0;
+        } else {
+            self.frames_over = 0;
+            self.frames_under = 0;
+        }
+        if self.frames_over >= DOWNGRADE_FRAMES {
+            self.set_tier(self.tier.lower());
+        } else if self.frames_under >= UPGRADE_FRAMES {
+            self.set_tier(self.tier.higher());
+        }
+    }
+
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::num::Float;
+use color::Color;
+use ui_context::UIID;
+
+/// How a signal modulates the color of the widget it's bound to.
+#[derive(Copy, Clone, Debug)]
+pub enum Modulation {
+    /// Mix the color toward the given color by the signal times the depth.
+    Tint(Color, f32),
+    /// Brighten the color by the signal times the depth.
+    Brighten(f32),
+    /// Scale the color's alpha by the signal.
+    Fade,
+}
+
+impl Modulation {
+    /// Apply the modulation to a color given a signal from 0.0 to 1.0.
+    pub fn apply(&self, color: Color, signal: f32) -> Color {
+        let signal = if signal < 0.0 { 0.0 } else if signal > 1.0 { 1.0 } else { signal };
+        match *self {
+            Modulation::Tint(tint, depth) => color.mix(tint, signal * depth),
+            Modulation::Brighten(depth) => color.mix(Color::white(), signal * depth),
+            Modulation::Fade => {
+                let mut color = color;
+                let a = color.a();
+                color.set_a(a * signal);
+                color
+            },
+        }
+    }
+}
+
+/// Named analysis values (i.e. levels, FFT bands and beats) fed in by the
+/// application each frame, along with the widgets whose colors they
+/// modulate. This allows audio-reactive visuals to be wired up once rather
+/// than by recoloring widgets manually every frame.
+#[derive(Clone, Debug)]
+pub struct Signals {
+    /// The time in seconds over which a triggered signal decays to a
+    /// third of its value.
+    pub decay: f64,
+    values: HashMap<String, f32>,
+    triggered: HashMap<String, f32>,
+    bindings: Vec<(UIID, String, Modulation)>,
+    maybe_background: Option<(String, Modulation)>,
+    maybe_last_update: Option<f64>,
+    /// Whether a signal was fed a new value or triggered since the last update.
+    changed: bool,
+}
+
+impl Signals {
+
+    /// Construct an empty set of Signals.
+    pub fn new() -> Signals {
+        Signals {
+            decay: 0.15,
+            values: HashMap::new(),
+            triggered: HashMap::new(),
+            bindings: Vec::new(),
+            maybe_background: None,
+            maybe_last_update: None,
+            changed: false,
+        }
+    }
+
+    /// Set the current value of the named signal, i.e. a level or an FFT
+    /// band normalised to 0.0 .. 1.0.
+    pub fn feed(&mut self, name: &str, value: f32) {
+        if self.values.insert(name.to_string(), value) != Some(value) {
+            self.changed = true;
+        }
+    }
+
+    /// Trigger the named signal (i.e. on a beat), setting it to 1.0 after
+    /// which it decays back towards 0.0.
+    pub fn trigger(&mut self, name: &str) {
+        self.triggered.insert(name.to_string(), 1.0);
+        self.changed = true;
+    }
+
+    /// The current value of the named signal.
+    pub fn get(&self, name: &str) -> f32 {
+        let value = self.values.get(name).cloned().unwrap_or(0.0);
+        let triggered = self.triggered.get(name).cloned().unwrap_or(0.0);
+        if triggered > value { triggered } else { value }
+    }
+
+    /// Bind the named signal to modulate the color of the given widget.
+    pub fn bind(&mut self, ui_id: UIID, name: &str, modulation: Modulation) {
+        self.unbind(ui_id);
+        self.bindings.push((ui_id, name.to_string(), modulation));
+    }
+
+    /// Remove the given widget's binding (if any).
+    pub fn unbind(&mut self, ui_id: UIID) {
+        self.bindings.retain(|&(id, _, _)| id != ui_id);
+    }
+
+    /// Bind the named signal to modulate the background color.
+    pub fn bind_background(&mut self, name: &str, modulation: Modulation) {
+        self.maybe_background = Some((name.to_string(), modulation));
+    }
+
+    /// Return the given widget color with the widget's modulation applied.
+    pub fn modulate(&self, ui_id: UIID, color: Color) -> Color {
+        match self.bindings.iter().find(|&&(id, _, _)| id == ui_id) {
+            Some(&(_, ref name, modulation)) => modulation.apply(color, self.get(name)),
+            None => color,
+        }
+    }
+
+    /// Return the given background color with its modulation applied.
+    pub fn modulate_background(&self, color: Color) -> Color {
+        match self.maybe_background {
+            Some((ref name, modulation)) => modulation.apply(color, self.get(name)),
+            None => color,
+        }
+    }
+
+    /// Decay the triggered signals. This is called once per frame.
+    pub fn update(&mut self, now: f64) {
+        if let Some(last) = self.maybe_last_update {
+            let factor = (-(now - last) / self.decay).exp() as f32;
+            for value in self.triggered.values_mut() {
+                *value *= factor;
+            }
+        }
+        self.maybe_last_update = Some(now);
+        self.changed = false;
+    }
+
+    /// Whether any signal has changed since the last update or is still
+    /// decaying, in which case the widgets bound to it need redrawing.
+    pub fn is_active(&self) -> bool {
+        self.changed || self.triggered.values().any(|&value| value > 0.001)
+    }
+
+}
@@ -1,4 +1,5 @@
 
+use std::num::Float;
 use color::Color;
 use dimensions::Dimensions;
 use graphics;
@@ -19,6 +20,39 @@ pub enum State {
     Clicked,
 }
 
+/// A fill whose color varies across a rectangle.
+#[derive(Copy, Clone, Debug)]
+pub enum Gradient {
+    /// From the first color at the top to the second at the bottom.
+    Vertical(Color, Color),
+    /// From the first color at the left to the second at the right.
+    Horizontal(Color, Color),
+    /// From the first color at the center to the second at the edges.
+    Radial(Color, Color),
+}
+
+/// The number of bands of color with which a gradient is drawn.
+const GRADIENT_BANDS: usize = 24;
+
+impl Gradient {
+
+    /// A subtle vertical gradient around the given color, similar to the
+    /// buttons of native toolkits.
+    pub fn subtle(color: Color) -> Gradient {
+        Gradient::Vertical(color.lighten(0.06), color.darken(0.06))
+    }
+
+    /// Return the gradient with the function applied to both of its colors.
+    pub fn map<F: Fn(Color) -> Color>(self, f: F) -> Gradient {
+        match self {
+            Gradient::Vertical(a, b) => Gradient::Vertical(f(a), f(b)),
+            Gradient::Horizontal(a, b) => Gradient::Horizontal(f(a), f(b)),
+            Gradient::Radial(a, b) => Gradient::Radial(f(a), f(b)),
+        }
+    }
+
+}
+
 /// Draw a basic rectangle. The primary purpose
 /// of this is to be used as a building block for
 /// other widgets.
@@ -41,6 +75,69 @@ pub fn draw<B: Graphics>(
     draw_normal(draw_state, transform, graphics, state, pos, dim, f_width, color);
 }
 
+/// Draw a rectangle filled with the given gradient. Like `draw`, the
+/// gradient's colors are highlighted or clicked according to the state.
+pub fn draw_gradient<B: Graphics>(
+    win_w: f64,
+    win_h: f64,
+    graphics: &mut B,
+    state: State,
+    pos: Point,
+    dim: Dimensions,
+    maybe_frame: Option<(f64, Color)>,
+    gradient: Gradient
+) {
+    let draw_state = graphics::default_draw_state();
+    let transform = graphics::abs_transform(win_w, win_h);
+    if let Some((_, f_color)) = maybe_frame {
+        draw_frame(draw_state, transform, graphics, pos, dim, f_color)
+    }
+    let f_width = if let Some((f_width, _)) = maybe_frame { f_width } else { 0.0 };
+    let pos = [pos[0] + f_width, pos[1] + f_width];
+    let dim = [dim[0] - f_width * 2.0, dim[1] - f_width * 2.0];
+    let n = GRADIENT_BANDS;
+    match gradient.map(|color| state_color(state, color)) {
+        Gradient::Vertical(a, b) => for i in 0..n {
+            let Color(col) = a.mix(b, (i as f32 + 0.5) / n as f32);
+            let (y, h) = (pos[1] + dim[1] * i as f64 / n as f64, dim[1] / n as f64);
+            // Overlap the next band slightly so that no seams show.
+            let h = if i + 1 < n { h + 0.5 } else { h };
+            graphics::Rectangle::new(col)
+                .draw([pos[0], y, dim[0], h], draw_state, transform, graphics);
+        },
+        Gradient::Horizontal(a, b) => for i in 0..n {
+            let Color(col) = a.mix(b, (i as f32 + 0.5) / n as f32);
+            let (x, w) = (pos[0] + dim[0] * i as f64 / n as f64, dim[0] / n as f64);
+            let w = if i + 1 < n { w + 0.5 } else { w };
+            graphics::Rectangle::new(col)
+                .draw([x, pos[1], w, dim[1]], draw_state, transform, graphics);
+        },
+        // Concentric ellipses are drawn from the edge inwards over the
+        // outer color, which also fills the corners.
+        Gradient::Radial(a, b) => {
+            graphics::Rectangle::new(b.0)
+                .draw([pos[0], pos[1], dim[0], dim[1]], draw_state, transform, graphics);
+            for i in 0..n {
+                let scale = (n - i) as f64 / n as f64;
+                let Color(col) = a.mix(b, scale as f32);
+                let (w, h) = (dim[0] * scale, dim[1] * scale);
+                graphics::Ellipse::new(col)
+                    .draw([pos[0] + (dim[0] - w) / 2.0, pos[1] + (dim[1] - h) / 2.0, w, h],
+                          draw_state, transform, graphics);
+            }
+        },
+    }
+}
+
+/// Return the color adjusted for the given state.
+fn state_color(state: State, color: Color) -> Color {
+    match state {
+        State::Normal => color,
+        State::Highlighted => color.highlighted(),
+        State::Clicked => color.clicked(),
+    }
+}
+
 /// Draw the button border.
 fn draw_frame<B: Graphics>(
     draw_state: &DrawState,
@@ -71,11 +168,7 @@ fn draw_normal<B: Graphics>(
     frame_width: f64,
     color: Color
 ) {
-    let Color(col) = match state {
-        State::Normal => color,
-        State::Highlighted => color.highlighted(),
-        State::Clicked => color.clicked(),
-    };
+    let Color(col) = state_color(state, color);
     graphics::Rectangle::new(col)
         .draw([pos[0] + frame_width,
             pos[1] + frame_width,
@@ -98,6 +191,18 @@ pub fn is_over(pos: Point,
     else { false }
 }
 
+/// Return the smallest rectangle containing both rectangles.
+pub fn union(a: (Point, Dimensions), b: (Point, Dimensions)) -> (Point, Dimensions) {
+    let ((a_pos, a_dim), (b_pos, b_dim)) = (a, b);
+    let axis = |i: usize| {
+        let start = a_pos[i].min(b_pos[i]);
+        let end = (a_pos[i] + a_dim[i]).max(b_pos[i] + b_dim[i]);
+        (start, end - start)
+    };
+    let ((x, w), (y, h)) = (axis(0), axis(1));
+    ([x, y], [w, h])
+}
+
 /// Draw a label centered within a rect of given position and dimensions.
 pub fn draw_with_centered_label<B, C>(
     win_w: f64,
@@ -124,6 +229,24 @@ pub fn draw_with_centered_label<B, C>(
     }
     let f_width = if let Some((f_width, _)) = maybe_frame { f_width } else { 0.0 };
     draw_normal(draw_state, transform, graphics, state, pos, dim, f_width, color);
+    draw_centered_label(graphics, uic, pos, dim, text, font_size, text_color);
+}
+
+/// Draw a label centered within a rect of given position and dimensions,
+/// without drawing the rect.
+pub fn draw_centered_label<B, C>(
+    graphics: &mut B,
+    uic: &mut UiContext<C>,
+    pos: Point,
+    dim: Dimensions,
+    text: &str,
+    font_size: FontSize,
+    text_color: Color
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
     let text_w = label::width(uic, font_size, text);
     let l_pos = [pos[0] + (dim[0] - text_w) / 2.0, pos[1] + (dim[1] - font_size as f64) / 2.0];
     uic.draw_text(graphics, l_pos, font_size, text_color, text);
@@ -0,0 +1,97 @@
+use std::num::Float;
+use dimensions::Dimensions;
+use point::Point;
+
+/// The handle of a rectangle that is being dragged to resize it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Handle {
+    TopLeft,
+    Top,
+    TopRight,
+    Right,
+    BottomRight,
+    Bottom,
+    BottomLeft,
+    Left,
+}
+
+impl Handle {
+    /// The direction that each axis moves in when the handle is dragged
+    /// away from the rectangle (-1 for the near edge, 1 for the far edge,
+    /// 0 if the axis is unaffected).
+    fn axes(&self) -> (f64, f64) {
+        match *self {
+            Handle::TopLeft => (-1.0, -1.0),
+            Handle::Top => (0.0, -1.0),
+            Handle::TopRight => (1.0, -1.0),
+            Handle::Right => (1.0, 0.0),
+            Handle::BottomRight => (1.0, 1.0),
+            Handle::Bottom => (0.0, 1.0),
+            Handle::BottomLeft => (-1.0, 1.0),
+            Handle::Left => (-1.0, 0.0),
+        }
+    }
+}
+
+/// Modifiers that change how a rectangle responds to a resize drag.
+/// Conventionally Shift preserves the aspect ratio and Alt resizes about
+/// the center.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Modifiers {
+    pub keep_aspect: bool,
+    pub about_center: bool,
+}
+
+impl Modifiers {
+    /// No modifiers.
+    pub fn none() -> Modifiers {
+        Modifiers { keep_aspect: false, about_center: false }
+    }
+}
+
+/// Return the new position and dimensions of a rectangle (as they were
+/// when the drag began) after its handle has been dragged by `delta`.
+/// All resizable widgets should resize via this function so that the
+/// modifiers behave consistently.
+pub fn resize(handle: Handle,
+              pos: Point,
+              dim: Dimensions,
+              delta: [f64; 2],
+              min_dim: Dimensions,
+              modifiers: Modifiers) -> (Point, Dimensions) {
+    let (ax, ay) = handle.axes();
+    // When resizing about the center both edges move, doubling the change.
+    let scale = if modifiers.about_center { 2.0 } else { 1.0 };
+    let mut w = dim[0] + delta[0] * ax * scale;
+    let mut h = dim[1] + delta[1] * ay * scale;
+
+    if modifiers.keep_aspect && dim[0] > 0.0 && dim[1] > 0.0 {
+        let aspect = dim[0] / dim[1];
+        match (ax != 0.0, ay != 0.0) {
+            (true, false) => h = w / aspect,
+            (false, true) => w = h * aspect,
+            _ => {
+                // Follow whichever axis has changed the most proportionally.
+                if (w / dim[0] - 1.0).abs() > (h / dim[1] - 1.0).abs() { h = w / aspect }
+                else { w = h * aspect }
+            },
+        }
+        // Respect the minimum size without breaking the ratio.
+        if w < min_dim[0] { w = min_dim[0]; h = w / aspect }
+        if h < min_dim[1] { h = min_dim[1]; w = h * aspect }
+    } else {
+        if w < min_dim[0] { w = min_dim[0] }
+        if h < min_dim[1] { h = min_dim[1] }
+    }
+
+    let (dw, dh) = (w - dim[0], h - dim[1]);
+    let x = if modifiers.about_center { pos[0] - dw / 2.0 }
+            else if ax < 0.0 { pos[0] - dw }
+            else if ax == 0.0 && modifiers.keep_aspect { pos[0] - dw / 2.0 }
+            else { pos[0] };
+    let y = if modifiers.about_center { pos[1] - dh / 2.0 }
+            else if ay < 0.0 { pos[1] - dh }
+            else if ay == 0.0 && modifiers.keep_aspect { pos[1] - dh / 2.0 }
+            else { pos[1] };
+    ([x, y], [w, h])
+}
@@ -0,0 +1,58 @@
+//! Translates SDL2 events into conrod's Input, enabled by the `sdl2_backend`
+//! feature. Pass each event from SDL's event pump to `from_sdl2` and hand the
+//! result to `UiContext::handle_input`. Call `sdl2::keyboard::start_text_input`
+//! so that SDL reports text input. SDL only reports the window's size when it
+//! changes, so first pass the UiContext the input from `window_size`.
+
+use backend::{ Button, Input, Key, MouseButton };
+use sdl2::event::{ Event, WindowEventId };
+use sdl2::keycode::KeyCode;
+use sdl2::mouse::Mouse;
+use sdl2::video::Window;
+use std::num::FromPrimitive;
+
+/// Translate an SDL2 event into the Input it describes (if any).
+pub fn from_sdl2(event: &Event) -> Option<Input> {
+    match *event {
+        Event::Window { win_event_id: WindowEventId::Resized, data1, data2, .. } =>
+            Some(Input::Resize(data1 as u32, data2 as u32)),
+        Event::MouseMotion { x, y, .. } =>
+            Some(Input::Move(x as f64, y as f64)),
+        Event::MouseWheel { x, y, .. } =>
+            Some(Input::Scroll(x as f64, y as f64)),
+        Event::MouseButtonDown { mouse_btn, .. } =>
+            Some(Input::Press(Button::Mouse(mouse_button(mouse_btn)))),
+        Event::MouseButtonUp { mouse_btn, .. } =>
+            Some(Input::Release(Button::Mouse(mouse_button(mouse_btn)))),
+        Event::KeyDown { keycode, .. } =>
+            Some(Input::Press(Button::Keyboard(key(keycode)))),
+        Event::KeyUp { keycode, .. } =>
+            Some(Input::Release(Button::Keyboard(key(keycode)))),
+        Event::TextInput { ref text, .. } =>
+            Some(Input::Text(text.clone())),
+        _ => None,
+    }
+}
+
+/// The Resize input describing the window's current size.
+pub fn window_size(window: &Window) -> Input {
+    let (w, h) = window.get_size();
+    Input::Resize(w as u32, h as u32)
+}
+
+/// Map an SDL2 mouse button to piston's.
+pub fn mouse_button(button: Mouse) -> MouseButton {
+    match button {
+        Mouse::Left => MouseButton::Left,
+        Mouse::Right => MouseButton::Right,
+        Mouse::Middle => MouseButton::Middle,
+        Mouse::X1 => MouseButton::X1,
+        Mouse::X2 => MouseButton::X2,
+        _ => MouseButton::Unknown,
+    }
+}
+
+/// Map an SDL2 key code to piston's Key, whose values are SDL's key codes.
+pub fn key(keycode: KeyCode) -> Key {
+    FromPrimitive::from_i32(keycode as i32).unwrap_or(Key::Unknown)
+}
@@ -0,0 +1,211 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use mouse::Mouse;
+use point::Point;
+use rectangle;
+use ui_context::{
+    Id,
+    UIID,
+    UiContext,
+};
+use widget::{ DefaultWidgetState, Widget };
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use Callback;
+use FrameColor;
+use FrameWidth;
+use LabelColor;
+use LabelFontSize;
+use Position;
+use Size;
+use Visible;
+
+/// Represents the state of the SegmentedControl widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(usize),
+    Clicked(usize),
+}
+
+widget_fns!(SegmentedControl, State, Widget::SegmentedControl(State::Normal));
+
+/// Return the index of the segment under the mouse (if any).
+fn segment_under(pos: Point, dim: Dimensions, segment_w: f64, num_segments: usize, mouse_pos: Point) -> Option<usize> {
+    if num_segments == 0 || !rectangle::is_over(pos, mouse_pos, dim) { return None }
+    let idx = ((mouse_pos[0] - pos[0]) / segment_w).floor() as usize;
+    Some(if idx >= num_segments { num_segments - 1 } else { idx })
+}
+
+/// Check and return the current state of the SegmentedControl.
+fn get_new_state(maybe_over: Option<usize>, prev: State, mouse: Mouse) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Clicked};
+    match (maybe_over, prev, mouse.left) {
+        (Some(_),   Normal,         Down) => Normal,
+        (Some(idx), Highlighted(_), Down) => Clicked(idx),
+        (_,         Clicked(idx),   Down) => Clicked(idx),
+        (Some(idx), _,              Up)   => Highlighted(idx),
+        _                                 => Normal,
+    }
+}
+
+/// A row of mutually exclusive labeled segments, of which one is selected.
+/// This is a compact alternative to a DropDownList for a handful of options.
+pub struct SegmentedControl<'a, F> {
+    ui_id: UIID,
+    labels: &'a [&'a str],
+    selected: usize,
+    pos: Point,
+    visible: bool,
+    dim: Dimensions,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+    maybe_callback: Option<F>,
+}
+
+impl<'a, F> SegmentedControl<'a, F> {
+
+    /// Create a segmented control context with the given labels and
+    /// selected segment.
+    pub fn new(ui_id: UIID, labels: &'a [&'a str], selected: usize) -> SegmentedControl<'a, F> {
+        SegmentedControl {
+            ui_id: ui_id,
+            labels: labels,
+            selected: selected,
+            pos: [0.0, 0.0],
+            visible: true,
+            dim: [256.0, 32.0],
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+            maybe_callback: None,
+        }
+    }
+
+}
+
+quack! {
+    sc: SegmentedControl['a, F]
+    get:
+        fn () -> Size [] { Size(sc.dim) }
+        fn () -> Position [] { Position(sc.pos) }
+        fn () -> Visible [] { Visible(sc.visible) }
+        fn () -> DefaultWidgetState [] {
+            DefaultWidgetState(Widget::SegmentedControl(State::Normal))
+        }
+        fn () -> Id [] { Id(sc.ui_id) }
+    set:
+        fn (val: Color) [] { sc.maybe_color = Some(val) }
+        fn (val: Callback<F>) [where F: FnMut(usize) + 'a] {
+            sc.maybe_callback = Some(val.0)
+        }
+        fn (val: FrameColor) [] { sc.maybe_frame_color = Some(val.0) }
+        fn (val: FrameWidth) [] { sc.maybe_frame = Some(val.0) }
+        fn (val: LabelColor) [] { sc.maybe_label_color = Some(val.0) }
+        fn (val: LabelFontSize) [] { sc.maybe_label_font_size = Some(val.0) }
+        fn (val: Position) [] { sc.pos = val.0 }
+        fn (val: Visible) [] { sc.visible = val.0 }
+        fn (val: Size) [] { sc.dim = val.0 }
+    action:
+}
+
+impl<'a, F> SegmentedControl<'a, F>
+    where
+        F: FnMut(usize) + 'a
+{
+
+    /// Update the control's state, calling the callback (if any) and drawing
+    /// it. Return the index of the segment that was newly selected (if any).
+    fn update<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B) -> Option<usize>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if !self.visible { uic.keep_state(self.ui_id); return None }
+        let state = *get_state(uic, self.ui_id);
+        let mouse = uic.get_mouse_state();
+        let num_segments = self.labels.len();
+        let segment_w = if num_segments > 0 { self.dim[0] / num_segments as f64 } else { self.dim[0] };
+        let maybe_over = segment_under(self.pos, self.dim, segment_w, num_segments, mouse.pos);
+        let new_state = get_new_state(maybe_over, state, mouse);
+
+        // A segment is selected when it's released over having been pressed.
+        let maybe_selected = match (state, new_state) {
+            (State::Clicked(idx), State::Highlighted(over)) if idx == over && idx != self.selected => Some(idx),
+            _ => None,
+        };
+        if let Some(idx) = maybe_selected {
+            match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(idx), None => (),
+            }
+        }
+        let selected = maybe_selected.unwrap_or(self.selected);
+
+        // Draw the segments.
+        let color = uic.flash_color(self.ui_id, self.maybe_color.unwrap_or(uic.theme.shape_color));
+        let frame_w = self.maybe_frame.unwrap_or(uic.theme.frame_width);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(uic.theme.frame_color))),
+            false => None,
+        };
+        let text_color = self.maybe_label_color.unwrap_or(uic.theme.label_color);
+        let size = self.maybe_label_font_size.unwrap_or(uic.theme.font_size_medium);
+        for (i, label) in self.labels.iter().enumerate() {
+            let rect_state = match new_state {
+                _ if i == selected => rectangle::State::Clicked,
+                State::Highlighted(idx) | State::Clicked(idx) if idx == i => rectangle::State::Highlighted,
+                _ => rectangle::State::Normal,
+            };
+            let segment_pos = [self.pos[0] + i as f64 * segment_w, self.pos[1]];
+            rectangle::draw_with_centered_label(
+                uic.win_w, uic.win_h, graphics, uic, rect_state,
+                segment_pos, [segment_w, self.dim[1]], maybe_frame, color,
+                label, size, text_color
+            );
+        }
+
+        if let Some(label) = self.labels.get(selected) {
+            uic.tooltips.set_value(self.ui_id, label.to_string());
+        }
+        set_state(uic, self.ui_id, Widget::SegmentedControl(new_state), self.pos, self.dim);
+
+        maybe_selected
+    }
+
+}
+
+impl<'a> SegmentedControl<'a, fn(usize)> {
+
+    /// Draw the control and return the index of the segment that was newly
+    /// selected (if any). This is an alternative to providing a callback.
+    pub fn changed<B, C>(mut self, uic: &mut UiContext<C>, graphics: &mut B) -> Option<usize>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics)
+    }
+
+}
+
+impl<'a, F> ::draw::Drawable for SegmentedControl<'a, F>
+    where
+        F: FnMut(usize) + 'a
+{
+
+    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics);
+    }
+
+}
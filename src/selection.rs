@@ -0,0 +1,160 @@
+use std::num::Float;
+use dimensions::Dimensions;
+use point::Point;
+use ui_context::UIID;
+use widget::Placing;
+
+/// How a change to one selected widget is applied to the others.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BatchMode {
+    /// Other widgets are offset by the same amount.
+    Relative,
+    /// Other widgets are set to the same value.
+    Absolute,
+}
+
+/// A change to be applied to a selected widget, in terms of the
+/// percentage of the widget's range.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BatchEdit {
+    Offset(f32),
+    Set(f32),
+}
+
+impl BatchEdit {
+    /// Apply the edit to the given percentage, clamping to the widget's range.
+    pub fn apply(&self, perc: f32) -> f32 {
+        let perc = match *self {
+            BatchEdit::Offset(offset) => perc + offset,
+            BatchEdit::Set(perc) => perc,
+        };
+        if perc < 0.0 { 0.0 } else if perc > 1.0 { 1.0 } else { perc }
+    }
+}
+
+/// The set of selected value widgets that are edited together, along with
+/// the marquee used to select them.
+#[derive(Clone, Debug)]
+pub struct Selection {
+    /// Whether or not dragging over empty space draws a selection marquee.
+    pub enabled: bool,
+    /// How a change to one selected widget is applied to the others.
+    pub mode: BatchMode,
+    selected: Vec<UIID>,
+    selectable: Vec<UIID>,
+    maybe_marquee: Option<(Point, Point)>,
+    edits: Vec<(UIID, BatchEdit)>,
+}
+
+impl Selection {
+
+    /// Construct an empty, disabled Selection.
+    pub fn new() -> Selection {
+        Selection {
+            enabled: false,
+            mode: BatchMode::Relative,
+            selected: Vec::new(),
+            selectable: Vec::new(),
+            maybe_marquee: None,
+            edits: Vec::new(),
+        }
+    }
+
+    /// Register a widget as one that may be selected via the marquee.
+    pub fn set_selectable(&mut self, ui_id: UIID) {
+        if !self.selectable.contains(&ui_id) { self.selectable.push(ui_id) }
+    }
+
+    /// The currently selected widgets.
+    pub fn selected(&self) -> &[UIID] {
+        &self.selected
+    }
+
+    /// Whether or not the given widget is selected.
+    pub fn is_selected(&self, ui_id: UIID) -> bool {
+        self.selected.contains(&ui_id)
+    }
+
+    /// Replace the selection.
+    pub fn select(&mut self, ids: &[UIID]) {
+        self.selected = ids.to_vec();
+        self.edits.clear();
+    }
+
+    /// Clear the selection.
+    pub fn clear(&mut self) {
+        self.select(&[]);
+    }
+
+    /// The corners of the marquee if one is being dragged.
+    pub fn marquee(&self) -> Option<(Point, Point)> {
+        self.maybe_marquee
+    }
+
+    /// Begin dragging a marquee from the given point.
+    pub fn begin_marquee(&mut self, p: Point) {
+        self.maybe_marquee = Some((p, p));
+    }
+
+    /// Drag the marquee's corner to the given point.
+    pub fn drag_marquee(&mut self, p: Point) {
+        if let Some((start, _)) = self.maybe_marquee {
+            self.maybe_marquee = Some((start, p));
+        }
+    }
+
+    /// Finish the marquee, selecting every selectable widget that it touches.
+    pub fn end_marquee(&mut self, placings: &[(UIID, Placing)]) {
+        let (a, b) = match self.maybe_marquee.take() {
+            Some(marquee) => marquee,
+            None => return,
+        };
+        let (pos, dim) = marquee_rect(a, b);
+        let selected: Vec<UIID> = placings.iter().filter_map(|&(ui_id, placing)| match placing {
+            Placing::Place(x, y, w, h) if self.selectable.contains(&ui_id)
+                && x < pos[0] + dim[0] && x + w > pos[0]
+                && y < pos[1] + dim[1] && y + h > pos[1] => Some(ui_id),
+            _ => None,
+        }).collect();
+        self.select(&selected);
+    }
+
+    /// Report that the user changed the given widget's value from one
+    /// percentage of its range to another. If the widget is selected the
+    /// change is queued for every other selected widget.
+    pub fn propagate(&mut self, ui_id: UIID, old_perc: f32, new_perc: f32) {
+        if !self.is_selected(ui_id) { return }
+        let edit = match self.mode {
+            BatchMode::Relative => BatchEdit::Offset(new_perc - old_perc),
+            BatchMode::Absolute => BatchEdit::Set(new_perc),
+        };
+        for &other in self.selected.iter().filter(|&&id| id != ui_id) {
+            match self.edits.iter_mut().find(|&&mut (id, _)| id == other) {
+                Some(&mut (_, ref mut pending)) => {
+                    *pending = match (*pending, edit) {
+                        (BatchEdit::Offset(a), BatchEdit::Offset(b)) => BatchEdit::Offset(a + b),
+                        _ => edit,
+                    };
+                    continue;
+                },
+                None => (),
+            }
+            self.edits.push((other, edit));
+        }
+    }
+
+    /// Take the edit queued for the given widget (if any).
+    pub fn take_edit(&mut self, ui_id: UIID) -> Option<BatchEdit> {
+        match self.edits.iter().position(|&(id, _)| id == ui_id) {
+            Some(idx) => Some(self.edits.remove(idx).1),
+            None => None,
+        }
+    }
+
+}
+
+/// Return the top left and dimensions of the rectangle between two corners.
+pub fn marquee_rect(a: Point, b: Point) -> (Point, Dimensions) {
+    let pos = [a[0].min(b[0]), a[1].min(b[1])];
+    ([pos[0], pos[1]], [(a[0] - b[0]).abs(), (a[1] - b[1]).abs()])
+}
@@ -1,5 +1,7 @@
 use piston::quack::{ GetFrom, Get, Pair, Set, SetAt };
+use canvas::CanvasId;
 use dimensions::Dimensions;
+use UiContext;
 
 /// A trait that indicates whether or not a widget
 /// builder is positionable.
@@ -8,6 +10,8 @@ pub trait Shapeable {
     fn dim(self, dim: Dimensions) -> Self;
     fn width(self, width: f64) -> Self;
     fn height(self, height: f64) -> Self;
+    fn dim_relative<C>(self, fraction: [f64; 2], uic: &UiContext<C>) -> Self;
+    fn dim_relative_to_canvas<C>(self, canvas_id: CanvasId, fraction: [f64; 2], uic: &UiContext<C>) -> Self;
 }
 
 /// Size property.
@@ -36,4 +40,17 @@ impl<T> Shapeable for T
         let Size(size) = self.get();
         self.set(Size([size[0], height]))
     }
+    /// Size the widget as a fraction of the window's dimensions.
+    #[inline]
+    fn dim_relative<C>(self, fraction: [f64; 2], uic: &UiContext<C>) -> Self {
+        self.set(Size([uic.win_w * fraction[0], uic.win_h * fraction[1]]))
+    }
+    /// Size the widget as a fraction of the given canvas' dimensions.
+    #[inline]
+    fn dim_relative_to_canvas<C>(self, canvas_id: CanvasId, fraction: [f64; 2], uic: &UiContext<C>) -> Self {
+        match uic.get_canvas(canvas_id) {
+            Some(rect) => self.set(Size([rect.dim[0] * fraction[0], rect.dim[1] * fraction[1]])),
+            None => self.dim_relative(fraction, uic),
+        }
+    }
 }
@@ -0,0 +1,68 @@
+use graphics::character::{ Character, CharacterCache };
+use label::FontSize;
+use std::cell::{ RefCell, RefMut };
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::rc::Rc;
+
+/// A handle to a glyph cache shared between several UiContexts, i.e. one per
+/// window of a multi-window application, so that each font is loaded and
+/// its glyphs rasterized only once. Every UiContext keeps its own widget
+/// state, UIIDs and theme; only the cache is shared.
+///
+/// Each UiContext is constructed with (or adds) a clone of the handle. The
+/// windows' graphics contexts must share textures for the glyphs to be drawn
+/// in every window.
+///
+/// Each handle keeps its own copy of the glyphs it has used, cloned from the
+/// shared cache, so the glyph textures should be cheap to clone (i.e. handles
+/// to a texture rather than the texture data itself).
+pub struct Shared<C: CharacterCache> {
+    cache: Rc<RefCell<C>>,
+    glyphs: HashMap<(FontSize, char), Character<C::Texture>>,
+}
+
+impl<C: CharacterCache> Clone for Shared<C> {
+    fn clone(&self) -> Shared<C> {
+        Shared { cache: self.cache.clone(), glyphs: HashMap::new() }
+    }
+}
+
+impl<C: CharacterCache> Shared<C> {
+
+    /// Share the given glyph cache.
+    pub fn new(cache: C) -> Shared<C> {
+        Shared { cache: Rc::new(RefCell::new(cache)), glyphs: HashMap::new() }
+    }
+
+    /// Mutably borrow the shared cache, i.e. to preload glyphs.
+    pub fn borrow_mut(&self) -> RefMut<C> {
+        self.cache.borrow_mut()
+    }
+
+}
+
+impl<C> CharacterCache for Shared<C>
+    where
+        C: CharacterCache,
+        C::Texture: Clone,
+{
+    type Texture = C::Texture;
+
+    fn character(&mut self, size: FontSize, ch: char) -> &Character<C::Texture> {
+        // The shared cache is only borrowed while the glyph is cloned, so the
+        // returned reference never outlives the RefCell's borrow.
+        match self.glyphs.entry((size, ch)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let mut cache = self.cache.borrow_mut();
+                let character = cache.character(size, ch);
+                entry.insert(Character {
+                    offset: character.offset,
+                    size: character.size,
+                    texture: character.texture.clone(),
+                })
+            },
+        }
+    }
+}
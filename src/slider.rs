@@ -1,14 +1,19 @@
 use std::num::Float;
 use std::num::ToPrimitive;
 use std::num::FromPrimitive;
+use axis;
 use color::Color;
+use density::DisplayDensity;
 use dimensions::Dimensions;
 use label;
 use mouse::Mouse;
 use graphics::Graphics;
 use graphics::character::CharacterCache;
+use keyboard;
 use point::Point;
+use primitive;
 use rectangle;
+use rectangle::Gradient;
 use ui_context::{
     Id,
     UIID,
@@ -18,6 +23,7 @@ use utils::{
     clamp,
     percentage,
     value_from_perc,
+    ValueFormatter,
 };
 use widget::{ DefaultWidgetState, Widget };
 use vecmath::vec2_add;
@@ -29,6 +35,7 @@ use LabelColor;
 use LabelFontSize;
 use Position;
 use Size;
+use Visible;
 
 /// Represents the state of the Button widget.
 #[derive(PartialEq, Clone, Copy)]
@@ -51,6 +58,23 @@ impl State {
 
 widget_fns!(Slider, State, Widget::Slider(State::Normal));
 
+/// The fraction of the slider's range moved per step of the scroll wheel.
+const SCROLL_STEP: f32 = 0.01;
+
+/// The values of `n` ticks spread evenly across the range, including both ends.
+fn even_ticks(min: f64, max: f64, n: usize) -> Vec<f64> {
+    if n < 2 { return Vec::new() }
+    (0..n).map(|i| min + (max - min) * i as f64 / (n - 1) as f64).collect()
+}
+
+/// The tick closest to the value.
+fn nearest_tick(value: f64, ticks: &[f64]) -> Option<f64> {
+    ticks.iter().fold(None, |maybe_nearest, &tick| match maybe_nearest {
+        Some(nearest) if (nearest - value).abs() <= (tick - value).abs() => Some(nearest),
+        _ => Some(tick),
+    })
+}
+
 /// Check the current state of the slider.
 fn get_new_state(is_over: bool,
                  prev: State,
@@ -73,6 +97,7 @@ pub struct Slider<'a, T, F> {
     min: T,
     max: T,
     pos: Point,
+    visible: bool,
     dim: Dimensions,
     maybe_callback: Option<F>,
     maybe_color: Option<Color>,
@@ -81,6 +106,68 @@ pub struct Slider<'a, T, F> {
     maybe_label: Option<&'a str>,
     maybe_label_color: Option<Color>,
     maybe_label_font_size: Option<u32>,
+    maybe_density: Option<DisplayDensity>,
+    maybe_default_value: Option<T>,
+    maybe_gradient: Option<Gradient>,
+    maybe_value_formatter: Option<ValueFormatter<'a>>,
+    maybe_ticks: Option<usize>,
+    maybe_tick_values: Option<&'a [f64]>,
+    tick_labels: bool,
+    snap_to_ticks: bool,
+}
+
+impl<'a, T, F> Slider<'a, T, F> {
+    /// Set the amount of detail to display. The label is hidden when Compact.
+    #[inline]
+    pub fn density(self, density: DisplayDensity) -> Slider<'a, T, F> {
+        Slider { maybe_density: Some(density), ..self }
+    }
+
+    /// Set the value to which the slider is reset when Ctrl-clicked or
+    /// double-clicked.
+    #[inline]
+    pub fn default_value(self, value: T) -> Slider<'a, T, F> {
+        Slider { maybe_default_value: Some(value), ..self }
+    }
+
+    /// Fill the slider's track with the given gradient rather than a flat color.
+    #[inline]
+    pub fn gradient(self, gradient: Gradient) -> Slider<'a, T, F> {
+        Slider { maybe_gradient: Some(gradient), ..self }
+    }
+
+    /// Format the value shown in the slider's readout.
+    #[inline]
+    pub fn value_formatter<G>(self, formatter: G) -> Slider<'a, T, F>
+        where G: Fn(f64) -> String + 'a
+    {
+        Slider { maybe_value_formatter: Some(Box::new(formatter)), ..self }
+    }
+
+    /// Mark `n` ticks spread evenly along the track, including both ends.
+    #[inline]
+    pub fn ticks(self, n: usize) -> Slider<'a, T, F> {
+        Slider { maybe_ticks: Some(n), ..self }
+    }
+
+    /// Mark ticks at the given values along the track, which may be unevenly
+    /// spaced. These take precedence over `ticks`.
+    #[inline]
+    pub fn tick_values(self, values: &'a [f64]) -> Slider<'a, T, F> {
+        Slider { maybe_tick_values: Some(values), ..self }
+    }
+
+    /// Label each tick with its value. Labels are hidden when Compact.
+    #[inline]
+    pub fn tick_labels(self, labels: bool) -> Slider<'a, T, F> {
+        Slider { tick_labels: labels, ..self }
+    }
+
+    /// Snap the dragged value to the nearest tick.
+    #[inline]
+    pub fn snap_to_ticks(self, snap: bool) -> Slider<'a, T, F> {
+        Slider { snap_to_ticks: snap, ..self }
+    }
 }
 
 impl<'a, T, F> Slider<'a, T, F> {
@@ -92,6 +179,7 @@ impl<'a, T, F> Slider<'a, T, F> {
             min: min,
             max: max,
             pos: [0.0, 0.0],
+            visible: true,
             dim: [192.0, 48.0],
             maybe_callback: None,
             maybe_color: None,
@@ -100,6 +188,14 @@ impl<'a, T, F> Slider<'a, T, F> {
             maybe_label: None,
             maybe_label_color: None,
             maybe_label_font_size: None,
+            maybe_density: None,
+            maybe_default_value: None,
+            maybe_gradient: None,
+            maybe_value_formatter: None,
+            maybe_ticks: None,
+            maybe_tick_values: None,
+            tick_labels: false,
+            snap_to_ticks: false,
         }
     }
 }
@@ -108,6 +204,8 @@ quack! {
     slider: Slider['a, T, F]
     get:
         fn () -> Size [] { Size(slider.dim) }
+        fn () -> Position [] { Position(slider.pos) }
+        fn () -> Visible [] { Visible(slider.visible) }
         fn () -> DefaultWidgetState [] {
             DefaultWidgetState(Widget::Slider(State::Normal))
         }
@@ -123,40 +221,84 @@ quack! {
         fn (val: LabelColor) [] { slider.maybe_label_color = Some(val.0) }
         fn (val: LabelFontSize) [] { slider.maybe_label_font_size = Some(val.0) }
         fn (val: Position) [] { slider.pos = val.0 }
+        fn (val: Visible) [] { slider.visible = val.0 }
         fn (val: Size) [] { slider.dim = val.0 }
     action:
 }
 
-impl<'a, T, F> ::draw::Drawable for Slider<'a, T, F>
+impl<'a, T, F> Slider<'a, T, F>
     where
         T: Float + FromPrimitive + ToPrimitive,
         F: FnMut(T) + 'a
 {
 
-    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+    /// Update the slider's state, calling the callback (if any) and drawing
+    /// it. Return the new value if it has changed or been grabbed/released.
+    fn update<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B) -> Option<T>
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
-
+        if !self.visible { uic.keep_state(self.ui_id); return None }
         let state = *get_state(uic, self.ui_id);
         let mouse = uic.get_mouse_state();
-        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let is_over = uic.is_over(self.ui_id, self.pos, self.dim, mouse.pos);
+
+        // Apply any change made to another widget selected for batch editing
+        // or linked to this one. `self.value` remains the declared value, so
+        // that these changes are compared against it below like the user's.
+        uic.selection.set_selectable(self.ui_id);
+        let maybe_selection_edit = uic.selection.take_edit(self.ui_id);
+        let maybe_link_edit = uic.links().take_edit(self.ui_id);
+        let maybe_batch_value = if maybe_selection_edit.is_some() || maybe_link_edit.is_some() {
+            let perc = percentage(self.value, self.min, self.max);
+            let perc = maybe_selection_edit.map_or(perc, |edit| edit.apply(perc));
+            let perc = maybe_link_edit.map_or(perc, |edit| edit.apply(perc));
+            Some(value_from_perc(perc, self.min, self.max))
+        } else {
+            // Otherwise apply any change reverted via the history.
+            uic.history.take_revert(self.ui_id).and_then(FromPrimitive::from_f64)
+        };
+        let is_reverting = maybe_batch_value.is_some()
+            && maybe_selection_edit.is_none() && maybe_link_edit.is_none();
+        let current_value = maybe_batch_value.unwrap_or(self.value);
+
         let new_state = get_new_state(is_over, state, mouse);
 
         let frame_w = self.maybe_frame.unwrap_or(uic.theme.frame_width);
         let frame_w2 = frame_w * 2.0;
         let frame_color = self.maybe_frame_color.unwrap_or(uic.theme.frame_color);
 
+        // Shift-dragging moves the slider finely.
+        let drag_pos = match new_state {
+            State::Clicked => uic.fine_pointer(self.ui_id, mouse.pos),
+            _ => { uic.reset_pointer_smoothing(self.ui_id); mouse.pos },
+        };
+
+        // Ctrl-clicking or double-clicking resets the value to its default (if
+        // any) rather than dragging, and the scroll wheel nudges the value
+        // while hovering.
+        let is_resetting = (uic.get_modifier_keys().contains(keyboard::CTRL) || mouse.clicks >= 2)
+            && self.maybe_default_value.is_some();
+        let value = match (is_over, new_state, mouse.scroll[0] + mouse.scroll[1]) {
+            (true, State::Clicked, _) if is_resetting && state == State::Highlighted =>
+                self.maybe_default_value.unwrap(),
+            (true, State::Highlighted, scroll) if scroll != 0.0 => {
+                let perc = clamp(percentage(current_value, self.min, self.max) + scroll as f32 * SCROLL_STEP, 0.0, 1.0);
+                value_from_perc(perc, self.min, self.max)
+            },
+            _ => current_value,
+        };
+
         let is_horizontal = self.dim[0] > self.dim[1];
         let (new_value, pad_pos, pad_dim) = if is_horizontal {
             // Horizontal.
             let p = vec2_add(self.pos, [frame_w, frame_w]);
             let max_w = self.dim[0] - frame_w2;
             let w = match (is_over, state, new_state) {
-                (true, State::Highlighted, State::Clicked) | (_, State::Clicked, State::Clicked)  =>
-                     clamp(mouse.pos[0] - p[0], 0f64, max_w),
-                _ => clamp(percentage(self.value, self.min, self.max) as f64 * max_w, 0f64, max_w),
+                (true, State::Highlighted, State::Clicked) | (_, State::Clicked, State::Clicked) if !is_resetting =>
+                     clamp(drag_pos[0] - p[0], 0f64, max_w),
+                _ => clamp(percentage(value, self.min, self.max) as f64 * max_w, 0f64, max_w),
             };
             let h = self.dim[1] - frame_w2;
             let new_value = value_from_perc((w / max_w) as f32, self.min, self.max);
@@ -167,13 +309,13 @@ impl<'a, T, F> ::draw::Drawable for Slider<'a, T, F>
             let corner = vec2_add(self.pos, [frame_w, frame_w]);
             let y_max = corner[1] + max_h;
             let (h, p) = match (is_over, state, new_state) {
-                (true, State::Highlighted, State::Clicked) | (_, State::Clicked, State::Clicked) => {
-                    let p = [corner[0], clamp(mouse.pos[1], corner[1], y_max)];
+                (true, State::Highlighted, State::Clicked) | (_, State::Clicked, State::Clicked) if !is_resetting => {
+                    let p = [corner[0], clamp(drag_pos[1], corner[1], y_max)];
                     let h = clamp(max_h - (p[1] - corner[1]), 0.0, max_h);
                     (h, p)
                 },
                 _ => {
-                    let h = clamp(percentage(self.value, self.min, self.max) as f64 * max_h, 0.0, max_h);
+                    let h = clamp(percentage(value, self.min, self.max) as f64 * max_h, 0.0, max_h);
                     let p = [corner[0], corner[1] + max_h - h];
                     (h, p)
                 },
@@ -183,29 +325,125 @@ impl<'a, T, F> ::draw::Drawable for Slider<'a, T, F>
             (new_value, p, [w, h])
         };
 
+        // Snap dragged values to the nearest tick, moving the slider with them.
+        // An empty range has nowhere to place ticks.
+        let (min_f, max_f) = (self.min.to_f64().unwrap(), self.max.to_f64().unwrap());
+        let ticks = match self.maybe_tick_values {
+            _ if min_f == max_f => Vec::new(),
+            Some(values) => values.to_vec(),
+            None => even_ticks(min_f, max_f, self.maybe_ticks.unwrap_or(0)),
+        };
+        let snapped = match nearest_tick(new_value.to_f64().unwrap(), &ticks) {
+            Some(tick) if self.snap_to_ticks && new_state == State::Clicked && !is_resetting =>
+                FromPrimitive::from_f64(tick),
+            _ => None,
+        };
+        let (new_value, pad_pos, pad_dim) = match snapped {
+            Some(snapped) => {
+                let perc = clamp(percentage(snapped, self.min, self.max) as f64, 0.0, 1.0);
+                let corner = vec2_add(self.pos, [frame_w, frame_w]);
+                let (max_w, max_h) = (self.dim[0] - frame_w2, self.dim[1] - frame_w2);
+                if is_horizontal { (snapped, corner, [max_w * perc, max_h]) }
+                else { (snapped, [corner[0], corner[1] + max_h * (1.0 - perc)], [max_w, max_h * perc]) }
+            },
+            None => (new_value, pad_pos, pad_dim),
+        };
+
+        uic.set_dragging(self.ui_id, state == State::Clicked, new_state == State::Clicked);
+
+        // Share the change with the other selected and linked widgets, other
+        // than those it came from, and record it unless it's a revert (which
+        // the history records itself).
+        if self.value != new_value {
+            let old_perc = percentage(self.value, self.min, self.max);
+            let new_perc = percentage(new_value, self.min, self.max);
+            if maybe_selection_edit.is_none() {
+                uic.selection.propagate(self.ui_id, old_perc, new_perc);
+            }
+            if maybe_link_edit.is_none() {
+                uic.links().propagate(self.ui_id, old_perc, new_perc);
+            }
+            if !is_reverting {
+                uic.history.record(self.ui_id,
+                                   self.value.to_f64().unwrap(),
+                                   new_value.to_f64().unwrap(),
+                                   uic.time());
+            }
+        }
+        if state == State::Clicked && new_state != State::Clicked {
+            uic.history.finish(self.ui_id);
+        }
+
+        // A change of value doesn't change the state, so damage the slider here.
+        if self.value != new_value { uic.damage(self.pos, self.dim) }
+
         // Callback.
-        match self.maybe_callback {
-            Some(ref mut callback) => {
-                if self.value != new_value || match (state, new_state) {
-                    (State::Highlighted, State::Clicked) | (State::Clicked, State::Highlighted) => true,
-                    _ => false,
-                } { (*callback)(new_value) }
-            }, None => (),
+        let changed = self.value != new_value || match (state, new_state) {
+            (State::Highlighted, State::Clicked) | (State::Clicked, State::Highlighted) => true,
+            _ => false,
+        };
+        if changed {
+            match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(new_value),
+                None => (),
+            }
         }
 
         // Draw.
         let rect_state = new_state.as_rectangle_state();
-        let color = self.maybe_color.unwrap_or(uic.theme.shape_color);
+        let color = uic.flash_color(self.ui_id, self.maybe_color.unwrap_or(uic.theme.shape_color));
 
         // Rectangle frame / backdrop.
         rectangle::draw(uic.win_w, uic.win_h, graphics, rect_state,
                         self.pos, self.dim, None, frame_color);
         // Slider rectangle.
-        rectangle::draw(uic.win_w, uic.win_h, graphics, rect_state,
-                        pad_pos, pad_dim, None, color);
+        match self.maybe_gradient {
+            Some(gradient) => {
+                let gradient = gradient.map(|color| uic.flash_color(self.ui_id, color));
+                rectangle::draw_gradient(uic.win_w, uic.win_h, graphics, rect_state,
+                                         pad_pos, pad_dim, None, gradient)
+            },
+            None => rectangle::draw(uic.win_w, uic.win_h, graphics, rect_state,
+                                    pad_pos, pad_dim, None, color),
+        }
+
+        // Ticks, marked across the edge of the track opposite the label.
+        let density = self.maybe_density.or(uic.theme.display_density).unwrap_or(DisplayDensity::Normal);
+        if ticks.len() > 0 {
+            let tick_color = color.plain_contrast().with_alpha(0.5);
+            let corner = vec2_add(self.pos, [frame_w, frame_w]);
+            let (max_w, max_h) = (self.dim[0] - frame_w2, self.dim[1] - frame_w2);
+            let font_size = uic.theme.font_size_small;
+            for &tick in ticks.iter() {
+                if tick < min_f.min(max_f) || tick > min_f.max(max_f) { continue }
+                let perc = (tick - min_f) / (max_f - min_f);
+                let (a, b, label_pos) = if is_horizontal {
+                    let x = corner[0] + max_w * perc;
+                    let y = corner[1] + max_h;
+                    ([x, y - max_h / 4.0], [x, y], [x + 2.0, y - max_h / 4.0 - font_size as f64])
+                } else {
+                    let y = corner[1] + max_h * (1.0 - perc);
+                    let x = corner[0] + max_w;
+                    ([x - max_w / 4.0, y], [x, y], [corner[0] + 2.0, y - font_size as f64])
+                };
+                primitive::line(uic.win_w, uic.win_h, graphics, a, b, 1.0, tick_color);
+                if self.tick_labels && density != DisplayDensity::Compact {
+                    let pixels = if is_horizontal { max_w } else { max_h };
+                    let tick_string = match self.maybe_value_formatter {
+                        Some(ref formatter) => formatter(tick),
+                        None => axis::tick_label(tick, min_f, max_f, pixels),
+                    };
+                    uic.draw_value_text(graphics, label_pos, font_size, tick_color, &tick_string);
+                }
+            }
+        }
 
         // If there's a label, draw it.
-        if let Some(text) = self.maybe_label {
+        let maybe_label = match density {
+            DisplayDensity::Compact => None,
+            _ => self.maybe_label,
+        };
+        if let Some(text) = maybe_label {
             let text_color = self.maybe_label_color.unwrap_or(uic.theme.label_color);
             let size = self.maybe_label_font_size.unwrap_or(uic.theme.font_size_medium);
             let is_horizontal = self.dim[0] > self.dim[1];
@@ -223,7 +461,47 @@ impl<'a, T, F> ::draw::Drawable for Slider<'a, T, F>
             uic.draw_text(graphics, l_pos, size, text_color, &text);
         }
 
+        let value_string = match self.maybe_value_formatter {
+            Some(ref formatter) => formatter(new_value.to_f64().unwrap()),
+            None => format!("{:.2}", new_value.to_f64().unwrap()),
+        };
+        uic.tooltips.set_value(self.ui_id, value_string);
         set_state(uic, self.ui_id, Widget::Slider(new_state), self.pos, self.dim);
 
+        if changed { Some(new_value) } else { None }
     }
+
+}
+
+impl<'a, T> Slider<'a, T, fn(T)>
+    where
+        T: Float + FromPrimitive + ToPrimitive
+{
+
+    /// Draw the slider and return its new value if it has changed. This is
+    /// an alternative to providing a callback.
+    pub fn changed<B, C>(mut self, uic: &mut UiContext<C>, graphics: &mut B) -> Option<T>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics)
+    }
+
+}
+
+impl<'a, T, F> ::draw::Drawable for Slider<'a, T, F>
+    where
+        T: Float + FromPrimitive + ToPrimitive,
+        F: FnMut(T) + 'a
+{
+
+    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics);
+    }
+
 }
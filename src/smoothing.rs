@@ -0,0 +1,51 @@
+use point::Point;
+
+/// A filter applied to the pointer position while dragging a widget in
+/// order to tame jittery input devices such as touchscreens and tablets.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Smoothing {
+    /// Exponential moving average where the given factor (0.0 to 1.0)
+    /// is the weight given to each new sample. Lower is smoother.
+    Exponential(f64),
+    /// The median of the last N samples, taken per axis.
+    Median(usize),
+}
+
+impl Smoothing {
+
+    /// Add the given sample to the history and return the filtered point.
+    /// The history should be cleared when the drag ends.
+    pub fn apply(&self, samples: &mut Vec<Point>, p: Point) -> Point {
+        match *self {
+            Smoothing::Exponential(factor) => {
+                let factor = if factor < 0.0 { 0.0 } else if factor > 1.0 { 1.0 } else { factor };
+                let smoothed = match samples.last() {
+                    Some(prev) => [prev[0] + (p[0] - prev[0]) * factor,
+                                   prev[1] + (p[1] - prev[1]) * factor],
+                    None => p,
+                };
+                samples.clear();
+                samples.push(smoothed);
+                smoothed
+            },
+            Smoothing::Median(n) => {
+                samples.push(p);
+                let n = if n == 0 { 1 } else { n };
+                while samples.len() > n {
+                    samples.remove(0);
+                }
+                [median(samples.iter().map(|s| s[0]).collect()),
+                 median(samples.iter().map(|s| s[1]).collect())]
+            },
+        }
+    }
+
+}
+
+/// Return the median of the given values.
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 { (values[mid - 1] + values[mid]) / 2.0 }
+    else { values[mid] }
+}
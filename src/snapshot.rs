@@ -0,0 +1,148 @@
+use dimensions::Dimensions;
+use point::Point;
+use rectangle;
+use std::fmt::{ Display, Formatter, Error };
+use ui_context::{
+    UIID,
+    UiContext,
+};
+use widget::Placing;
+
+/// A single rendered frame stored as rows of RGBA8 pixels,
+/// starting from the top left of the window.
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Snapshot {
+
+    /// Construct a Snapshot from a buffer of RGBA8 pixels.
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Result<Snapshot, String> {
+        let expected_len = width as usize * height as usize * 4;
+        if pixels.len() != expected_len {
+            return Err(format!("Expected {} bytes for a {}x{} Snapshot but found {}.",
+                               expected_len, width, height, pixels.len()));
+        }
+        Ok(Snapshot { width: width, height: height, pixels: pixels })
+    }
+
+    /// Return the RGBA channels of the pixel at the given position.
+    #[inline]
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let idx = (y as usize * self.width as usize + x as usize) * 4;
+        [self.pixels[idx], self.pixels[idx + 1], self.pixels[idx + 2], self.pixels[idx + 3]]
+    }
+
+}
+
+/// A rectangular region of the window that should be ignored
+/// when comparing two Snapshots (i.e. a blinking cursor).
+#[derive(Copy, Clone, Debug)]
+pub struct Region {
+    pub pos: Point,
+    pub dim: Dimensions,
+}
+
+/// The pixels that differ between two Snapshots.
+#[derive(Clone, Debug)]
+pub struct Diff {
+    /// The positions of every differing pixel.
+    pub pixels: Vec<(u32, u32)>,
+    /// The greatest difference found in any single channel.
+    pub max_delta: u8,
+}
+
+/// Compare two Snapshots pixel by pixel. Pixels whose channels all
+/// differ by no more than `tolerance`, or which lie within one of the
+/// `ignored` regions, are considered equal.
+pub fn diff(a: &Snapshot, b: &Snapshot, tolerance: u8, ignored: &[Region]) -> Result<Diff, String> {
+    if a.width != b.width || a.height != b.height {
+        return Err(format!("Cannot diff a {}x{} Snapshot against a {}x{} Snapshot.",
+                           a.width, a.height, b.width, b.height));
+    }
+    let mut pixels = Vec::new();
+    let mut max_delta = 0u8;
+    for y in 0..a.height {
+        for x in 0..a.width {
+            let (pa, pb) = (a.pixel(x, y), b.pixel(x, y));
+            let delta = (0..4).fold(0u8, |max, i| {
+                let d = if pa[i] > pb[i] { pa[i] - pb[i] } else { pb[i] - pa[i] };
+                if d > max { d } else { max }
+            });
+            if delta <= tolerance { continue }
+            let p = [x as f64 + 0.5, y as f64 + 0.5];
+            if ignored.iter().any(|r| rectangle::is_over(r.pos, p, r.dim)) { continue }
+            if delta > max_delta { max_delta = delta }
+            pixels.push((x, y));
+        }
+    }
+    Ok(Diff { pixels: pixels, max_delta: max_delta })
+}
+
+impl Diff {
+
+    /// Whether or not the two Snapshots matched.
+    pub fn is_empty(&self) -> bool {
+        self.pixels.len() == 0
+    }
+
+    /// Return the bounding box of all differing pixels.
+    pub fn bounds(&self) -> Option<(Point, Dimensions)> {
+        if self.is_empty() { return None }
+        let (mut min_x, mut min_y) = (::std::u32::MAX, ::std::u32::MAX);
+        let (mut max_x, mut max_y) = (0u32, 0u32);
+        for &(x, y) in self.pixels.iter() {
+            if x < min_x { min_x = x }
+            if y < min_y { min_y = y }
+            if x > max_x { max_x = x }
+            if y > max_y { max_y = y }
+        }
+        Some(([min_x as f64, min_y as f64],
+              [(max_x - min_x + 1) as f64, (max_y - min_y + 1) as f64]))
+    }
+
+    /// Return the UIIDs of all widgets (according to the rects stored in
+    /// the UiContext) that contain at least one differing pixel, along
+    /// with the number of differing pixels within each.
+    pub fn differing_widgets<C>(&self, uic: &UiContext<C>) -> Vec<(UIID, usize)> {
+        uic.get_placings().into_iter().filter_map(|(ui_id, placing)| match placing {
+            Placing::Place(x, y, w, h) => {
+                let count = self.pixels.iter().filter(|&&(px, py)| {
+                    rectangle::is_over([x, y], [px as f64 + 0.5, py as f64 + 0.5], [w, h])
+                }).count();
+                if count > 0 { Some((ui_id, count)) } else { None }
+            },
+            Placing::NoPlace => None,
+        }).collect()
+    }
+
+    /// Produce a human readable report of the Diff, listing each
+    /// widget that differs.
+    pub fn report<C>(&self, uic: &UiContext<C>) -> String {
+        let mut report = self.to_string();
+        for (ui_id, count) in self.differing_widgets(uic).into_iter() {
+            let (x, y, w, h) = match uic.get_placing(ui_id) {
+                Placing::Place(x, y, w, h) => (x, y, w, h),
+                Placing::NoPlace => continue,
+            };
+            report.push_str(&format!("\n    UIID {}: {} pixels differ within [{}, {}, {}, {}]",
+                                     ui_id, count, x, y, w, h));
+        }
+        report
+    }
+
+}
+
+impl Display for Diff {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
+        match self.bounds() {
+            None => write!(fmt, "Snapshots match."),
+            Some((pos, dim)) => write!(fmt, "{} pixels differ (max channel delta {}) within [{}, {}, {}, {}].",
+                                       self.pixels.len(), self.max_delta,
+                                       pos[0], pos[1], dim[0], dim[1]),
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use mouse::Mouse;
+use point::Point;
+use primitive;
+use rectangle;
+use ui_context::{
+    Id,
+    UIID,
+    UiContext,
+};
+use widget::{ DefaultWidgetState, Widget };
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use Callback;
+use FrameColor;
+use FrameWidth;
+use LabelColor;
+use LabelFontSize;
+use Position;
+use Size;
+use Visible;
+
+/// The distance the mouse must move while pressing a tab before it is dragged.
+const DRAG_THRESHOLD: f64 = 4.0;
+
+/// The distance above or below the headers that a tab must be dragged
+/// before releasing it detaches the tab.
+const DETACH_DISTANCE: f64 = 32.0;
+
+/// The fraction of the remaining distance that the insertion indicator
+/// moves towards its target each frame.
+const INDICATOR_EASING: f64 = 0.35;
+
+/// Represents the state of the Tabs widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(usize),
+    /// The tab at the index was pressed at the point.
+    Clicked(usize, Point),
+    /// The tab at the index is being dragged. The insertion indicator is
+    /// currently drawn at the given x position.
+    Dragging(usize, f64),
+}
+
+widget_fns!(Tabs, State, Widget::Tabs(State::Normal));
+
+/// The events that may occur while interacting with Tabs.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Event {
+    /// The tab at the index was clicked.
+    Selected(usize),
+    /// The tab at the first index was dragged to the second index. The
+    /// titles should be reordered to match.
+    Reordered(usize, usize),
+    /// The tab at the index was dragged away from the headers and released
+    /// at the point. The application may move its content into a floating
+    /// panel, i.e. a canvas placed via `UiContext::dock_canvas`.
+    Detached(usize, Point),
+}
+
+/// Return the index of the tab under the mouse (if any).
+fn tab_under(pos: Point, dim: Dimensions, tab_w: f64, num_tabs: usize, mouse_pos: Point) -> Option<usize> {
+    if num_tabs == 0 || !rectangle::is_over(pos, mouse_pos, dim) { return None }
+    let idx = ((mouse_pos[0] - pos[0]) / tab_w).floor() as usize;
+    Some(if idx >= num_tabs { num_tabs - 1 } else { idx })
+}
+
+/// Return the index at which a tab dragged to the given x position would
+/// be inserted.
+fn insertion_idx(pos: Point, tab_w: f64, num_tabs: usize, x: f64) -> usize {
+    let idx = ((x - pos[0]) / tab_w + 0.5).floor();
+    if idx < 0.0 { 0 } else if idx as usize > num_tabs { num_tabs } else { idx as usize }
+}
+
+/// Whether or not the given point is far enough above or below the
+/// headers for a dragged tab to be detached.
+fn is_detaching(pos: Point, dim: Dimensions, p: Point) -> bool {
+    p[1] < pos[1] - DETACH_DISTANCE || p[1] > pos[1] + dim[1] + DETACH_DISTANCE
+}
+
+/// Check and return the current state of the Tabs.
+fn get_new_state(maybe_over: Option<usize>, prev: State, mouse: Mouse) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Clicked, Dragging};
+    match (maybe_over, prev, mouse.left) {
+        (_,          Clicked(idx, start), Down) => {
+            if (mouse.pos[0] - start[0]).abs() > DRAG_THRESHOLD
+            || (mouse.pos[1] - start[1]).abs() > DRAG_THRESHOLD { Dragging(idx, start[0]) }
+            else { Clicked(idx, start) }
+        },
+        (_,          Dragging(idx, x),    Down) => Dragging(idx, x),
+        (Some(idx),  Highlighted(_),      Down) => Clicked(idx, mouse.pos),
+        (Some(idx),  _,                   Up)   => Highlighted(idx),
+        _                                       => Normal,
+    }
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct Tabs<'a, F> {
+    ui_id: UIID,
+    titles: &'a [&'a str],
+    selected: usize,
+    detachable: bool,
+    persist: bool,
+    pos: Point,
+    visible: bool,
+    dim: Dimensions,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+    maybe_label_color: Option<Color>,
+    maybe_label_font_size: Option<u32>,
+    maybe_callback: Option<F>,
+}
+
+impl<'a, F> Tabs<'a, F> {
+
+    /// Create a tabs context with the given titles and selected tab.
+    pub fn new(ui_id: UIID, titles: &'a [&'a str], selected: usize) -> Tabs<'a, F> {
+        Tabs {
+            ui_id: ui_id,
+            titles: titles,
+            selected: selected,
+            detachable: false,
+            persist: false,
+            pos: [0.0, 0.0],
+            visible: true,
+            dim: [384.0, 32.0],
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+            maybe_callback: None,
+        }
+    }
+
+    /// Allow tabs to be dragged away from the headers to be detached.
+    pub fn detachable(self, detachable: bool) -> Tabs<'a, F> {
+        Tabs { detachable: detachable, ..self }
+    }
+
+    /// Save the selected tab between sessions via `UiContext::save_persisted`.
+    /// The restored tab is selected the first time the tabs are drawn.
+    pub fn persist(self, persist: bool) -> Tabs<'a, F> {
+        Tabs { persist: persist, ..self }
+    }
+
+}
+
+quack! {
+    tabs: Tabs['a, F]
+    get:
+        fn () -> Size [] { Size(tabs.dim) }
+        fn () -> Position [] { Position(tabs.pos) }
+        fn () -> Visible [] { Visible(tabs.visible) }
+        fn () -> DefaultWidgetState [] {
+            DefaultWidgetState(Widget::Tabs(State::Normal))
+        }
+        fn () -> Id [] { Id(tabs.ui_id) }
+    set:
+        fn (val: Color) [] { tabs.maybe_color = Some(val) }
+        fn (val: Callback<F>) [where F: FnMut(Event) + 'a] {
+            tabs.maybe_callback = Some(val.0)
+        }
+        fn (val: FrameColor) [] { tabs.maybe_frame_color = Some(val.0) }
+        fn (val: FrameWidth) [] { tabs.maybe_frame = Some(val.0) }
+        fn (val: LabelColor) [] { tabs.maybe_label_color = Some(val.0) }
+        fn (val: LabelFontSize) [] { tabs.maybe_label_font_size = Some(val.0) }
+        fn (val: Position) [] { tabs.pos = val.0 }
+        fn (val: Visible) [] { tabs.visible = val.0 }
+        fn (val: Size) [] { tabs.dim = val.0 }
+    action:
+}
+
+impl<'a, F> Tabs<'a, F>
+    where
+        F: FnMut(Event) + 'a
+{
+
+    /// Update the tabs' state, calling the callback (if any) and drawing
+    /// them. Return the event that occurred (if any).
+    fn update<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if !self.visible { uic.keep_state(self.ui_id); return None }
+        let state = *get_state(uic, self.ui_id);
+        let mouse = uic.get_mouse_state();
+        let num_tabs = self.titles.len();
+        let tab_w = if num_tabs > 0 { self.dim[0] / num_tabs as f64 } else { self.dim[0] };
+        let maybe_over = tab_under(self.pos, self.dim, tab_w, num_tabs, mouse.pos);
+        let new_state = get_new_state(maybe_over, state, mouse);
+
+        // Ease the insertion indicator towards the slot under the mouse. The
+        // drag is cancelled if its tab has since been removed.
+        let new_state = match new_state {
+            State::Dragging(idx, _) if self.titles.get(idx).is_none() => State::Normal,
+            State::Dragging(idx, x) => {
+                let insert = insertion_idx(self.pos, tab_w, num_tabs, mouse.pos[0]);
+                let target_x = self.pos[0] + insert as f64 * tab_w;
+                State::Dragging(idx, x + (target_x - x) * INDICATOR_EASING)
+            },
+            _ => new_state,
+        };
+        let is_detaching = self.detachable && is_detaching(self.pos, self.dim, mouse.pos);
+
+        // Determine the event from the change in state. A restored tab is
+        // selected as though it were clicked.
+        let mut restored = self.selected;
+        if self.persist { uic.persist(self.ui_id, &mut restored) }
+        let maybe_event = match (state, new_state) {
+            _ if restored != self.selected && restored < num_tabs => Some(Event::Selected(restored)),
+            (State::Clicked(idx, _), State::Highlighted(over)) if idx == over => Some(Event::Selected(idx)),
+            (State::Dragging(idx, _), State::Highlighted(_))
+            | (State::Dragging(idx, _), State::Normal) if idx < num_tabs => {
+                if is_detaching { Some(Event::Detached(idx, mouse.pos)) }
+                else {
+                    let insert = insertion_idx(self.pos, tab_w, num_tabs, mouse.pos[0]);
+                    let to = if insert > idx { insert - 1 } else { insert };
+                    if to != idx { Some(Event::Reordered(idx, to)) } else { None }
+                }
+            },
+            _ => None,
+        };
+        if let Some(event) = maybe_event {
+            match self.maybe_callback {
+                Some(ref mut callback) => (*callback)(event), None => (),
+            }
+        }
+        let was_dragging = match state { State::Dragging(_, _) => true, _ => false };
+        let is_dragging = match new_state { State::Dragging(_, _) => true, _ => false };
+        uic.set_dragging(self.ui_id, was_dragging, is_dragging);
+
+        // Draw the headers.
+        let color = uic.flash_color(self.ui_id, self.maybe_color.unwrap_or(uic.theme.shape_color));
+        let frame_w = self.maybe_frame.unwrap_or(uic.theme.frame_width);
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, self.maybe_frame_color.unwrap_or(uic.theme.frame_color))),
+            false => None,
+        };
+        let text_color = self.maybe_label_color.unwrap_or(uic.theme.label_color);
+        let size = self.maybe_label_font_size.unwrap_or(uic.theme.font_size_medium);
+        for (i, title) in self.titles.iter().enumerate() {
+            let rect_state = match new_state {
+                _ if i == self.selected => rectangle::State::Clicked,
+                State::Highlighted(idx) | State::Clicked(idx, _) if idx == i => rectangle::State::Highlighted,
+                _ => rectangle::State::Normal,
+            };
+            let tab_pos = [self.pos[0] + i as f64 * tab_w, self.pos[1]];
+            rectangle::draw_with_centered_label(
+                uic.win_w, uic.win_h, graphics, uic, rect_state,
+                tab_pos, [tab_w, self.dim[1]], maybe_frame, color,
+                title, size, text_color
+            );
+        }
+
+        // Draw the dragged tab beneath the mouse along with the insertion
+        // indicator, which is hidden while the tab would be detached.
+        let maybe_dragged = match new_state {
+            State::Dragging(idx, x) => self.titles.get(idx).map(|title| (x, *title)),
+            _ => None,
+        };
+        if let Some((x, title)) = maybe_dragged {
+            let mut ghost_color = color.highlighted();
+            ghost_color.set_a(0.6);
+            let ghost_pos = [mouse.pos[0] - tab_w / 2.0, mouse.pos[1] - self.dim[1] / 2.0];
+            rectangle::draw_with_centered_label(
+                uic.win_w, uic.win_h, graphics, uic, rectangle::State::Normal,
+                ghost_pos, [tab_w, self.dim[1]], maybe_frame, ghost_color,
+                title, size, text_color
+            );
+            if !is_detaching {
+                primitive::line(uic.win_w, uic.win_h, graphics, [x, self.pos[1]],
+                                [x, self.pos[1] + self.dim[1]], 2.0, color.plain_contrast());
+            }
+        }
+
+        set_state(uic, self.ui_id, Widget::Tabs(new_state), self.pos, self.dim);
+
+        maybe_event
+    }
+
+}
+
+impl<'a> Tabs<'a, fn(Event)> {
+
+    /// Draw the tabs and return the event that occurred (if any). This is
+    /// an alternative to providing a callback.
+    pub fn event<B, C>(mut self, uic: &mut UiContext<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics)
+    }
+
+}
+
+impl<'a, F> ::draw::Drawable for Tabs<'a, F>
+    where
+        F: FnMut(Event) + 'a
+{
+
+    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics);
+    }
+
+}
@@ -1,23 +1,26 @@
 use color::Color;
 use dimensions::Dimensions;
-use graphics;
+use font::FontId;
 use graphics::{
     Graphics,
 };
 use graphics::character::CharacterCache;
 use label;
-use label::FontSize;
+use label::{ Align, FontSize };
 use mouse::Mouse;
 use piston::input::keyboard::Key::{
     Backspace,
+    Delete,
+    End,
+    Home,
     Left,
     Right,
     Return,
 };
 use point::Point;
+use primitive;
 use rectangle;
 use std::num::Float;
-use clock_ticks::precise_time_s;
 use ui_context::{
     Id,
     UIID,
@@ -30,11 +33,15 @@ use vecmath::{
 use widget::{ DefaultWidgetState, Widget };
 use std::cmp;
 use Callback;
+use Font;
 use FrameColor;
 use FrameWidth;
 use Position;
 use Size;
+use Visible;
 
+/// The index of a grapheme cluster within the text, so that the cursor
+/// never splits a multi-byte character or a character from its accents.
 pub type Idx = usize;
 pub type CursorX = f64;
 
@@ -55,6 +62,8 @@ pub enum DrawState {
 pub enum Capturing {
     Uncaptured,
     Captured(Idx, CursorX),
+    /// Captured with the text between the two indices selected.
+    Selected(Idx, Idx),
 }
 
 /// Represents an element of the TextBox widget.
@@ -70,7 +79,7 @@ impl State {
     fn as_rectangle_state(&self) -> rectangle::State {
         match self {
             &State(state, capturing) => match capturing {
-                Capturing::Captured(_, _) => rectangle::State::Normal,
+                Capturing::Captured(_, _) | Capturing::Selected(_, _) => rectangle::State::Normal,
                 Capturing::Uncaptured => match state {
                     DrawState::Normal => rectangle::State::Normal,
                     DrawState::Highlighted(_) => rectangle::State::Highlighted,
@@ -79,12 +88,24 @@ impl State {
             }
         }
     }
+
+    /// Return the index of the cursor if the TextBox is captured.
+    pub fn cursor_idx(&self) -> Option<Idx> {
+        match self {
+            &State(_, Capturing::Captured(idx, _)) => Some(idx),
+            &State(_, Capturing::Selected(_, end)) => Some(end),
+            &State(_, Capturing::Uncaptured) => None,
+        }
+    }
 }
 
 widget_fns!(TextBox, State, Widget::TextBox(State(DrawState::Normal, Capturing::Uncaptured)));
 
 static TEXT_PADDING: f64 = 5f64;
 
+/// The frame color of a TextBox whose text fails validation.
+const INVALID_COLOR: Color = Color([0.9, 0.2, 0.2, 1.0]);
+
 /// Check if cursor is over the pad and if so, which
 fn over_elem<C: CharacterCache>(uic: &mut UiContext<C>,
              pos: Point,
@@ -95,13 +116,14 @@ fn over_elem<C: CharacterCache>(uic: &mut UiContext<C>,
              text_pos: Point,
              text_w: f64,
              font_size: FontSize,
-             text: &str) -> Element {
+             text: &str,
+             maybe_mask: Option<char>) -> Element {
     match rectangle::is_over(pos, mouse_pos, rect_dim) {
         false => Element::Nill,
         true => match rectangle::is_over(pad_pos, mouse_pos, pad_dim) {
             false => Element::Rect,
             true => {
-                let (idx, cursor_x) = closest_idx(uic, mouse_pos, text_pos[0], text_w, font_size, text);
+                let (idx, cursor_x) = closest_idx(uic, mouse_pos, text_pos[0], text_w, font_size, text, maybe_mask);
                 Element::Text(idx, cursor_x)
             },
         },
@@ -114,21 +136,64 @@ fn closest_idx<C: CharacterCache>(uic: &mut UiContext<C>,
                text_x: f64,
                text_w: f64,
                font_size: FontSize,
-               text: &str) -> (Idx, f64) {
+               text: &str,
+               maybe_mask: Option<char>) -> (Idx, f64) {
     if mouse_pos[0] <= text_x { return (0, text_x) }
     let mut x = text_x;
     let mut prev_x = x;
     let mut left_x = text_x;
-    for (i, ch) in text.chars().enumerate() {
-        let character = uic.get_character(font_size, ch);
-        let char_w = character.width();
+    for (i, grapheme) in text.graphemes(true).enumerate() {
+        let char_w = masked_width(uic, font_size, grapheme, maybe_mask);
         x += char_w;
         let right_x = prev_x + char_w / 2.0;
         if mouse_pos[0] > left_x && mouse_pos[0] < right_x { return (i, prev_x) }
         prev_x = x;
         left_x = right_x;
     }
-    (text.len(), text_x + text_w)
+    (grapheme_count(text), text_x + text_w)
+}
+
+/// The number of grapheme clusters in the text.
+fn grapheme_count(text: &str) -> Idx {
+    text.graphemes(true).count()
+}
+
+/// The byte offset of the grapheme cluster at the given index, or the
+/// length of the text if the index is at (or past) the end.
+fn byte_idx(text: &str, idx: Idx) -> usize {
+    text.grapheme_indices(true).nth(idx).map(|(i, _)| i).unwrap_or(text.len())
+}
+
+/// Replace the grapheme clusters from `start` up to `end` with the given
+/// string, returning the index of the grapheme following the insertion.
+fn splice(text: &mut String, start: Idx, end: Idx, insert: &str) -> Idx {
+    let (start, end) = (byte_idx(text, start), byte_idx(text, end));
+    let new_text = format!("{}{}{}", &text[..start], insert, &text[end..]);
+    *text = new_text;
+    grapheme_count(&text[..start + insert.len()])
+}
+
+/// The width of the text as it is displayed, with every grapheme cluster
+/// replaced by the mask character if there is one.
+fn masked_width<C: CharacterCache>(uic: &mut UiContext<C>,
+                                   font_size: FontSize,
+                                   text: &str,
+                                   maybe_mask: Option<char>) -> f64 {
+    match maybe_mask {
+        Some(mask) => uic.get_character_w(font_size, mask) * grapheme_count(text) as f64,
+        None => label::width(uic, font_size, text),
+    }
+}
+
+/// The x position of the cursor at the given index of the text drawn at
+/// `text_x`.
+fn cursor_x<C: CharacterCache>(uic: &mut UiContext<C>,
+                               text_x: f64,
+                               font_size: FontSize,
+                               text: &str,
+                               idx: Idx,
+                               maybe_mask: Option<char>) -> f64 {
+    text_x + masked_width(uic, font_size, &text[..byte_idx(text, idx)], maybe_mask)
 }
 
 /// Check and return the current state of the TextBox.
@@ -154,42 +219,78 @@ fn get_new_state(over_elem: Element,
                 _                                       => prev_box_state,
             }
         },
-        State(prev, Captured(p_idx, p_x)) => {
+        State(prev, capturing) => {
             match (over_elem, prev, mouse.left) {
                 (Nill, Clicked(Nill), Up)               => State(Normal, Uncaptured),
                 (Text(idx, x), Clicked(Text(_, _)), Up) => State(Highlighted(over_elem), Captured(idx, x)),
                 (_, Normal, Up)                         |
                 (_, Highlighted(_), Up)                 |
-                (_, Clicked(_), Up)                     => State(Highlighted(over_elem), Captured(p_idx, p_x)),
+                (_, Clicked(_), Up)                     => State(Highlighted(over_elem), capturing),
                 (_, Highlighted(p_elem), Down)          |
-                (_, Clicked(p_elem), Down)              => State(Clicked(p_elem), Captured(p_idx, p_x)),
+                (_, Clicked(p_elem), Down)              => State(Clicked(p_elem), capturing),
                 _                                       => prev_box_state,
             }
         },
     }
 }
 
-/// Draw the text cursor.
-fn draw_cursor<B: Graphics>(
+/// Whether or not the grapheme cluster is part of a word.
+fn is_word(grapheme: &str) -> bool {
+    match grapheme.chars().next() {
+        Some(ch) => ch.is_alphanumeric() || ch == '_',
+        None => false,
+    }
+}
+
+/// Return the range of the word surrounding the given index. If the index
+/// isn't within a word, the single grapheme at the index is returned.
+fn word_range(text: &str, idx: Idx) -> (Idx, Idx) {
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    let idx = cmp::min(idx, graphemes.len());
+    let mut start = idx;
+    while start > 0 && is_word(graphemes[start - 1]) { start -= 1 }
+    let mut end = idx;
+    while end < graphemes.len() && is_word(graphemes[end]) { end += 1 }
+    if start == end && end < graphemes.len() { end += 1 }
+    (start, end)
+}
+
+/// Return the index of the start of the word before the given index,
+/// skipping any non-word characters in between.
+fn prev_word_boundary(text: &str, idx: Idx) -> Idx {
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    let mut i = cmp::min(idx, graphemes.len());
+    while i > 0 && !is_word(graphemes[i - 1]) { i -= 1 }
+    while i > 0 && is_word(graphemes[i - 1]) { i -= 1 }
+    i
+}
+
+/// Return the index of the end of the word after the given index,
+/// skipping any non-word characters in between.
+fn next_word_boundary(text: &str, idx: Idx) -> Idx {
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    let mut i = cmp::min(idx, graphemes.len());
+    while i < graphemes.len() && !is_word(graphemes[i]) { i += 1 }
+    while i < graphemes.len() && is_word(graphemes[i]) { i += 1 }
+    i
+}
+
+/// Draw the text cursor, blinking with the given time.
+pub fn draw_cursor<B: Graphics>(
     win_w: f64,
     win_h: f64,
     graphics: &mut B,
     color: Color,
     cursor_x: f64,
     pad_pos_y: f64,
-    pad_h: f64
+    pad_h: f64,
+    time: f64
 ) {
-    let draw_state = graphics::default_draw_state();
-    let transform = graphics::abs_transform(win_w, win_h);
-    let Color(color) = color.plain_contrast();
-    let (r, g, b, a) = (color[0], color[1], color[2], color[3]);
-    graphics::Line::round([r, g, b, (a * (precise_time_s() * 2.5).sin() as f32).abs()], 0.5f64)
-        .draw(
-            [cursor_x, pad_pos_y, cursor_x, pad_pos_y + pad_h],
-            draw_state,
-            transform,
-            graphics
-        );
+    let mut color = color.plain_contrast();
+    let a = color.a();
+    color.set_a((a * (time * 2.5).sin() as f32).abs());
+    primitive::line(win_w, win_h, graphics, [cursor_x, pad_pos_y],
+                    [cursor_x, pad_pos_y + pad_h], 1.0, color);
 }
 
 /// A context on which the builder pattern can be implemented.
@@ -197,18 +298,63 @@ pub struct TextBox<'a, F> {
     ui_id: UIID,
     text: &'a mut String,
     font_size: u32,
+    maybe_font: Option<FontId>,
     pos: Point,
+    visible: bool,
     dim: Dimensions,
     maybe_callback: Option<F>,
     maybe_color: Option<Color>,
     maybe_frame: Option<f64>,
     maybe_frame_color: Option<Color>,
+    read_only: bool,
+    maybe_mask: Option<char>,
+    maybe_filter: Option<Box<Fn(char) -> bool + 'a>>,
+    maybe_validator: Option<Box<Fn(&str) -> Result<(), String> + 'a>>,
+    align: Align,
+    persist: bool,
 }
 
 impl<'a, F> TextBox<'a, F> {
     pub fn font_size(self, font_size: FontSize) -> TextBox<'a, F> {
         TextBox { font_size: font_size, ..self }
     }
+    /// Display the text without allowing it to be edited. The text may
+    /// still be selected and the cursor moved.
+    pub fn read_only(self, read_only: bool) -> TextBox<'a, F> {
+        TextBox { read_only: read_only, ..self }
+    }
+    /// Only accept typed characters for which the given predicate returns true.
+    pub fn filter<P>(self, filter: P) -> TextBox<'a, F>
+        where
+            P: Fn(char) -> bool + 'a
+    {
+        TextBox { maybe_filter: Some(Box::new(filter)), ..self }
+    }
+    /// Check the text each frame. While the text is invalid the frame is
+    /// tinted red and the error is shown in the TextBox's tooltip.
+    pub fn validator<V>(self, validator: V) -> TextBox<'a, F>
+        where
+            V: Fn(&str) -> Result<(), String> + 'a
+    {
+        TextBox { maybe_validator: Some(Box::new(validator)), ..self }
+    }
+    /// Align the text within the TextBox, i.e. right-aligning numbers.
+    pub fn align(self, align: Align) -> TextBox<'a, F> {
+        TextBox { align: align, ..self }
+    }
+    /// Display a bullet in place of each character, i.e. for passwords.
+    pub fn obscure(self, obscure: bool) -> TextBox<'a, F> {
+        TextBox { maybe_mask: if obscure { Some('\u{2022}') } else { None }, ..self }
+    }
+    /// Display the given character in place of each character.
+    pub fn mask(self, mask: char) -> TextBox<'a, F> {
+        TextBox { maybe_mask: Some(mask), ..self }
+    }
+    /// Save the text between sessions via `UiContext::save_persisted`. The
+    /// text is restored the first time the TextBox is drawn.
+    pub fn persist(self, persist: bool) -> TextBox<'a, F> {
+        TextBox { persist: persist, ..self }
+    }
 }
 
 impl<'a, F> TextBox<'a, F> {
@@ -218,12 +364,20 @@ impl<'a, F> TextBox<'a, F> {
             ui_id: ui_id,
             text: text,
             font_size: 24, // Default font_size.
+            maybe_font: None,
             pos: [0.0, 0.0],
+            visible: true,
             dim: [192.0, 48.0],
             maybe_callback: None,
             maybe_color: None,
             maybe_frame: None,
             maybe_frame_color: None,
+            read_only: false,
+            maybe_mask: None,
+            maybe_filter: None,
+            maybe_validator: None,
+            align: Align::Left,
+            persist: false,
         }
     }
 }
@@ -232,6 +386,8 @@ quack! {
     tb: TextBox['a, F]
     get:
         fn () -> Size [] { Size(tb.dim) }
+        fn () -> Position [] { Position(tb.pos) }
+        fn () -> Visible [] { Visible(tb.visible) }
         fn () -> DefaultWidgetState [] {
             DefaultWidgetState(
                 Widget::TextBox(State(DrawState::Normal, Capturing::Uncaptured))
@@ -240,132 +396,220 @@ quack! {
         fn () -> Id [] { Id(tb.ui_id) }
     set:
         fn (val: Color) [] { tb.maybe_color = Some(val) }
+        fn (val: Font) [] { tb.maybe_font = Some(val.0) }
         fn (val: Callback<F>) [where F: FnMut(&mut String) + 'a] {
             tb.maybe_callback = Some(val.0)
         }
         fn (val: FrameColor) [] { tb.maybe_frame_color = Some(val.0) }
         fn (val: FrameWidth) [] { tb.maybe_frame = Some(val.0) }
         fn (val: Position) [] { tb.pos = val.0 }
+        fn (val: Visible) [] { tb.visible = val.0 }
         fn (val: Size) [] { tb.dim = val.0 }
     action:
 }
 
-impl<'a, F> ::draw::Drawable for TextBox<'a, F>
+/// The events that may occur while editing a TextBox.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Event {
+    /// The text was edited.
+    Changed,
+    /// Return was pressed.
+    Entered,
+}
+
+impl<'a, F> TextBox<'a, F>
     where
         F: FnMut(&mut String) + 'a
 {
 
-    #[inline]
-    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+    /// Update the text box's state, calling the callback (if any) and
+    /// drawing it. Return the most significant event that occurred.
+    fn update<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B) -> Option<Event>
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        if !self.visible { uic.keep_state(self.ui_id); return None }
+        let maybe_prev_font = self.maybe_font.map(|font| uic.set_font(font));
+        if self.persist { uic.persist(self.ui_id, &mut *self.text) }
+        let mut maybe_event = None;
         let mouse = uic.get_mouse_state();
         let state = *get_state(uic, self.ui_id);
 
         // Rect.
-        let color = self.maybe_color.unwrap_or(uic.theme.shape_color);
+        let color = uic.flash_color(self.ui_id, self.maybe_color.unwrap_or(uic.theme.shape_color));
         let frame_w = self.maybe_frame.unwrap_or(uic.theme.frame_width);
         let frame_w2 = frame_w * 2.0;
-        let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(uic.theme.frame_color))),
-            false => None,
+        let maybe_error = match self.maybe_validator {
+            Some(ref validator) => validator(&self.text).err(),
+            None => None,
+        };
+        let maybe_frame = match (frame_w > 0.0, maybe_error.is_some()) {
+            (_, true) => Some((frame_w.max(1.0), INVALID_COLOR)),
+            (true, false) => Some((frame_w, self.maybe_frame_color.unwrap_or(uic.theme.frame_color))),
+            (false, false) => None,
         };
+        uic.tooltips.set_error(self.ui_id, maybe_error);
         let pad_pos = vec2_add(self.pos, [frame_w; 2]);
         let pad_dim = vec2_sub(self.dim, [frame_w2; 2]);
-        let text_x = pad_pos[0] + TEXT_PADDING;
+        let text_w = masked_width(uic, self.font_size, &self.text, self.maybe_mask);
+        let text_area_w = pad_dim[0] - TEXT_PADDING * 2.0;
+        let text_x = pad_pos[0] + TEXT_PADDING + self.align.offset(text_w, text_area_w);
         let text_y = pad_pos[1] + (pad_dim[1] - self.font_size as f64) / 2.0;
         let text_pos = [text_x, text_y];
-        let text_w = label::width(uic, self.font_size, &self.text);
         let over_elem = over_elem(uic, self.pos, mouse.pos, self.dim,
                                   pad_pos, pad_dim, text_pos, text_w,
-                                  self.font_size, &self.text);
+                                  self.font_size, &self.text, self.maybe_mask);
         let new_state = get_new_state(over_elem, state, mouse);
 
+        // Double-clicking selects the word under the cursor and triple-clicking
+        // selects all of the text. Masked text has no visible words, so
+        // double-clicking selects all of it.
+        let new_state = match (state, new_state) {
+            (State(DrawState::Clicked(_), _), State(w_state, Capturing::Captured(idx, _))) if mouse.clicks >= 2 => {
+                let (start, end) = if mouse.clicks == 2 && self.maybe_mask.is_none() { word_range(&self.text, idx) }
+                                   else { (0, grapheme_count(&self.text)) };
+                State(w_state, Capturing::Selected(start, end))
+            },
+            _ => new_state,
+        };
+
+        // While an input method is composing, its preedit text is displayed
+        // at the cursor. Masked text doesn't accept compositions.
+        let maybe_preedit = match (new_state, uic.ime.preedit()) {
+            (State(_, Capturing::Captured(idx, _)), Some((preedit, cursor)))
+                if !self.read_only && self.maybe_mask.is_none() => Some((idx, preedit.to_string(), cursor)),
+            _ => None,
+        };
+
         rectangle::draw(uic.win_w, uic.win_h, graphics, new_state.as_rectangle_state(),
                         self.pos, self.dim, maybe_frame, color);
+        let display_text: String = match (self.maybe_mask, &maybe_preedit) {
+            (Some(mask), _) => self.text.graphemes(true).map(|_| mask).collect(),
+            (None, &Some((idx, ref preedit, _))) => {
+                let i = byte_idx(&self.text, idx);
+                format!("{}{}{}", &self.text[..i], preedit, &self.text[i..])
+            },
+            (None, &None) => self.text.clone(),
+        };
+        uic.push_clip(pad_pos, pad_dim);
         uic.draw_text(graphics, text_pos, self.font_size,
-                           color.plain_contrast(), &self.text);
+                           color.plain_contrast(), &display_text);
+        uic.pop_clip();
 
+        // Read-only text keeps its selection unless the cursor is moved.
+        // Typed characters rejected by the filter are dropped before they
+        // reach the text.
+        let entered_text: Vec<String> = match (self.read_only, &self.maybe_filter) {
+            (true, _) => Vec::new(),
+            (false, &Some(ref filter)) => uic.get_entered_text().iter().map(|t| {
+                t.chars().filter(|&ch| filter(ch)).collect::<String>()
+            }).filter(|t| t.len() > 0).collect(),
+            (false, &None) => uic.get_entered_text(),
+        };
+        let has_input = uic.get_pressed_keys().len() > 0 || entered_text.len() > 0;
+        let has_movement = uic.get_pressed_keys().iter().any(|key| match *key {
+            Left | Right | Home | End => true,
+            _ => false,
+        });
         let new_state = match new_state { State(w_state, capturing) => match capturing {
             Capturing::Uncaptured => new_state,
-            Capturing::Captured(idx, cursor_x) => {
-                draw_cursor(uic.win_w, uic.win_h, graphics, color,
-                            cursor_x, pad_pos[1], pad_dim[1]);
+            Capturing::Selected(start, end) if !has_input || (self.read_only && !has_movement) => {
+                let start_x = cursor_x(uic, text_pos[0], self.font_size, &self.text, start, self.maybe_mask);
+                let end_x = cursor_x(uic, text_pos[0], self.font_size, &self.text, end, self.maybe_mask);
+                let mut selection_color = color.plain_contrast();
+                selection_color.set_a(0.3);
+                uic.push_clip(pad_pos, pad_dim);
+                rectangle::draw(uic.win_w, uic.win_h, graphics, rectangle::State::Normal,
+                                [start_x, pad_pos[1]], [end_x - start_x, pad_dim[1]],
+                                None, selection_color);
+                uic.pop_clip();
+                new_state
+            },
+            Capturing::Captured(_, _) | Capturing::Selected(_, _) => {
+                // Keys pressed while composing are handled by the input
+                // method, as is the key that confirmed a composition.
+                let mut pressed_keys = match maybe_preedit {
+                    Some(_) => Vec::new(),
+                    None => uic.get_pressed_keys(),
+                };
+                if uic.ime.was_committed() { pressed_keys.retain(|key| *key != Return) }
+
+                // Input replaces the selection, except for the movement keys.
+                // Left and Right move the cursor to the start or end of the
+                // selection.
+                let idx = match capturing {
+                    Capturing::Selected(start, end) => match pressed_keys.first() {
+                        Some(&Left) => { pressed_keys.remove(0); start },
+                        Some(&Right) => { pressed_keys.remove(0); end },
+                        Some(&Home) | Some(&End) => start,
+                        _ if self.read_only => start,
+                        maybe_key => {
+                            if maybe_key == Some(&Backspace) || maybe_key == Some(&Delete) {
+                                pressed_keys.remove(0);
+                            }
+                            splice(self.text, start, end, "");
+                            maybe_event = Some(Event::Changed);
+                            start
+                        },
+                    },
+                    Capturing::Captured(idx, _) => idx,
+                    Capturing::Uncaptured => unreachable!(),
+                };
                 let mut new_idx = idx;
-                let mut new_cursor_x = cursor_x;
 
-                // Check for entered text.
-                let entered_text = uic.get_entered_text();
+                // Check for entered text. Text is only entered while it fits.
+                let mut new_text_w = masked_width(uic, self.font_size, &self.text, self.maybe_mask);
                 for t in entered_text.iter() {
-                    let mut entered_text_width = 0.0;
-                    for ch in t[..].chars() {
-                        let c = uic.get_character(self.font_size, ch);
-                        entered_text_width += c.width();
-                    }
-                    if new_cursor_x + entered_text_width < pad_pos[0] + pad_dim[0] - TEXT_PADDING {
-                        new_cursor_x += entered_text_width;
+                    let entered_text_width = masked_width(uic, self.font_size, t, self.maybe_mask);
+                    if new_text_w + entered_text_width < pad_dim[0] - TEXT_PADDING * 2.0 {
+                        new_text_w += entered_text_width;
                     }
                     else {
                         break;
                     }
-                    let new_text = format!("{}{}{}", &self.text[..idx], t, &self.text[idx..]);
-                    *self.text = new_text;
-                    new_idx += t.len();
+                    new_idx = splice(self.text, new_idx, new_idx, t);
+                    maybe_event = Some(Event::Changed);
                 }
 
-                // Check for control keys.
-                let pressed_keys = uic.get_pressed_keys();
+                // Check for control keys. With the word modifier held, the
+                // cursor moves and deletes by whole words. Masked text has
+                // no visible words, so it moves to the start or end instead.
+                let by_word = uic.get_modifier_keys().contains(uic.conventions().word_modifier);
                 for key in pressed_keys.iter() {
+                    let prev_word = match self.maybe_mask {
+                        None => prev_word_boundary(&self.text, new_idx),
+                        Some(_) => 0,
+                    };
+                    let next_word = match self.maybe_mask {
+                        None => next_word_boundary(&self.text, new_idx),
+                        Some(_) => grapheme_count(&self.text),
+                    };
                     match *key {
-                        Backspace => {
-                            if self.text.len() > 0
-                            && self.text.len() >= idx
-                            && idx > 0 {
-                                let rem_idx = idx - 1;
-                                new_cursor_x -= uic.get_character_w(
-                                    self.font_size, self.text[..].char_at(rem_idx)
-                                );
-                                let new_text = format!("{}{}", &self.text[..rem_idx], &self.text[idx..]);
-                                *self.text = new_text;
-                                new_idx = rem_idx;
-                            }
+                        Backspace => if !self.read_only && new_idx > 0 {
+                            let rem_idx = if by_word { prev_word } else { new_idx - 1 };
+                            new_idx = splice(self.text, rem_idx, new_idx, "");
+                            maybe_event = Some(Event::Changed);
                         },
-                        Left => {
-                            if idx > 0 {
-                                new_cursor_x -= uic.get_character_w(
-                                    self.font_size, self.text[..].char_at(idx - 1)
-                                );
-                                new_idx -= 1;
-                            }
+                        Delete => if !self.read_only && new_idx < grapheme_count(&self.text) {
+                            let rem_end = if by_word { next_word } else { new_idx + 1 };
+                            splice(self.text, new_idx, rem_end, "");
+                            maybe_event = Some(Event::Changed);
                         },
-                        Right => {
-                            if self.text.len() > idx {
-                                new_cursor_x += uic.get_character_w(
-                                    self.font_size, self.text[..].char_at(idx)
-                                );
-                                new_idx += 1;
-                            }
+                        Left => if new_idx > 0 {
+                            new_idx = if by_word { prev_word } else { new_idx - 1 };
                         },
-                        Return => if self.text.len() > 0 {
-                            let TextBox { // borrowck
-                                ref mut maybe_callback,
-                                ref font_size,
-                                ref mut text,
-                                ..
-                            } = *self;
-                            match *maybe_callback {
+                        Right => if new_idx < grapheme_count(&self.text) {
+                            new_idx = if by_word { next_word } else { new_idx + 1 };
+                        },
+                        Home => new_idx = 0,
+                        End => new_idx = grapheme_count(&self.text),
+                        Return => if !self.read_only && self.text.len() > 0 {
+                            maybe_event = Some(Event::Entered);
+                            match self.maybe_callback {
                                 Some(ref mut callback) => {
-                                    (*callback)(*text);
-
-                                    new_idx = cmp::min(new_idx, text.len());
-                                    let text = &*text;
-                                    new_cursor_x = text.chars()
-                                                       // Add text_pos.x for padding
-                                                       .fold(text_pos[0], |acc, c| {
-                                        acc + uic.get_character_w(*font_size, c)
-                                    });
+                                    (*callback)(&mut *self.text);
+                                    new_idx = cmp::min(new_idx, grapheme_count(&self.text));
                                 },
                                 None => (),
                             }
@@ -374,11 +618,71 @@ impl<'a, F> ::draw::Drawable for TextBox<'a, F>
                     }
                 }
 
+                // The text may have changed, so the cursor position is
+                // always recomputed from its index (and the alignment).
+                let new_text_w = masked_width(uic, self.font_size, &self.text, self.maybe_mask);
+                let new_text_x = pad_pos[0] + TEXT_PADDING + self.align.offset(new_text_w, text_area_w);
+                let new_cursor_x = cursor_x(uic, new_text_x, self.font_size, &self.text, new_idx, self.maybe_mask);
+
+                // Underline the preedit text, placing the cursor within it.
+                let draw_cursor_x = match maybe_preedit {
+                    Some((_, ref preedit, cursor)) => {
+                        let preedit_w = label::width(uic, self.font_size, preedit);
+                        let underline_y = text_pos[1] + self.font_size as f64 + 2.0;
+                        primitive::line(uic.win_w, uic.win_h, graphics, [new_cursor_x, underline_y],
+                                        [new_cursor_x + preedit_w, underline_y], 1.0, color.plain_contrast());
+                        new_cursor_x + label::width(uic, self.font_size, &preedit[..byte_idx(preedit, cursor)])
+                    },
+                    None => new_cursor_x,
+                };
+                draw_cursor(uic.win_w, uic.win_h, graphics, color,
+                            draw_cursor_x, pad_pos[1], pad_dim[1], uic.time());
+                if !self.read_only {
+                    uic.ime.set_cursor_area([draw_cursor_x, pad_pos[1]], [1.0, pad_dim[1]]);
+                }
+
                 State(w_state, Capturing::Captured(new_idx, new_cursor_x))
             },
         }};
 
+        // Editing the text doesn't necessarily change the state (i.e. Delete
+        // leaves the cursor where it is) and the callback may replace the text
+        // once it's entered, so damage the text box here.
+        if maybe_event.is_some() { uic.damage(self.pos, self.dim) }
+
         set_state(uic, self.ui_id, Widget::TextBox(new_state), self.pos, self.dim);
+        if let Some(font) = maybe_prev_font { uic.set_font(font); }
+
+        maybe_event
+    }
 
+}
+
+impl<'a> TextBox<'a, fn(&mut String)> {
+
+    /// Draw the text box and return the most significant event that occurred
+    /// (if any). This is an alternative to providing a callback.
+    pub fn event<B, C>(mut self, uic: &mut UiContext<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics)
+    }
+
+}
+
+impl<'a, F> ::draw::Drawable for TextBox<'a, F>
+    where
+        F: FnMut(&mut String) + 'a
+{
+
+    #[inline]
+    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics);
     }
 }
@@ -1,5 +1,7 @@
 
 use color::Color;
+use density::DisplayDensity;
+use primitive::{ CircleQuality, LineStyle };
 use rustc_serialize::{
     json,
     Encodable,
@@ -24,6 +26,17 @@ pub struct Theme {
     pub font_size_large: u32,
     pub font_size_medium: u32,
     pub font_size_small: u32,
+    /// The default style for lines drawn within widgets.
+    pub line_style: Option<LineStyle>,
+    /// The default quality for circles drawn within widgets.
+    pub circle_quality: Option<CircleQuality>,
+    /// The default amount of detail displayed by widgets.
+    pub display_density: Option<DisplayDensity>,
+    /// Whether colors are interpolated in linear space rather than sRGB.
+    pub linear_blending: Option<bool>,
+    /// Whether value readouts draw digits with uniform widths so that
+    /// changing numbers don't jitter.
+    pub tabular_figures: Option<bool>,
     //TODO: Add unique theme-ing for each widget.
     //i.e. maybe_slider: Option<SliderTheme>, etc
 }
@@ -42,10 +55,97 @@ impl Theme {
             font_size_large: 26,
             font_size_medium: 18,
             font_size_small: 12,
+            line_style: None,
+            circle_quality: None,
+            display_density: None,
+            linear_blending: None,
+            tabular_figures: None,
         }
     }
 
-    /// Load a theme from file.
+    /// A dark theme with light text, suited to dim environments.
+    pub fn dark() -> Theme {
+        Theme {
+            name: "Dark".to_string(),
+            background_color: Color::new(0.11, 0.11, 0.13, 1.0),
+            shape_color: Color::new(0.24, 0.25, 0.28, 1.0),
+            frame_color: Color::new(0.06, 0.06, 0.07, 1.0),
+            frame_width: 1.0,
+            label_color: Color::new(0.88, 0.88, 0.9, 1.0),
+            font_size_large: 26,
+            font_size_medium: 18,
+            font_size_small: 12,
+            line_style: Some(LineStyle::solid()),
+            circle_quality: Some(CircleQuality::default()),
+            display_density: Some(DisplayDensity::Normal),
+            linear_blending: Some(true),
+            tabular_figures: Some(true),
+        }
+    }
+
+    /// A light theme with dark text.
+    pub fn light() -> Theme {
+        Theme {
+            name: "Light".to_string(),
+            background_color: Color::new(0.94, 0.94, 0.95, 1.0),
+            shape_color: Color::new(0.82, 0.83, 0.86, 1.0),
+            frame_color: Color::new(0.55, 0.56, 0.6, 1.0),
+            frame_width: 1.0,
+            label_color: Color::new(0.1, 0.1, 0.12, 1.0),
+            font_size_large: 26,
+            font_size_medium: 18,
+            font_size_small: 12,
+            line_style: Some(LineStyle::solid()),
+            circle_quality: Some(CircleQuality::default()),
+            display_density: Some(DisplayDensity::Normal),
+            linear_blending: Some(true),
+            tabular_figures: Some(true),
+        }
+    }
+
+    /// A high-contrast theme with pure black and white, thick frames and
+    /// larger text, for low-vision users and bright environments.
+    pub fn high_contrast() -> Theme {
+        Theme {
+            name: "High Contrast".to_string(),
+            background_color: Color::black(),
+            shape_color: Color::black(),
+            frame_color: Color::new(1.0, 1.0, 0.0, 1.0),
+            frame_width: 3.0,
+            label_color: Color::white(),
+            font_size_large: 32,
+            font_size_medium: 24,
+            font_size_small: 18,
+            line_style: Some(LineStyle::solid()),
+            circle_quality: Some(CircleQuality::default()),
+            display_density: Some(DisplayDensity::Normal),
+            linear_blending: Some(false),
+            tabular_figures: Some(true),
+        }
+    }
+
+    /// Return the theme `amount` (0.0 .. 1.0) of the way between this theme
+    /// and the `other` theme. Colors and the frame width are interpolated
+    /// (in linear space if `other` enables linear blending). All other
+    /// fields are taken from `other`.
+    pub fn interpolate(&self, other: &Theme, amount: f32) -> Theme {
+        let mix = |a: Color, b: Color| {
+            if other.linear_blending.unwrap_or(false) { a.mix_linear(b, amount) }
+            else { a.mix(b, amount) }
+        };
+        Theme {
+            background_color: mix(self.background_color, other.background_color),
+            shape_color: mix(self.shape_color, other.shape_color),
+            frame_color: mix(self.frame_color, other.frame_color),
+            frame_width: self.frame_width + (other.frame_width - self.frame_width) * amount as f64,
+            label_color: mix(self.label_color, other.label_color),
+            ..other.clone()
+        }
+    }
+
+    /// Load a theme from a JSON file. Fields holding an Option may be
+    /// omitted, in which case the UiContext's default is used. Any other
+    /// missing or malformed field is named in the returned error.
     pub fn load(path: &str) -> Result<Theme, String> {
         let mut file = match File::open(&Path::new(path)) {
             Ok(file) => file,
@@ -55,24 +155,35 @@ impl Theme {
         if let Err(e) = ::std::io::Read::read_to_end(&mut file, &mut contents) {
             return Err(format!("Failed to load Theme correctly: {}", Error::description(&e)));
         }
-        let json_object = match json::Json::from_str(str::from_utf8(&contents[..]).unwrap()) {
+        let contents = match str::from_utf8(&contents[..]) {
+            Ok(contents) => contents,
+            Err(e) => return Err(format!("Theme file {} is not valid UTF-8: {}", path, Error::description(&e))),
+        };
+        let json_object = match json::Json::from_str(contents) {
             Ok(json_object) => json_object,
+            Err(json::ParserError::SyntaxError(code, line, col)) => return Err(format!(
+                "Theme file {} is not valid JSON: {} at line {}, column {}", path, json::error_str(code), line, col
+            )),
             Err(e) => return Err(format!("Failed to construct json_object from str: {}", Error::description(&e))),
         };
         let mut decoder = json::Decoder::new(json_object);
-        let theme = match Decodable::decode(&mut decoder) {
+        match Decodable::decode(&mut decoder) {
             Ok(theme) => Ok(theme),
+            Err(json::DecoderError::MissingFieldError(field)) =>
+                Err(format!("Theme file {} is missing the field \"{}\"", path, field)),
+            Err(json::DecoderError::ExpectedError(expected, found)) =>
+                Err(format!("Theme file {} has a field of the wrong type: expected {}, found {}", path, expected, found)),
             Err(e) => Err(format!("Failed to construct Theme from json decoder: {}", Error::description(&e))),
-        };
-        theme
+        }
     }
 
-    /// Save a theme to file.
+    /// Save a theme to a JSON file. The JSON is indented so that it may be
+    /// edited by hand.
     pub fn save(&self, path: &str) -> Result<(), String> {
-        let json_string = match json::encode(self) {
-            Ok(x) => x,
-            Err(e) => return Err(e.description().to_owned())
-        };
+        if let Err(e) = json::encode(self) {
+            return Err(e.description().to_owned())
+        }
+        let json_string = format!("{}", json::as_pretty_json(self));
         let mut file = match File::create(&Path::new(path)) {
             Ok(file) => file,
             Err(e) => return Err(format!("Failed to create a File at the given path: {}", Error::description(&e)))
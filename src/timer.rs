@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use ui_context::UIID;
+
+/// Timers keyed by UIID that fire once their deadline has passed, allowing
+/// widgets and applications to schedule delayed actions within the UI.
+#[derive(Clone, Debug)]
+pub struct Timers {
+    deadlines: HashMap<UIID, f64>,
+}
+
+impl Timers {
+
+    /// Construct an empty set of Timers.
+    pub fn new() -> Timers {
+        Timers { deadlines: HashMap::new() }
+    }
+
+    /// Start the timer with the given UIID, due `delay` seconds from `now`.
+    /// Starting a timer that is already running restarts it, so starting the
+    /// timer on every change debounces the changes.
+    pub fn start(&mut self, ui_id: UIID, delay: f64, now: f64) {
+        self.deadlines.insert(ui_id, now + delay);
+    }
+
+    /// Stop the timer with the given UIID without it firing.
+    pub fn cancel(&mut self, ui_id: UIID) {
+        self.deadlines.remove(&ui_id);
+    }
+
+    /// Whether the timer with the given UIID is due, in which case it is
+    /// stopped so that it fires only once.
+    pub fn fired(&mut self, ui_id: UIID, now: f64) -> bool {
+        match self.deadlines.get(&ui_id) {
+            Some(&deadline) if deadline <= now => (),
+            _ => return false,
+        }
+        self.deadlines.remove(&ui_id);
+        true
+    }
+
+    /// Whether the timer with the given UIID is running.
+    pub fn is_running(&self, ui_id: UIID) -> bool {
+        self.deadlines.contains_key(&ui_id)
+    }
+
+    /// The earliest deadline of all running timers (if any).
+    pub fn next_deadline(&self) -> Option<f64> {
+        self.deadlines.values().fold(None, |maybe_next, &deadline| match maybe_next {
+            Some(next) if next <= deadline => Some(next),
+            _ => Some(deadline),
+        })
+    }
+
+}
@@ -20,6 +20,7 @@ use LabelColor;
 use LabelFontSize;
 use Position;
 use Size;
+use Visible;
 
 /// Represents the state of the Toggle widget.
 #[derive(PartialEq, Clone, Copy)]
@@ -61,6 +62,7 @@ fn get_new_state(is_over: bool,
 pub struct Toggle<'a, F> {
     ui_id: UIID,
     pos: Point,
+    visible: bool,
     dim: Dimensions,
     maybe_callback: Option<F>,
     maybe_color: Option<Color>,
@@ -79,6 +81,7 @@ impl<'a, F> Toggle<'a, F> {
         Toggle {
             ui_id: ui_id,
             pos: [0.0, 0.0],
+            visible: true,
             dim: [64.0, 64.0],
             maybe_callback: None,
             maybe_color: None,
@@ -97,6 +100,8 @@ quack! {
     toggle: Toggle['a, F]
     get:
         fn () -> Size [] { Size(toggle.dim) }
+        fn () -> Position [] { Position(toggle.pos) }
+        fn () -> Visible [] { Visible(toggle.visible) }
         fn () -> DefaultWidgetState [] {
             DefaultWidgetState(Widget::Toggle(State::Normal))
         }
@@ -114,34 +119,38 @@ quack! {
             toggle.maybe_label_font_size = Some(val.0)
         }
         fn (val: Position) [] { toggle.pos = val.0 }
+        fn (val: Visible) [] { toggle.visible = val.0 }
         fn (val: Size) [] { toggle.dim = val.0 }
     action:
 }
 
-impl<'a, F> ::draw::Drawable for Toggle<'a, F> where F: FnMut(bool) + 'a {
-    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+impl<'a, F> Toggle<'a, F> where F: FnMut(bool) + 'a {
+    /// Update the toggle's state, calling the callback (if any) and drawing
+    /// it. Return the new value if the toggle was clicked.
+    fn update<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B) -> Option<bool>
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        if !self.visible { uic.keep_state(self.ui_id); return None }
         let color = self.maybe_color.unwrap_or(uic.theme.shape_color);
         let color = match self.value {
             true => color,
             false => color * Color::new(0.1, 0.1, 0.1, 1.0)
         };
+        let color = uic.flash_color(self.ui_id, color);
         let state = *get_state(uic, self.ui_id);
         let mouse = uic.get_mouse_state();
-        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let is_over = uic.is_over(self.ui_id, self.pos, self.dim, mouse.pos);
         let new_state = get_new_state(is_over, state, mouse);
         let rect_state = new_state.as_rectangle_state();
-        match self.maybe_callback {
-            Some(ref mut callback) => {
-                match (is_over, state, new_state) {
-                    (true, State::Clicked, State::Highlighted) =>
-                        (*callback)(match self.value { true => false, false => true }),
-                    _ => (),
-                }
-            }, None => (),
+        let maybe_new_value = match (is_over, state, new_state) {
+            (true, State::Clicked, State::Highlighted) => Some(!self.value),
+            _ => None,
+        };
+        match (maybe_new_value, self.maybe_callback.as_mut()) {
+            (Some(new_value), Some(callback)) => (*callback)(new_value),
+            _ => (),
         }
         let frame_w = self.maybe_frame.unwrap_or(uic.theme.frame_width);
         let maybe_frame = match frame_w > 0.0 {
@@ -166,7 +175,32 @@ impl<'a, F> ::draw::Drawable for Toggle<'a, F> where F: FnMut(bool) + 'a {
             },
         }
 
+        let value = maybe_new_value.unwrap_or(self.value);
+        uic.tooltips.set_value(self.ui_id, if value { "On".to_string() } else { "Off".to_string() });
         set_state(uic, self.ui_id, Widget::Toggle(new_state), self.pos, self.dim);
 
+        maybe_new_value
+    }
+}
+
+impl<'a> Toggle<'a, fn(bool)> {
+    /// Draw the toggle and return its new value if it was clicked. This is
+    /// an alternative to providing a callback.
+    pub fn changed<B, C>(mut self, uic: &mut UiContext<C>, graphics: &mut B) -> Option<bool>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics)
+    }
+}
+
+impl<'a, F> ::draw::Drawable for Toggle<'a, F> where F: FnMut(bool) + 'a {
+    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(uic, graphics);
     }
 }
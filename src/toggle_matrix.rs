@@ -0,0 +1,274 @@
+use std::num::Float;
+use color::Color;
+use dimensions::Dimensions;
+use graphics;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use mouse::Mouse;
+use point::Point;
+use rectangle;
+use ui_context::{
+    Id,
+    UIID,
+    UiContext,
+};
+use vecmath::{
+    vec2_add,
+    vec2_sub,
+};
+use widget::{ DefaultWidgetState, Widget };
+use Callback;
+use FrameColor;
+use FrameWidth;
+use Position;
+use Size;
+use Visible;
+
+/// Callback params.
+pub type RowNum = usize;
+pub type ColNum = usize;
+
+/// Represents the state of the ToggleMatrix widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(RowNum, ColNum),
+    /// The mouse is down and cells that are dragged over will be
+    /// set to the given value. The last painted cell is stored so
+    /// that cells skipped over by fast drags may also be painted.
+    Painting(bool, RowNum, ColNum),
+}
+
+widget_fns!(ToggleMatrix, State, Widget::ToggleMatrix(State::Normal));
+
+/// Return the cell that the mouse is over (if any).
+fn over_cell(pad_pos: Point,
+             pad_dim: Dimensions,
+             mouse_pos: Point,
+             rows: usize,
+             cols: usize) -> Option<(RowNum, ColNum)> {
+    match rectangle::is_over(pad_pos, mouse_pos, pad_dim) {
+        false => None,
+        true => {
+            let col = ((mouse_pos[0] - pad_pos[0]) / pad_dim[0] * cols as f64) as usize;
+            let row = ((mouse_pos[1] - pad_pos[1]) / pad_dim[1] * rows as f64) as usize;
+            if row < rows && col < cols { Some((row, col)) } else { None }
+        },
+    }
+}
+
+/// Return the cell nearest to the mouse, clamped to the matrix.
+fn nearest_cell(pad_pos: Point,
+                pad_dim: Dimensions,
+                mouse_pos: Point,
+                rows: usize,
+                cols: usize) -> (RowNum, ColNum) {
+    let col = ((mouse_pos[0] - pad_pos[0]) / pad_dim[0] * cols as f64).floor();
+    let row = ((mouse_pos[1] - pad_pos[1]) / pad_dim[1] * rows as f64).floor();
+    let clamp_idx = |idx: f64, len: usize| if idx < 0.0 { 0 }
+                                           else if idx as usize >= len { len - 1 }
+                                           else { idx as usize };
+    (clamp_idx(row, rows), clamp_idx(col, cols))
+}
+
+/// Check the current state of the matrix.
+fn get_new_state(over_cell: Option<(RowNum, ColNum)>,
+                 nearest_cell: (RowNum, ColNum),
+                 prev: State,
+                 mouse: Mouse,
+                 values: &Vec<Vec<bool>>) -> State {
+    use mouse::ButtonState::{Down, Up};
+    use self::State::{Normal, Highlighted, Painting};
+    match (over_cell, prev, mouse.left) {
+        (Some(_),          Normal,             Down) => Normal,
+        (Some((r, c)),     _,                  Up)   => Highlighted(r, c),
+        (Some((r, c)),     Highlighted(_, _),  Down) => Painting(!values[r][c], r, c),
+        (_,                Painting(v, _, _),  Down) => Painting(v, nearest_cell.0, nearest_cell.1),
+        _                                            => Normal,
+    }
+}
+
+/// Return every cell along the line between cells `a` and `b`.
+fn cells_between(a: (RowNum, ColNum), b: (RowNum, ColNum)) -> Vec<(RowNum, ColNum)> {
+    let (r_a, c_a) = (a.0 as isize, a.1 as isize);
+    let (r_b, c_b) = (b.0 as isize, b.1 as isize);
+    let steps = ::std::cmp::max((r_b - r_a).abs(), (c_b - c_a).abs());
+    if steps == 0 { return vec![b] }
+    (0..steps + 1).map(|i| {
+        let t = i as f64 / steps as f64;
+        let r = (r_a as f64 + (r_b - r_a) as f64 * t).round() as usize;
+        let c = (c_a as f64 + (c_b - c_a) as f64 * t).round() as usize;
+        (r, c)
+    }).collect()
+}
+
+/// A context on which the builder pattern can be implemented.
+pub struct ToggleMatrix<'a, F> {
+    ui_id: UIID,
+    values: &'a mut Vec<Vec<bool>>,
+    rows: usize,
+    cols: usize,
+    pos: Point,
+    visible: bool,
+    dim: Dimensions,
+    cell_pad_w: f64,
+    cell_pad_h: f64,
+    maybe_cell_colors: Option<&'a [Vec<Option<Color>>]>,
+    maybe_callback: Option<F>,
+    maybe_color: Option<Color>,
+    maybe_frame: Option<f64>,
+    maybe_frame_color: Option<Color>,
+}
+
+impl<'a, F> ToggleMatrix<'a, F> {
+    /// A builder method for adding padding to each cell.
+    #[inline]
+    pub fn cell_padding(self, w: f64, h: f64) -> ToggleMatrix<'a, F> {
+        ToggleMatrix { cell_pad_w: w, cell_pad_h: h, ..self }
+    }
+    /// Override the color of individual cells. The colors are
+    /// indexed by `[row][col]`, `None` using the matrix color.
+    #[inline]
+    pub fn cell_colors(self, colors: &'a [Vec<Option<Color>>]) -> ToggleMatrix<'a, F> {
+        ToggleMatrix { maybe_cell_colors: Some(colors), ..self }
+    }
+}
+
+impl<'a, F> ToggleMatrix<'a, F> {
+    /// Create a toggle matrix context to be built upon. The values
+    /// are indexed by `[row][col]` and determine the number of rows
+    /// and columns.
+    pub fn new(ui_id: UIID, values: &'a mut Vec<Vec<bool>>) -> ToggleMatrix<'a, F> {
+        let rows = values.len();
+        let cols = values.iter().map(|row| row.len()).min().unwrap_or(0);
+        ToggleMatrix {
+            ui_id: ui_id,
+            values: values,
+            rows: rows,
+            cols: cols,
+            pos: [0.0, 0.0],
+            visible: true,
+            dim: [256.0, 256.0],
+            cell_pad_w: 1.0,
+            cell_pad_h: 1.0,
+            maybe_cell_colors: None,
+            maybe_callback: None,
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+        }
+    }
+}
+
+quack! {
+    tm: ToggleMatrix['a, F]
+    get:
+        fn () -> Size [] { Size(tm.dim) }
+        fn () -> Position [] { Position(tm.pos) }
+        fn () -> Visible [] { Visible(tm.visible) }
+        fn () -> DefaultWidgetState [] {
+            DefaultWidgetState(Widget::ToggleMatrix(State::Normal))
+        }
+        fn () -> Id [] { Id(tm.ui_id) }
+    set:
+        fn (val: Color) [] { tm.maybe_color = Some(val) }
+        fn (val: Callback<F>) [where F: FnMut(RowNum, ColNum, bool) + 'a] {
+            tm.maybe_callback = Some(val.0)
+        }
+        fn (val: FrameColor) [] { tm.maybe_frame_color = Some(val.0) }
+        fn (val: FrameWidth) [] { tm.maybe_frame = Some(val.0) }
+        fn (val: Position) [] { tm.pos = val.0 }
+        fn (val: Visible) [] { tm.visible = val.0 }
+        fn (val: Size) [] { tm.dim = val.0 }
+    action:
+}
+
+impl<'a, F> ::draw::Drawable for ToggleMatrix<'a, F>
+    where
+        F: FnMut(RowNum, ColNum, bool) + 'a
+{
+
+    fn draw<B, C>(&mut self, uic: &mut UiContext<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if !self.visible { uic.keep_state(self.ui_id); return }
+        let (rows, cols) = (self.rows, self.cols);
+        let state = *get_state(uic, self.ui_id);
+        let mouse = uic.get_mouse_state();
+        let frame_w = self.maybe_frame.unwrap_or(uic.theme.frame_width);
+        let frame_color = self.maybe_frame_color.unwrap_or(uic.theme.frame_color);
+        let color = uic.flash_color(self.ui_id, self.maybe_color.unwrap_or(uic.theme.shape_color));
+        let pad_pos = vec2_add(self.pos, [frame_w, frame_w]);
+        let pad_dim = vec2_sub(self.dim, [frame_w * 2.0; 2]);
+
+        if rows == 0 || cols == 0 {
+            set_state(uic, self.ui_id, Widget::ToggleMatrix(State::Normal), self.pos, self.dim);
+            return;
+        }
+
+        let over_cell = over_cell(pad_pos, pad_dim, mouse.pos, rows, cols);
+        let nearest_cell = nearest_cell(pad_pos, pad_dim, mouse.pos, rows, cols);
+        let new_state = get_new_state(over_cell, nearest_cell, state, mouse, self.values);
+
+        // Paint every cell dragged over since the last frame.
+        let painted = match (state, new_state) {
+            (State::Highlighted(_, _), State::Painting(v, r, c)) => Some((v, vec![(r, c)])),
+            (State::Painting(_, p_r, p_c), State::Painting(v, r, c)) =>
+                Some((v, cells_between((p_r, p_c), (r, c)))),
+            _ => None,
+        };
+        if let Some((value, cells)) = painted {
+            for &(r, c) in cells.iter() {
+                if self.values[r][c] != value {
+                    self.values[r][c] = value;
+                    match self.maybe_callback {
+                        Some(ref mut callback) => (*callback)(r, c, value),
+                        None => (),
+                    }
+                }
+            }
+        }
+
+        // Draw the frame as a backdrop behind the cells.
+        let draw_state = graphics::default_draw_state();
+        let transform = graphics::abs_transform(uic.win_w, uic.win_h);
+        if frame_w > 0.0 {
+            graphics::Rectangle::new(frame_color.0)
+                .draw([self.pos[0], self.pos[1], self.dim[0], self.dim[1]],
+                      draw_state, transform, graphics);
+        }
+
+        // Draw the cells.
+        let cell_w = pad_dim[0] / cols as f64;
+        let cell_h = pad_dim[1] / rows as f64;
+        for r in 0..rows {
+            for c in 0..cols {
+                let cell_color = match self.maybe_cell_colors {
+                    Some(colors) if r < colors.len() && c < colors[r].len() =>
+                        colors[r][c].unwrap_or(color),
+                    _ => color,
+                };
+                let cell_color = match self.values[r][c] {
+                    true => cell_color,
+                    false => cell_color * Color::new(0.1, 0.1, 0.1, 1.0),
+                };
+                let cell_color = match new_state {
+                    State::Highlighted(h_r, h_c) if h_r == r && h_c == c => cell_color.highlighted(),
+                    State::Painting(_, p_r, p_c) if p_r == r && p_c == c => cell_color.clicked(),
+                    _ => cell_color,
+                };
+                graphics::Rectangle::new(cell_color.0)
+                    .draw([pad_pos[0] + cell_w * c as f64 + self.cell_pad_w,
+                           pad_pos[1] + cell_h * r as f64 + self.cell_pad_h,
+                           cell_w - self.cell_pad_w * 2.0,
+                           cell_h - self.cell_pad_h * 2.0],
+                          draw_state, transform, graphics);
+            }
+        }
+
+        set_state(uic, self.ui_id, Widget::ToggleMatrix(new_state), self.pos, self.dim);
+
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use ui_context::UIID;
+
+/// The text, keyboard shortcut and current value registered for each
+/// widget, composed into a tooltip that is shown while hovering.
+#[derive(Clone, Debug)]
+pub struct Tooltips {
+    /// The time in seconds for which a widget must be hovered before its
+    /// tooltip is shown.
+    pub delay: f64,
+    texts: HashMap<UIID, String>,
+    shortcuts: HashMap<UIID, String>,
+    values: HashMap<UIID, String>,
+    errors: HashMap<UIID, String>,
+    /// The hovered widget and the time at which hovering began.
+    maybe_hover: Option<(UIID, f64)>,
+}
+
+impl Tooltips {
+
+    /// Construct an empty set of Tooltips.
+    pub fn new() -> Tooltips {
+        Tooltips {
+            delay: 0.5,
+            texts: HashMap::new(),
+            shortcuts: HashMap::new(),
+            values: HashMap::new(),
+            errors: HashMap::new(),
+            maybe_hover: None,
+        }
+    }
+
+    /// Set the descriptive text for the given widget's tooltip.
+    pub fn set_text(&mut self, ui_id: UIID, text: &str) {
+        self.texts.insert(ui_id, text.to_string());
+    }
+
+    /// Register the keyboard shortcut (i.e. "Ctrl+M") that activates the
+    /// given widget.
+    pub fn set_shortcut(&mut self, ui_id: UIID, shortcut: &str) {
+        self.shortcuts.insert(ui_id, shortcut.to_string());
+    }
+
+    /// The keyboard shortcut registered for the given widget (if any).
+    pub fn shortcut(&self, ui_id: UIID) -> Option<&str> {
+        self.shortcuts.get(&ui_id).map(|s| &s[..])
+    }
+
+    /// Set the formatted current value of the given widget. Value widgets
+    /// call this each time they're drawn.
+    pub fn set_value(&mut self, ui_id: UIID, value: String) {
+        self.values.insert(ui_id, value);
+    }
+
+    /// Set or clear the validation error of the given widget. Widgets that
+    /// validate their input call this each time they're drawn.
+    pub fn set_error(&mut self, ui_id: UIID, maybe_error: Option<String>) {
+        match maybe_error {
+            Some(error) => { self.errors.insert(ui_id, error); },
+            None => { self.errors.remove(&ui_id); },
+        }
+    }
+
+    /// The current validation error of the given widget (if any).
+    pub fn error(&self, ui_id: UIID) -> Option<&str> {
+        self.errors.get(&ui_id).map(|s| &s[..])
+    }
+
+    /// Forget the value and validation error set by the given widget. The
+    /// text and shortcut, which are set by the application, are kept.
+    pub fn forget_state(&mut self, ui_id: UIID) {
+        self.values.remove(&ui_id);
+        self.errors.remove(&ui_id);
+    }
+
+    /// Compose the tooltip for the given widget from its text, shortcut
+    /// and value, i.e. "Volume (Ctrl+V): 0.50". A validation error takes
+    /// the place of the value.
+    pub fn compose(&self, ui_id: UIID) -> Option<String> {
+        let maybe_text = self.texts.get(&ui_id);
+        let maybe_shortcut = self.shortcuts.get(&ui_id);
+        let maybe_value = self.errors.get(&ui_id).or(self.values.get(&ui_id));
+        if maybe_text.is_none() && maybe_shortcut.is_none() && !self.errors.contains_key(&ui_id) {
+            return None
+        }
+        let mut tooltip = maybe_text.cloned().unwrap_or(String::new());
+        if let Some(shortcut) = maybe_shortcut {
+            if tooltip.len() > 0 { tooltip.push(' ') }
+            tooltip.push_str(&format!("({})", shortcut));
+        }
+        if let Some(value) = maybe_value {
+            if tooltip.len() > 0 { tooltip.push_str(": ") }
+            tooltip.push_str(value);
+        }
+        Some(tooltip)
+    }
+
+    /// Whether a widget is being hovered but its tooltip is yet to appear.
+    pub fn is_pending(&self, now: f64) -> bool {
+        match self.maybe_hover {
+            Some((_, start)) => now - start < self.delay,
+            None => false,
+        }
+    }
+
+    /// Update the hovered widget, returning it if it has been hovered for
+    /// longer than the delay.
+    pub fn hover(&mut self, maybe_ui_id: Option<UIID>, now: f64) -> Option<UIID> {
+        match (self.maybe_hover, maybe_ui_id) {
+            (Some((prev, _)), Some(ui_id)) if prev == ui_id => (),
+            (_, Some(ui_id)) => self.maybe_hover = Some((ui_id, now)),
+            (_, None) => self.maybe_hover = None,
+        }
+        match self.maybe_hover {
+            Some((ui_id, start)) if now - start >= self.delay => Some(ui_id),
+            _ => None,
+        }
+    }
+
+}
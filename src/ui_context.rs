@@ -1,43 +1,138 @@
-use std::iter::repeat;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{ HashMap, HashSet, VecDeque };
+use std::collections::hash_map::Entry;
+use std::hash::{ Hash, Hasher, SipHasher };
+use std::rc::Rc;
+use animation::{ Animations, Easing };
+use autosave;
+use autosave::Autosave;
+use backend;
+use backend::{ Button, Input, Renderer, RendererGraphics };
+use canvas::{ Canvases, CanvasId, Direction, Dock, Rect };
+use clip::{ self, Scissor };
+use clock_ticks::precise_time_s;
 use Color;
 use dimensions::Dimensions;
+use font::{ self, FontId };
 use graphics;
 use graphics::Graphics;
 use graphics::character::{ Character, CharacterCache };
+use hit_shape::HitShape;
+use history::History;
+use ime::Ime;
+use keyboard;
+use keyboard::ModifierKeys;
+use label;
 use label::FontSize;
+use layer::{ self, Commands, Depth, Recorder, Textures };
+use layout::{ Breakpoint, Breakpoints, Flow };
+use link::{ LinkMode, Links };
 use mouse::{
     ButtonState,
+    Drag,
     Mouse,
 };
 use piston::input;
-use piston::event::{
-    GenericEvent,
-    MouseCursorEvent,
-    PressEvent,
-    ReleaseEvent,
-    RenderEvent,
-    TextEvent,
-};
+use piston::event::{ GenericEvent, RenderEvent };
+use num_pad::NumPad;
+use overlay;
+use persist::Persisted;
+use platform::Conventions;
 use point::Point;
+use primitive;
+use primitive::CircleQuality;
+use quality;
+use rustc_serialize::{ Decodable, Encodable };
+use reactive::Signals;
+use rectangle;
+use selection;
+use selection::Selection;
+use smoothing::Smoothing;
 use theme::Theme;
+use timer::Timers;
+use tooltip::Tooltips;
+use utils::clamp;
 use widget;
 use widget::Widget;
+use width_cache::WidthCache;
 
 /// User Interface Identifier. Each unique `widget::draw` call
 /// should pass it's own unique UIID so that UiContext can keep
 /// track of it's state.
 pub type UIID = u64;
 
+/// Return a stable UIID for the given name. Named ids always have their
+/// highest bit set so that they won't collide with small hand-assigned
+/// integer ids. See the `widget_id!` macro.
+pub fn named_id(name: &str) -> UIID {
+    let mut hasher = SipHasher::new();
+    name.hash(&mut hasher);
+    hasher.finish() | (1 << 63)
+}
+
+/// Return a stable UIID for a part of the widget with the given UIID that
+/// is itself drawn as a widget, i.e. the text field of a NumberBox.
+pub fn sub_id(ui_id: UIID, name: &str) -> UIID {
+    scoped_id(ui_id, name)
+}
+
+/// Return a UIID derived from both the given scope id and key.
+fn scoped_id<T: Hash>(scope: UIID, key: T) -> UIID {
+    let mut hasher = SipHasher::new();
+    scope.hash(&mut hasher);
+    key.hash(&mut hasher);
+    hasher.finish() | (1 << 63)
+}
+
+/// The factor by which each zoom hotkey press scales the UI.
+const UI_SCALE_STEP: f64 = 1.1;
+/// The range within which the UI may be zoomed.
+const MIN_UI_SCALE: f64 = 0.5;
+const MAX_UI_SCALE: f64 = 3.0;
+
+/// The furthest the mouse may move between clicks for them to count
+/// towards a double-click.
+const MULTI_CLICK_DISTANCE: f64 = 4.0;
+
+/// The factor by which pointer movement is scaled while fine dragging.
+const FINE_DRAG_SCALE: f64 = 0.1;
+
+/// The UIID of the root id scope.
+const ROOT_SCOPE: UIID = 1 << 63;
+
+/// Whether or not the given key repeats while held, i.e. for deleting or
+/// moving through text.
+fn is_repeatable(key: input::keyboard::Key) -> bool {
+    use piston::input::keyboard::Key;
+    match key {
+        Key::Backspace | Key::Delete | Key::Left | Key::Right | Key::Up | Key::Down => true,
+        _ => false,
+    }
+}
+
+/// A temporary color flash applied to a widget.
+#[derive(Copy, Clone, Debug)]
+struct Flash {
+    ui_id: UIID,
+    color: Color,
+    start: f64,
+    duration: f64,
+}
+
 /// UiContext retains the state of all widgets and
 /// data relevant to the draw_widget functions.
 pub struct UiContext<C> {
-    data: Vec<(Widget, widget::Placing)>,
+    data: HashMap<UIID, (Widget, widget::Placing)>,
     pub theme: Theme,
     pub mouse: Mouse,
     pub keys_just_pressed: Vec<input::keyboard::Key>,
     pub keys_just_released: Vec<input::keyboard::Key>,
     pub text_just_entered: Vec<String>,
-    glyph_cache: C,
+    /// The registered fonts, the first being the default.
+    fonts: Vec<C>,
+    /// The font in which text is currently drawn.
+    current_font: FontId,
     prev_event_was_render: bool,
     /// Window width.
     pub win_w: f64,
@@ -45,6 +140,130 @@ pub struct UiContext<C> {
     pub win_h: f64,
     /// The UIID of the widget drawn previously.
     prev_uiid: u64,
+    /// Widgets whose colors are currently being flashed.
+    flashes: Vec<Flash>,
+    /// Values animated via `animate`.
+    animations: Animations,
+    /// The time at the start of the current frame, in seconds.
+    time: f64,
+    /// The time elapsed since the start of the previous frame, in seconds.
+    dt: f64,
+    /// Timers started via `start_timer`.
+    timers: Timers,
+    /// Non-rectangular hit shapes registered for widgets.
+    hit_shapes: HashMap<UIID, HitShape>,
+    /// Adapts the quality of optional effects to the frame time.
+    pub quality: quality::Governor,
+    /// The canvases declared since the last render.
+    canvases: Canvases,
+    /// Recent pointer samples for widgets with smoothed drags.
+    pointer_samples: HashMap<UIID, Vec<Point>>,
+    /// The most recent positions recorded for widgets that draw a trail,
+    /// oldest first.
+    trails: HashMap<UIID, VecDeque<Point>>,
+    /// Where the next automatically laid out row or column will begin.
+    layout_pos: Point,
+    /// The spacing between automatically laid out widgets.
+    pub layout_spacing: f64,
+    /// The window widths at which larger layouts begin.
+    pub breakpoints: Breakpoints,
+    /// Open overlays and their areas in the order that they were opened.
+    overlays: Vec<(UIID, Point, Dimensions)>,
+    /// Overlays that have been dismissed but not yet closed by their widget.
+    dismissed: Vec<UIID>,
+    /// The active id scopes and the number of ids generated within each.
+    id_scopes: Vec<(UIID, u64)>,
+    /// The user-adjustable zoom applied to the whole UI.
+    ui_scale: f64,
+    /// The ratio of the display's pixels to UI units, i.e. 2.0 on retina
+    /// displays.
+    dpi_factor: f64,
+    /// The window dimensions in pixels.
+    physical_dim: Dimensions,
+    /// The modifier keys that are currently held.
+    modifiers: ModifierKeys,
+    /// The modifier keys that are held, so that releasing one of a pair
+    /// (i.e. LShift) doesn't clear its modifier while the other is held.
+    held_modifier_keys: Vec<input::keyboard::Key>,
+    /// The interaction conventions of the platform.
+    conventions: Conventions,
+    /// The longest time in seconds between clicks for them to count towards
+    /// a double or triple-click.
+    pub double_click_threshold: f64,
+    /// The time and position of the last left click.
+    maybe_last_click: Option<(f64, Point)>,
+    /// The time in seconds for which a key must be held before it repeats.
+    pub key_repeat_delay: f64,
+    /// The number of times per second that a held key repeats.
+    pub key_repeat_rate: f64,
+    /// Held repeatable keys along with the time at which they were pressed
+    /// and the number of times they have repeated.
+    held_keys: Vec<(input::keyboard::Key, f64, u32)>,
+    /// The last raw and adjusted pointer positions for widgets being
+    /// finely dragged.
+    fine_drags: HashMap<UIID, (Point, Point)>,
+    /// Where the pointer was when drags that may be locked to an axis began.
+    drag_origins: HashMap<UIID, Point>,
+    /// Value widgets selected for batch editing.
+    pub selection: Selection,
+    /// Groups of value widgets whose changes propagate to one another.
+    links: Links,
+    /// The drags that began or ended on widgets during the current frame.
+    drags: Vec<(UIID, Drag)>,
+    /// An optional record of the user's changes to value widgets.
+    pub history: History,
+    /// The tooltip text, shortcuts and values registered for widgets.
+    pub tooltips: Tooltips,
+    /// Analysis values fed in each frame to modulate widget colors.
+    pub signals: Signals,
+    /// The on-screen keypad used for entering values on touch screens.
+    pub num_pad: NumPad,
+    /// The input method composition fed in by the backend.
+    pub ime: Ime,
+    /// Periodically saves the session for crash recovery (if enabled).
+    maybe_autosave: Option<Autosave>,
+    /// Text being edited on behalf of widgets that don't own a String.
+    text_buffers: HashMap<UIID, String>,
+    /// The points of the envelope last copied from an EnvelopeEditor.
+    maybe_envelope_clipboard: Option<Vec<(f64, f64, f32)>>,
+    /// The theme being transitioned from and to, along with the number of
+    /// frames elapsed and the length of the transition in frames.
+    maybe_theme_transition: Option<(Theme, Theme, u32, u32)>,
+    /// Commands recorded for layers, drawn at the end of the frame.
+    layer_commands: Commands,
+    /// The depth of the layer being recorded (if any).
+    maybe_layer: Option<Depth>,
+    /// The `Textures` of the images recorded during `render`, so that
+    /// `draw_image` can record to whichever layer is being drawn.
+    maybe_textures: Option<Box<Any>>,
+    /// The rectangles that drawing is clipped to, innermost last.
+    clip_stack: Vec<(Point, Dimensions)>,
+    /// The cached width of each character measured.
+    widths: WidthCache,
+    /// Whether input has arrived or widget state has changed since the
+    /// start of the last frame.
+    changed: bool,
+    /// The union of the areas that have changed since the start of the frame.
+    maybe_damage: Option<(Point, Dimensions)>,
+    /// The area covered by the tooltip when it was last drawn.
+    maybe_tooltip_rect: Option<(Point, Dimensions)>,
+    /// The number of frames for which a widget may go undeclared before its
+    /// state is dropped. If `None`, state is kept for as long as the UiContext.
+    pub state_lifetime: Option<u32>,
+    /// The number of frames that have begun.
+    frame: u64,
+    /// The frame in which each widget was last declared.
+    last_declared: HashMap<UIID, u64>,
+    /// The widgets placed during the current frame, in the order in which
+    /// they were drawn.
+    placed: Vec<UIID>,
+    /// Widgets whose state is kept regardless of the state lifetime.
+    pinned: HashSet<UIID>,
+    /// Widget state that is saved between sessions.
+    persisted: Persisted,
+    /// The widget that key presses are directed to (if any), i.e. the
+    /// NumberDialer that was last clicked.
+    maybe_focus: Option<UIID>,
 }
 
 impl<C> UiContext<C>
@@ -55,67 +274,250 @@ impl<C> UiContext<C>
     /// Constructor for a UiContext.
     pub fn new(glyph_cache: C, theme: Theme) -> UiContext<C> {
         UiContext {
-            data: repeat((widget::Widget::NoWidget, widget::Placing::NoPlace)).take(512).collect(),
+            data: HashMap::new(),
             theme: theme,
             mouse: Mouse::new([0.0, 0.0], ButtonState::Up, ButtonState::Up, ButtonState::Up),
             keys_just_pressed: Vec::with_capacity(10),
             keys_just_released: Vec::with_capacity(10),
             text_just_entered: Vec::with_capacity(10),
-            glyph_cache: glyph_cache,
+            fonts: vec![glyph_cache],
+            current_font: font::DEFAULT,
             prev_event_was_render: false,
             win_w: 0.0,
             win_h: 0.0,
             prev_uiid: 0,
+            flashes: Vec::new(),
+            animations: Animations::new(),
+            time: 0.0,
+            dt: 0.0,
+            timers: Timers::new(),
+            hit_shapes: HashMap::new(),
+            quality: quality::Governor::new(1.0 / 60.0),
+            canvases: Canvases::new(),
+            pointer_samples: HashMap::new(),
+            trails: HashMap::new(),
+            layout_pos: [10.0, 10.0],
+            layout_spacing: 10.0,
+            breakpoints: Breakpoints::new(800.0, 1400.0),
+            overlays: Vec::new(),
+            dismissed: Vec::new(),
+            id_scopes: vec![(ROOT_SCOPE, 0)],
+            ui_scale: 1.0,
+            dpi_factor: 1.0,
+            physical_dim: [0.0, 0.0],
+            modifiers: keyboard::NO_MODIFIER,
+            held_modifier_keys: Vec::new(),
+            conventions: Conventions::current(),
+            double_click_threshold: Conventions::current().double_click_threshold,
+            maybe_last_click: None,
+            key_repeat_delay: 0.5,
+            key_repeat_rate: 30.0,
+            held_keys: Vec::new(),
+            fine_drags: HashMap::new(),
+            drag_origins: HashMap::new(),
+            selection: Selection::new(),
+            links: Links::new(),
+            drags: Vec::new(),
+            history: History::new(),
+            tooltips: Tooltips::new(),
+            signals: Signals::new(),
+            num_pad: NumPad::new(),
+            ime: Ime::new(),
+            maybe_autosave: None,
+            text_buffers: HashMap::new(),
+            maybe_envelope_clipboard: None,
+            maybe_theme_transition: None,
+            layer_commands: layer::new_commands(),
+            clip_stack: Vec::new(),
+            widths: WidthCache::new(),
+            changed: true,
+            maybe_damage: None,
+            maybe_tooltip_rect: None,
+            state_lifetime: None,
+            frame: 0,
+            last_declared: HashMap::new(),
+            placed: Vec::new(),
+            pinned: HashSet::new(),
+            persisted: Persisted::new(),
+            maybe_layer: None,
+            maybe_textures: None,
+            maybe_focus: None,
         }
     }
 
     /// Handle game events and update the state.
     pub fn handle_event<E: GenericEvent + ::std::fmt::Debug>(&mut self, event: &E) {
-        if self.prev_event_was_render {
-            self.flush_input();
-            self.prev_event_was_render = false;
-        }
-        event.render(|args| {
-            self.win_w = args.width as f64;
-            self.win_h = args.height as f64;
+        // A render event's input (its Resize) is handled before the frame
+        // begins, so that it doesn't flush the input gathered for the frame.
+        self.flush_rendered_input();
+        if let Some(input) = backend::from_piston(event) { self.handle_input(input) }
+        event.render(|_| {
             self.prev_event_was_render = true;
+            self.begin_frame();
         });
-        event.mouse_cursor(|x, y| {
-            self.mouse.pos = [x, y];
-        });
-        event.press(|button_type| {
-            use piston::input::Button;
-            use piston::input::MouseButton::Left;
-
-            match button_type {
-                Button::Mouse(button) => {
-                    *match button {
-                        Left => &mut self.mouse.left,
-                        _/*input::mouse::Right*/ => &mut self.mouse.right,
-                        //Middle => &mut self.mouse.middle,
-                    } = ButtonState::Down;
-                },
-                Button::Keyboard(key) => self.keys_just_pressed.push(key),
-            }
-        });
-        event.release(|button_type| {
-            use piston::input::Button;
-            use piston::input::MouseButton::Left;
-
-            match button_type {
-                Button::Mouse(button) => {
-                    *match button {
-                        Left => &mut self.mouse.left,
-                        _/*input::mouse::Right*/ => &mut self.mouse.right,
-                        //Middle => &mut self.mouse.middle,
-                    } = ButtonState::Up;
-                },
-                Button::Keyboard(key) => self.keys_just_released.push(key),
-            }
-        });
-        event.text(|text| {
-            self.text_just_entered.push(text.to_string())
-        });
+    }
+
+    /// Step the time and reset the per-frame state before the frame's
+    /// widgets are drawn.
+    fn begin_frame(&mut self) {
+        self.step_time();
+        self.begin_change_tracking();
+        self.collect_state();
+        self.quality.frame(self.time);
+        self.reset_id_scopes();
+        self.drags.clear();
+        self.placed.clear();
+        self.reset_layout();
+        self.repeat_held_keys();
+        self.signals.update(self.time);
+        self.step_theme_transition();
+    }
+
+    /// Process a batch of events without drawing. This allows the UI state
+    /// to be updated at a different cadence to rendering. Input received
+    /// here is made available to widgets during the next call to `render`.
+    pub fn update<E: GenericEvent + ::std::fmt::Debug>(&mut self, events: &[E]) {
+        self.flush_rendered_input();
+        for event in events.iter() {
+            if let Some(input) = backend::from_piston(event) { self.handle_input(input) }
+        }
+    }
+
+    /// Draw the UI by calling `draw` with the UiContext and a Recorder,
+    /// which collects the frame's render commands. Once `draw` returns, the
+    /// commands are sorted by layer and drawn to the graphics backend at once,
+    /// so widgets always overlap according to their depth.
+    /// Widgets only receive key and text input that has arrived via `update`
+    /// since the previous render, so rendering more often than updating will
+    /// not repeat input.
+    /// Images drawn to the Recorder are recorded with a clone of their
+    /// texture, so textures should be cheap to clone (i.e. an `Rc`).
+    pub fn render<B, F>(&mut self, graphics: &mut B, draw: F)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            F: FnOnce(&mut UiContext<C>, &mut Recorder<<C as CharacterCache>::Texture>),
+            <C as CharacterCache>::Texture: Clone + 'static,
+    {
+        // Input that was already seen by the previous render is dropped, while
+        // input that has arrived since is kept for this one.
+        self.flush_rendered_input();
+        self.begin_frame();
+        let textures: Textures<<C as CharacterCache>::Texture> = Rc::new(RefCell::new(Vec::new()));
+        let recorder = Recorder::with_textures(layer::DEFAULT, self.layer_commands.clone(), textures.clone());
+        self.maybe_textures = Some(Box::new(textures.clone()));
+        self.draw_to_recorder(recorder, draw);
+        self.maybe_textures = None;
+        self.draw_layers_with_textures(graphics, &textures.borrow());
+        self.prev_event_was_render = true;
+    }
+
+    /// Draw the UI via a Renderer (rather than piston's graphics), as `render`.
+    pub fn render_to<R, F>(&mut self, renderer: &mut R, draw: F)
+        where
+            R: Renderer<Texture = <C as CharacterCache>::Texture>,
+            F: FnOnce(&mut UiContext<C>, &mut Recorder<<C as CharacterCache>::Texture>),
+            <C as CharacterCache>::Texture: Clone + 'static,
+    {
+        self.render(&mut RendererGraphics(renderer), draw);
+    }
+
+    /// Update the window dimensions and input state from the given input.
+    /// Back ends that don't use piston's event loop should call this for
+    /// each of their window's events, then draw via `render` or `render_to`.
+    pub fn handle_input(&mut self, input: Input) {
+        self.flush_rendered_input();
+        match input {
+            Input::Resize(w, h) => {
+                let physical_dim = [w as f64, h as f64];
+                if physical_dim != self.physical_dim { self.damage_all() }
+                self.physical_dim = physical_dim;
+                let scale = self.scale_factor();
+                self.win_w = self.physical_dim[0] / scale;
+                self.win_h = self.physical_dim[1] / scale;
+                self.reset_layout();
+            },
+            Input::Move(x, y) => {
+                self.changed = true;
+                let scale = self.scale_factor();
+                self.mouse.pos = [x / scale, y / scale];
+                self.selection.drag_marquee([x / scale, y / scale]);
+            },
+            Input::Scroll(x, y) => {
+                self.changed = true;
+                self.mouse.scroll[0] += x;
+                self.mouse.scroll[1] += y;
+            },
+            Input::Press(button_type) => {
+                use piston::input::MouseButton::Left;
+
+                self.changed = true;
+                match button_type {
+                    Button::Mouse(button) => {
+                        if let Some(state) = self.mouse_button_state(button) {
+                            *state = ButtonState::Down;
+                        }
+                        let mouse_pos = self.mouse.pos;
+                        if button == Left { self.count_click(mouse_pos) }
+                        self.dismiss_overlays_not_under(mouse_pos);
+                        if button == Left && self.selection.enabled && !self.is_over_any_widget(mouse_pos) {
+                            self.selection.begin_marquee(mouse_pos);
+                        }
+                    },
+                    Button::Keyboard(key) => {
+                        use piston::input::keyboard::Key;
+                        let modifier = ModifierKeys::from_key(key);
+                        if modifier != keyboard::NO_MODIFIER && !self.held_modifier_keys.contains(&key) {
+                            self.held_modifier_keys.push(key);
+                        }
+                        self.modifiers.insert(modifier);
+                        if is_repeatable(key) && !self.held_keys.iter().any(|&(k, _, _)| k == key) {
+                            self.held_keys.push((key, precise_time_s(), 0));
+                        }
+                        match key {
+                            Key::Escape => self.dismiss_top_overlay(),
+                            Key::Equals if self.modifiers.contains(self.conventions.command_modifier) => {
+                                let scale = self.ui_scale * UI_SCALE_STEP;
+                                self.set_ui_scale(scale);
+                            },
+                            Key::Minus if self.modifiers.contains(self.conventions.command_modifier) => {
+                                let scale = self.ui_scale / UI_SCALE_STEP;
+                                self.set_ui_scale(scale);
+                            },
+                            Key::D0 if self.modifiers.contains(self.conventions.command_modifier) => self.set_ui_scale(1.0),
+                            _ => (),
+                        }
+                        self.keys_just_pressed.push(key)
+                    },
+                }
+            },
+            Input::Release(button_type) => {
+                use piston::input::MouseButton::Left;
+
+                self.changed = true;
+                match button_type {
+                    Button::Mouse(button) => {
+                        if let Some(state) = self.mouse_button_state(button) {
+                            *state = ButtonState::Up;
+                        }
+                        if button == Left {
+                            let placings = self.get_placings();
+                            self.selection.end_marquee(&placings);
+                        }
+                    },
+                    Button::Keyboard(key) => {
+                        self.held_modifier_keys.retain(|&k| k != key);
+                        self.modifiers = self.held_modifier_keys.iter()
+                            .fold(keyboard::NO_MODIFIER, |modifiers, &k| modifiers | ModifierKeys::from_key(k));
+                        self.held_keys.retain(|&(k, _, _)| k != key);
+                        self.keys_just_released.push(key)
+                    },
+                }
+            },
+            Input::Text(text) => {
+                self.changed = true;
+                self.text_just_entered.push(text)
+            },
+        }
     }
 
     /// Return a reference to a `Character` from the GlyphCache.
@@ -124,12 +526,60 @@ impl<C> UiContext<C>
         size: FontSize,
         ch: char
     ) -> &Character<<C as CharacterCache>::Texture> {
-        self.glyph_cache.character(size, ch)
+        self.fonts[self.current_font].character(size, ch)
+    }
+
+    /// Register another font, returning the id with which widgets may
+    /// select it.
+    pub fn add_font(&mut self, glyph_cache: C) -> FontId {
+        self.fonts.push(glyph_cache);
+        self.fonts.len() - 1
     }
 
-    /// Return the width of a 'Character'.
+    /// Replace the glyph cache of a registered font, i.e. after the font
+    /// was reloaded. The font's cached character widths are forgotten.
+    pub fn replace_font(&mut self, font: FontId, glyph_cache: C) {
+        if font < self.fonts.len() {
+            self.fonts[font] = glyph_cache;
+            self.widths.clear();
+        }
+    }
+
+    /// Draw all following text in the given font, returning the font that
+    /// was previously current. Unknown ids select the default font.
+    pub fn set_font(&mut self, font: FontId) -> FontId {
+        let prev = self.current_font;
+        self.current_font = if font < self.fonts.len() { font } else { font::DEFAULT };
+        prev
+    }
+
+    /// The font in which text is currently drawn.
+    pub fn font(&self) -> FontId {
+        self.current_font
+    }
+
+    /// Return the width of a 'Character'. Widths are cached, so this only
+    /// queries the CharacterCache the first time each character is measured.
     pub fn get_character_w(&mut self, size: FontSize, ch: char) -> f64 {
-        self.get_character(size, ch).width()
+        let font = self.current_font;
+        match self.widths.get(font, size, ch) {
+            Some(w) => w,
+            None => {
+                let w = self.get_character(size, ch).width();
+                self.widths.insert(font, size, ch, w);
+                w
+            },
+        }
+    }
+
+    /// Flush the input if it has already been seen by a render, so that the
+    /// next frame's input begins empty. Input received since the last render
+    /// is kept.
+    fn flush_rendered_input(&mut self) {
+        if self.prev_event_was_render {
+            self.flush_input();
+            self.prev_event_was_render = false;
+        }
     }
 
     /// Flush all stored keys.
@@ -137,6 +587,8 @@ impl<C> UiContext<C>
         self.keys_just_pressed.clear();
         self.keys_just_released.clear();
         self.text_just_entered.clear();
+        self.ime.flush();
+        self.mouse.scroll = [0.0, 0.0];
     }
 
     /// Draws text
@@ -150,24 +602,349 @@ impl<C> UiContext<C>
     )
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        // Text drawn to a layer is recorded along with the layer's shapes.
+        let maybe_clip = self.layer_commands.borrow().maybe_clip;
+        if let Some(depth) = self.maybe_layer {
+            let command = layer::Command::Text(pos, size, color, self.current_font, text.to_string(), maybe_clip);
+            self.layer_commands.borrow_mut().commands.push((depth, command));
+            return
+        }
+        self.draw_clipped_text(graphics, pos, size, color, text, maybe_clip);
+    }
+
+    /// Draws text clipped to the scissor rectangle (if any).
+    fn draw_clipped_text<B>(
+        &mut self,
+        graphics: &mut B,
+        pos: Point,
+        size: FontSize,
+        color: Color,
+        text: &str,
+        maybe_clip: Option<Scissor>
+    )
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
     {
         use graphics::text::Text;
         use graphics::RelativeTransform;
         use std::num::Float;
 
+        // Rasterize glyphs at the zoomed size so that text stays crisp.
         let Color(col) = color;
-        let draw_state = graphics::default_draw_state();
+        let scale = self.scale_factor();
+        let draw_state = &clip::apply(graphics::default_draw_state(), maybe_clip);
         let transform = graphics::abs_transform(self.win_w, self.win_h)
-                        .trans(pos[0].ceil(), pos[1].ceil() + size as f64);
-        Text::colored(col, size).draw(
+                        .trans(pos[0].ceil(), pos[1].ceil() + size as f64)
+                        .zoom(1.0 / scale);
+        Text::colored(col, (size as f64 * scale).round() as FontSize).draw(
             text,
-            &mut self.glyph_cache,
+            &mut self.fonts[self.current_font],
             draw_state,
             transform,
             graphics
         );
     }
 
+
+    /// Draws a value readout. If the theme enables tabular figures, each
+    /// digit is centered within a slot the width of the widest digit so
+    /// that rapidly changing values don't jitter.
+    pub fn draw_value_text<B>(
+        &mut self,
+        graphics: &mut B,
+        pos: Point,
+        size: FontSize,
+        color: Color,
+        text: &str,
+    )
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        if !self.theme.tabular_figures.unwrap_or(false) {
+            return self.draw_text(graphics, pos, size, color, text);
+        }
+        let digit_w = label::tabular_digit_width(self, size);
+        let mut x = pos[0];
+        let mut buf = String::new();
+        for ch in text.chars() {
+            buf.clear();
+            buf.push(ch);
+            let ch_w = self.get_character_w(size, ch);
+            let slot_w = if ch.is_digit(10) { digit_w } else { ch_w };
+            self.draw_text(graphics, [x + (slot_w - ch_w) / 2.0, pos[1]], size, color, &buf);
+            x += slot_w;
+        }
+    }
+
+    /// Draws text with additional space between characters and words. Each
+    /// character is placed by its advance width without kerning.
+    pub fn draw_spaced_text<B>(
+        &mut self,
+        graphics: &mut B,
+        pos: Point,
+        size: FontSize,
+        color: Color,
+        text: &str,
+        spacing: label::Spacing,
+    )
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        let mut x = pos[0];
+        let mut buf = String::new();
+        for ch in text.chars() {
+            buf.clear();
+            buf.push(ch);
+            self.draw_text(graphics, [x, pos[1]], size, color, &buf);
+            x += self.get_character_w(size, ch) + spacing.after(ch);
+        }
+    }
+
+    /// Draw a line between two points. This and the following primitives
+    /// are drawn in the same coordinates as widgets, so they may be used
+    /// to annotate the UI, i.e. connecting two widgets found via `rect_of`.
+    pub fn draw_line<B>(&mut self, graphics: &mut B, a: Point, b: Point, width: f64, color: Color)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        primitive::line(self.win_w, self.win_h, graphics, a, b, width, color);
+    }
+
+    /// Draw a line through each of the points in turn.
+    pub fn draw_polyline<B>(&mut self, graphics: &mut B, points: &[Point], width: f64, color: Color)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        primitive::polyline(self.win_w, self.win_h, graphics, points, width, color);
+    }
+
+    /// Draw a filled circle at the theme's circle quality.
+    pub fn draw_circle<B>(&mut self, graphics: &mut B, center: Point, radius: f64, color: Color)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        let quality = self.theme.circle_quality.unwrap_or(CircleQuality::default());
+        primitive::circle_with_quality(self.win_w, self.win_h, graphics, center, radius, color, quality);
+    }
+
+    /// Draw a filled polygon. The polygon may be concave.
+    pub fn draw_polygon<B>(&mut self, graphics: &mut B, points: &[Point], color: Color)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        primitive::polygon(self.win_w, self.win_h, graphics, points, color);
+    }
+
+    /// Draw the texture stretched over the given rectangle. During `render`
+    /// the image is recorded to the current layer along with a clone of the
+    /// texture, clipped like the layer's shapes and text.
+    pub fn draw_image<B>(&mut self, graphics: &mut B, texture: &<C as CharacterCache>::Texture,
+                         pos: Point, dim: Dimensions)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            <C as CharacterCache>::Texture: Clone + 'static,
+    {
+        let image = graphics::Image::new().rect([pos[0], pos[1], dim[0], dim[1]]);
+        let draw_state = graphics::default_draw_state();
+        let transform = graphics::abs_transform(self.win_w, self.win_h);
+        let maybe_textures = self.maybe_textures.as_ref()
+            .and_then(|textures| textures.downcast_ref::<Textures<<C as CharacterCache>::Texture>>())
+            .map(|textures| textures.clone());
+        match (self.maybe_layer, maybe_textures) {
+            (Some(depth), Some(textures)) => {
+                let mut recorder = Recorder::with_textures(depth, self.layer_commands.clone(), textures);
+                image.draw(texture, draw_state, transform, &mut recorder);
+            },
+            _ => image.draw(texture, draw_state, transform, graphics),
+        }
+    }
+
+    /// Draw the selection marquee (if one is being dragged) and an outline
+    /// around each widget selected for batch editing.
+    pub fn draw_selection<B>(&mut self, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        let color = self.theme.shape_color.highlighted();
+        for &ui_id in self.selection.selected().iter() {
+            if let widget::Placing::Place(x, y, w, h) = self.get_placing(ui_id) {
+                let (x, y, w, h) = (x - 2.0, y - 2.0, w + 4.0, h + 4.0);
+                primitive::polygon_outline(self.win_w, self.win_h, graphics,
+                                           &[[x, y], [x + w, y], [x + w, y + h], [x, y + h]],
+                                           1.0, color);
+            }
+        }
+        if let Some((a, b)) = self.selection.marquee() {
+            let (pos, dim) = selection::marquee_rect(a, b);
+            let mut fill = color;
+            fill.set_a(0.2);
+            graphics::Rectangle::new(fill.0)
+                .draw([pos[0], pos[1], dim[0], dim[1]], graphics::default_draw_state(),
+                      graphics::abs_transform(self.win_w, self.win_h), graphics);
+            let (x, y, w, h) = (pos[0], pos[1], dim[0], dim[1]);
+            primitive::polygon_outline(self.win_w, self.win_h, graphics,
+                                       &[[x, y], [x + w, y], [x + w, y + h], [x, y + h]],
+                                       1.0, color);
+        }
+    }
+
+    /// Draw a panel listing the recorded changes from newest to oldest.
+    /// Clicking a change reverts it.
+    pub fn draw_history<B>(&mut self, graphics: &mut B, pos: Point, dim: Dimensions)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        use std::num::Float;
+        let size = self.theme.font_size_small;
+        let pad = 4.0;
+        let row_h = size as f64 + pad * 2.0;
+        let frame = Some((self.theme.frame_width, self.theme.frame_color));
+        rectangle::draw(self.win_w, self.win_h, graphics, rectangle::State::Normal,
+                        pos, dim, frame, self.theme.background_color);
+
+        // Find the row under the mouse and handle clicks.
+        let mouse = self.mouse;
+        let num_rows = (dim[1] / row_h).floor() as usize;
+        let num_changes = self.history.changes().len();
+        let maybe_hovered = if rectangle::is_over(pos, mouse.pos, dim) {
+            let row = ((mouse.pos[1] - pos[1]) / row_h).floor() as usize;
+            if row < num_rows && row < num_changes { Some(row) } else { None }
+        } else { None };
+        let now = self.time;
+        match mouse.left {
+            ButtonState::Down => if self.history.maybe_pressed_row.is_none() {
+                self.history.maybe_pressed_row = maybe_hovered;
+            },
+            ButtonState::Up => {
+                if let (Some(pressed), Some(hovered)) = (self.history.maybe_pressed_row, maybe_hovered) {
+                    if pressed == hovered { self.history.revert(num_changes - 1 - hovered, now) }
+                }
+                self.history.maybe_pressed_row = None;
+            },
+        }
+
+        // Draw the rows, newest first.
+        let changes: Vec<_> = self.history.changes().iter().rev().take(num_rows).cloned().collect();
+        for (row, change) in changes.iter().enumerate() {
+            let row_pos = [pos[0], pos[1] + row as f64 * row_h];
+            if Some(row) == maybe_hovered {
+                rectangle::draw(self.win_w, self.win_h, graphics, rectangle::State::Highlighted,
+                                row_pos, [dim[0], row_h], None, self.theme.shape_color);
+            }
+            let text = format!("{}: {:.2} -> {:.2} ({:.0}s ago)",
+                               change.ui_id, change.old, change.new, now - change.time);
+            let color = self.theme.label_color;
+            self.draw_text(graphics, [row_pos[0] + pad, row_pos[1] + pad], size, color, &text);
+        }
+    }
+
+    /// Draw the tooltip for the widget under the mouse once it has been
+    /// hovered for longer than the tooltip delay. The tooltip is drawn to
+    /// the top layer, so this may be called at any point in the frame.
+    pub fn draw_tooltip(&mut self) {
+        let mouse_pos = self.mouse.pos;
+        // The hovered widget is the last drawn this frame under the mouse.
+        let maybe_hovered = self.placed.iter().rev().cloned().find(|&ui_id| match self.get_placing(ui_id) {
+            widget::Placing::Place(x, y, w, h) => self.is_over(ui_id, [x, y], [w, h], mouse_pos),
+            widget::Placing::NoPlace => false,
+        });
+        let maybe_text = self.tooltips.hover(maybe_hovered, self.time)
+            .and_then(|ui_id| self.tooltips.compose(ui_id));
+        let text = match maybe_text {
+            Some(text) => text,
+            None => return self.set_tooltip_rect(None),
+        };
+        let size = self.theme.font_size_small;
+        let pad = 4.0;
+        let dim = [label::width(self, size, &text) + pad * 2.0, size as f64 + pad * 2.0];
+        let pos = overlay::slide([mouse_pos[0] + 12.0, mouse_pos[1] + 18.0], dim, self.win_w, self.win_h);
+        let frame = Some((self.theme.frame_width, self.theme.frame_color));
+        let color = self.theme.shape_color;
+        self.set_tooltip_rect(Some((pos, dim)));
+        self.draw_layer(layer::TOOLTIP, |uic, graphics| {
+            rectangle::draw(uic.win_w, uic.win_h, graphics, rectangle::State::Normal, pos, dim, frame, color);
+            let text_color = uic.theme.label_color;
+            uic.draw_text(graphics, [pos[0] + pad, pos[1] + pad], size, text_color, &text);
+        });
+    }
+
+    /// Damage the tooltip's previous and new areas if it has moved.
+    fn set_tooltip_rect(&mut self, maybe_rect: Option<(Point, Dimensions)>) {
+        if maybe_rect == self.maybe_tooltip_rect { return }
+        if let Some((pos, dim)) = self.maybe_tooltip_rect { self.damage(pos, dim) }
+        if let Some((pos, dim)) = maybe_rect { self.damage(pos, dim) }
+        self.maybe_tooltip_rect = maybe_rect;
+    }
+
+    /// Draw to the layer at the given depth by calling `f` with the
+    /// UiContext and a graphics backend that records what is drawn to it.
+    /// Layers are drawn in order of depth by `draw_layers`. Outside of
+    /// `render`, they're drawn over widgets drawn directly to the backend.
+    /// Images should be drawn via `draw_image`, as only the Recorder given
+    /// by `render` records images drawn to it directly.
+    pub fn draw_layer<F>(&mut self, depth: Depth, f: F)
+        where
+            F: FnOnce(&mut UiContext<C>, &mut Recorder<<C as CharacterCache>::Texture>)
+    {
+        let recorder = Recorder::new(depth, self.layer_commands.clone());
+        self.draw_to_recorder(recorder, f);
+    }
+
+    /// Call `f` with the UiContext and the Recorder, within the Recorder's layer.
+    fn draw_to_recorder<F>(&mut self, mut recorder: Recorder<<C as CharacterCache>::Texture>, f: F)
+        where
+            F: FnOnce(&mut UiContext<C>, &mut Recorder<<C as CharacterCache>::Texture>)
+    {
+        let maybe_prev_layer = self.maybe_layer;
+        self.maybe_layer = Some(recorder.depth());
+        f(self, &mut recorder);
+        self.maybe_layer = maybe_prev_layer;
+    }
+
+    /// Draw the layers recorded this frame, in order of depth. Runs of
+    /// triangles of the same color are batched into a single draw call.
+    /// This is called at the end of `render`, otherwise it should be called
+    /// after all widgets have been drawn.
+    pub fn draw_layers<B>(&mut self, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        self.draw_layers_with_textures(graphics, &[]);
+    }
+
+    /// Draw the layers as `draw_layers`, drawing recorded images with the
+    /// given textures.
+    fn draw_layers_with_textures<B>(&mut self, graphics: &mut B,
+                                    textures: &[<C as CharacterCache>::Texture])
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        let prev_font = self.current_font;
+        for command in layer::batch(layer::take_sorted(&self.layer_commands)).into_iter() {
+            match command {
+                layer::Command::Clear(color) => graphics.clear(color),
+                layer::Command::Triangles(draw_state, color, chunks) => {
+                    graphics.tri_list(&draw_state, &color, |f| {
+                        for chunk in chunks.iter() { f(&chunk[..]) }
+                    });
+                },
+                layer::Command::Text(pos, size, color, font, text, maybe_clip) => {
+                    self.current_font = font;
+                    self.draw_clipped_text(graphics, pos, size, color, &text, maybe_clip);
+                },
+                layer::Command::Image(draw_state, color, idx, chunks) => {
+                    if let Some(texture) = textures.get(idx) {
+                        graphics.tri_list_uv(&draw_state, &color, texture, |f| {
+                            for &(ref xy, ref uv) in chunks.iter() { f(&xy[..], &uv[..]) }
+                        });
+                    }
+                },
+            }
+        }
+        self.current_font = prev_font;
+    }
+
 }
 
 impl<C> UiContext<C> {
@@ -176,6 +953,23 @@ impl<C> UiContext<C> {
         self.mouse
     }
 
+    /// The interaction conventions of the platform.
+    pub fn conventions(&self) -> Conventions {
+        self.conventions
+    }
+
+    /// Override the platform conventions, i.e. to follow another OS's
+    /// conventions or for testing. This also resets the double-click threshold.
+    pub fn set_conventions(&mut self, conventions: Conventions) {
+        self.double_click_threshold = conventions.double_click_threshold;
+        self.conventions = conventions;
+    }
+
+    /// Return the modifier keys that are currently held.
+    pub fn get_modifier_keys(&self) -> ModifierKeys {
+        self.modifiers
+    }
+
     /// Return the vector of recently pressed keys.
     pub fn get_pressed_keys(&self) -> Vec<input::keyboard::Key> {
         self.keys_just_pressed.clone()
@@ -186,59 +980,848 @@ impl<C> UiContext<C> {
         self.text_just_entered.clone()
     }
 
+    /// Whether the key was pressed this frame along with the platform's
+    /// command modifier, i.e. Ctrl+C or Cmd+C.
+    pub fn command_key_pressed(&self, key: input::keyboard::Key) -> bool {
+        self.modifiers.contains(self.conventions.command_modifier)
+            && self.keys_just_pressed.iter().any(|&k| k == key)
+    }
+
+    /// The envelope last copied from an EnvelopeEditor (if any), as the
+    /// X, Y and curve of each point where X and Y are fractions (0.0 to 1.0)
+    /// of the ranges of the editor it was copied from.
+    pub fn envelope_clipboard(&self) -> Option<&[(f64, f64, f32)]> {
+        self.maybe_envelope_clipboard.as_ref().map(|points| &points[..])
+    }
+
+    /// Replace the envelope clipboard, i.e. with points read from the OS
+    /// clipboard, in the form described by `envelope_clipboard`.
+    pub fn set_envelope_clipboard(&mut self, points: Vec<(f64, f64, f32)>) {
+        self.maybe_envelope_clipboard = Some(points);
+    }
+
+    /// End the input method's composition, entering the given text as
+    /// though it had been typed.
+    pub fn commit_composition(&mut self, text: &str) {
+        self.ime.commit();
+        if text.len() > 0 { self.text_just_entered.push(text.to_string()) }
+    }
+
     /// Return a mutable reference to the widget that matches the given ui_id
     pub fn get_widget(&mut self, ui_id: UIID, default: Widget) -> &mut Widget {
-        let ui_id_idx = ui_id as usize;
-        if self.data.len() > ui_id_idx {
-            match &mut self.data[ui_id_idx] {
-                &mut (widget::Widget::NoWidget, _) => {
-                    match &mut self.data[ui_id_idx] {
-                        &mut (ref mut widget, _) => {
-                            *widget = default; widget
-                        }
-                    }
-                },
-                _ => {
-                    match &mut self.data[ui_id_idx] {
-                        &mut (ref mut widget, _) => widget
-                    }
-                },
-            }
-        } else {
-            if ui_id_idx >= self.data.len() {
-                let num_to_push = ui_id_idx - self.data.len();
-                let mut vec: Vec<(widget::Widget, widget::Placing)> = repeat((widget::Widget::NoWidget, widget::Placing::NoPlace)).take(num_to_push).collect();
-                vec.push((default, widget::Placing::NoPlace));
-                self.data.extend(vec.into_iter());
-            } else {
-                self.data[ui_id_idx] = (default, widget::Placing::NoPlace);
-            }
-            match &mut self.data[ui_id_idx] {
-                &mut (ref mut widget, _) => widget,
-            }
+        self.last_declared.insert(ui_id, self.frame);
+        let &mut (ref mut widget, _) = match self.data.entry(ui_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert((default, widget::Placing::NoPlace)),
+        };
+        if let widget::Widget::NoWidget = *widget { *widget = default }
+        widget
+    }
+
+    /// Return a copy of the widget state stored for the given ui_id
+    /// without inserting a default.
+    pub fn get_widget_state(&self, ui_id: UIID) -> Widget {
+        match self.data.get(&ui_id) {
+            Some(&(widget, _)) => widget,
+            None => widget::Widget::NoWidget,
         }
     }
 
     /// Set the Placing for a particular widget.
     pub fn set_place(&mut self, ui_id: UIID, pos: Point, dim: Dimensions) {
-        match &mut self.data[ui_id as usize] {
-            &mut (_, ref mut placing) => {
-                *placing = widget::Placing::Place(pos[0], pos[1], dim[0], dim[1])
-            }
+        let placing = widget::Placing::Place(pos[0], pos[1], dim[0], dim[1]);
+        self.last_declared.insert(ui_id, self.frame);
+        self.placed.push(ui_id);
+        let no_prev = ([pos[0], pos[1]], [0.0, 0.0]);
+        let maybe_prev = match self.data.entry(ui_id) {
+            Entry::Occupied(entry) => {
+                let prev = &mut entry.into_mut().1;
+                let maybe_prev = match *prev {
+                    _ if *prev == placing => None,
+                    widget::Placing::Place(x, y, w, h) => Some(([x, y], [w, h])),
+                    widget::Placing::NoPlace => Some(no_prev),
+                };
+                *prev = placing;
+                maybe_prev
+            },
+            Entry::Vacant(entry) => { entry.insert((widget::Widget::NoWidget, placing)); Some(no_prev) },
+        };
+        if let Some(prev) = maybe_prev {
+            self.damage(prev.0, prev.1);
+            self.damage(pos, dim);
         }
         self.prev_uiid = ui_id;
     }
 
+    /// Note that something has changed that requires the UI to be redrawn.
+    /// The whole window is considered damaged, so `damage` should be used
+    /// instead where the changed area is known.
+    pub fn mark_changed(&mut self) {
+        self.damage_all();
+    }
+
+    /// Note that the given area has changed and needs to be redrawn. This is
+    /// called when a widget's state or placing changes, and should be called
+    /// by the application when content that it provides changes (i.e. the
+    /// text of a Label).
+    pub fn damage(&mut self, pos: Point, dim: Dimensions) {
+        self.changed = true;
+        self.maybe_damage = Some(match self.maybe_damage {
+            Some(damage) => rectangle::union(damage, (pos, dim)),
+            None => (pos, dim),
+        });
+    }
+
+    /// Note that the whole window needs to be redrawn.
+    pub fn damage_all(&mut self) {
+        let (win_w, win_h) = (self.win_w, self.win_h);
+        self.damage([0.0, 0.0], [win_w, win_h]);
+    }
+
+    /// The union of the areas that have changed since the start of the frame
+    /// (if any), in UI coordinates. Backends that support partial presentation
+    /// may re-render only this area once the frame has been drawn.
+    pub fn damaged_rect(&self) -> Option<(Point, Dimensions)> {
+        self.maybe_damage
+    }
+
+    /// Keep the given widget's state even while it isn't declared, i.e. the
+    /// collapsed state of tree nodes that are currently scrolled out of view.
+    pub fn pin_state(&mut self, ui_id: UIID) {
+        self.pinned.insert(ui_id);
+    }
+
+    /// Keep the given widget's state for another frame without it being
+    /// updated or drawn, i.e. while it's hidden.
+    pub fn keep_state(&mut self, ui_id: UIID) {
+        self.last_declared.insert(ui_id, self.frame);
+    }
+
+    /// Direct key presses to the given widget.
+    pub fn focus(&mut self, ui_id: UIID) {
+        self.maybe_focus = Some(ui_id);
+    }
+
+    /// Stop directing key presses to the given widget (if they are).
+    pub fn unfocus(&mut self, ui_id: UIID) {
+        if self.maybe_focus == Some(ui_id) { self.maybe_focus = None }
+    }
+
+    /// Whether or not key presses are directed to the given widget.
+    pub fn is_focused(&self, ui_id: UIID) -> bool {
+        self.maybe_focus == Some(ui_id)
+    }
+
+    /// Allow the given widget's state to be dropped once it goes undeclared
+    /// for longer than the state lifetime.
+    pub fn unpin_state(&mut self, ui_id: UIID) {
+        self.pinned.remove(&ui_id);
+    }
+
+    /// Begin a new frame, dropping the state of unpinned widgets that haven't
+    /// been declared within the state lifetime.
+    fn collect_state(&mut self) {
+        self.frame += 1;
+        let lifetime = match self.state_lifetime {
+            Some(lifetime) => lifetime as u64,
+            None => return,
+        };
+        let frame = self.frame;
+        let expired: Vec<UIID> = self.last_declared.iter()
+            .filter(|&(ui_id, &declared)| frame - declared > lifetime && !self.pinned.contains(ui_id))
+            .map(|(&ui_id, _)| ui_id)
+            .collect();
+        for ui_id in expired.iter() {
+            self.last_declared.remove(ui_id);
+            self.data.remove(ui_id);
+            self.text_buffers.remove(ui_id);
+            self.pointer_samples.remove(ui_id);
+            self.trails.remove(ui_id);
+            self.fine_drags.remove(ui_id);
+            self.drag_origins.remove(ui_id);
+            self.hit_shapes.remove(ui_id);
+            self.close_overlay(*ui_id);
+            self.tooltips.forget_state(*ui_id);
+            self.animations.forget(*ui_id);
+            self.unfocus(*ui_id);
+        }
+    }
+
+    /// Reset the changes tracked for the previous frame at the start of a new
+    /// frame, damaging the areas of anything that is animating.
+    fn begin_change_tracking(&mut self) {
+        self.changed = false;
+        self.maybe_damage = None;
+        let now = self.time;
+        if self.maybe_theme_transition.is_some() || self.signals.is_active()
+        || self.animations.is_active(now) {
+            return self.damage_all()
+        }
+        let flashing: Vec<UIID> = self.flashes.iter()
+            .filter(|flash| now - flash.start < flash.duration)
+            .map(|flash| flash.ui_id)
+            .collect();
+        for ui_id in flashing.into_iter() {
+            if let Some((pos, dim)) = self.rect_of(ui_id) { self.damage(pos, dim) }
+        }
+    }
+
+    /// Whether the next frame would differ from the last, because input has
+    /// arrived, a widget's state changed while it was last drawn or something
+    /// is animating. Applications may skip rendering (and swapping buffers)
+    /// while this is false to save power when the UI is idle.
+    pub fn needs_redraw(&self) -> bool {
+        let now = precise_time_s();
+        self.changed
+            || self.maybe_theme_transition.is_some()
+            || self.held_keys.len() > 0
+            || self.flashes.iter().any(|flash| now - flash.start < flash.duration)
+            || self.signals.is_active()
+            || self.animations.is_active(now)
+            || self.tooltips.is_pending(now)
+            || self.timers.next_deadline().map_or(false, |deadline| deadline <= now)
+    }
+
+    /// The time at the start of the current frame in seconds. Widgets use this
+    /// rather than reading the clock so that everything drawn in a frame is
+    /// in step.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// The time elapsed since the previous frame in seconds, i.e. for
+    /// decaying meters. This is zero on the first frame.
+    pub fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    /// Advance the frame clock.
+    fn step_time(&mut self) {
+        let now = precise_time_s();
+        self.dt = if self.time > 0.0 { now - self.time } else { 0.0 };
+        self.time = now;
+    }
+
+    /// Start a timer due `delay` seconds from the start of this frame.
+    /// Starting a running timer restarts it, so restarting it on every change
+    /// debounces the changes, i.e. to search once typing pauses.
+    pub fn start_timer(&mut self, ui_id: UIID, delay: f64) {
+        let now = self.time;
+        self.timers.start(ui_id, delay, now);
+    }
+
+    /// Stop the timer without it firing.
+    pub fn cancel_timer(&mut self, ui_id: UIID) {
+        self.timers.cancel(ui_id);
+    }
+
+    /// Whether the timer is due, which is true for only one frame.
+    pub fn timer_fired(&mut self, ui_id: UIID) -> bool {
+        let now = self.time;
+        self.timers.fired(ui_id, now)
+    }
+
+    /// The time at which the next timer is due (if any), so that applications
+    /// skipping idle frames know when to wake.
+    pub fn next_timer_deadline(&self) -> Option<f64> {
+        self.timers.next_deadline()
+    }
+
+    /// Return the current value of a value animated from `from` to `to` over
+    /// `duration` seconds, i.e. for a hover transition or a section sliding
+    /// open. Call this each frame with the value's target; when the target
+    /// changes the value is animated towards the new one from wherever it is.
+    /// Animations are keyed by UIID and widgets animate under their own, so
+    /// applications should use UIIDs of their own for their values.
+    pub fn animate(&mut self, ui_id: UIID, from: f64, to: f64, duration: f64, easing: Easing) -> f64 {
+        self.animations.animate(ui_id, from, to, duration, easing, self.time)
+    }
+
+    /// Flash the widget with the given UIID to the given color, fading
+    /// back to its regular color over `duration` seconds. This is useful
+    /// for drawing attention to a widget, i.e. after a failed action.
+    pub fn flash_widget(&mut self, ui_id: UIID, color: Color, duration: f64) {
+        let now = self.time;
+        self.flashes.retain(|flash| flash.ui_id != ui_id && now - flash.start < flash.duration);
+        self.flashes.push(Flash { ui_id: ui_id, color: color, start: now, duration: duration });
+    }
+
+    /// Return the given color with any signal modulation and active flash
+    /// for the widget applied.
+    pub fn flash_color(&self, ui_id: UIID, color: Color) -> Color {
+        let color = self.signals.modulate(ui_id, color);
+        match self.flashes.iter().find(|flash| flash.ui_id == ui_id) {
+            Some(flash) => {
+                let elapsed = self.time - flash.start;
+                if elapsed >= flash.duration { color }
+                else { self.mix_colors(flash.color, color, (elapsed / flash.duration) as f32) }
+            },
+            None => color,
+        }
+    }
+
+    /// Replace the theme immediately. This may be called at any time,
+    /// cancelling any transition in progress.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.maybe_theme_transition = None;
+        self.theme = theme;
+        self.damage_all();
+    }
+
+    /// Transition to the given theme over the given number of frames,
+    /// interpolating its colors so that the change doesn't pop. The
+    /// transition begins from the theme as it is currently displayed.
+    pub fn transition_theme(&mut self, theme: Theme, frames: u32) {
+        if frames == 0 { return self.set_theme(theme) }
+        self.maybe_theme_transition = Some((self.theme.clone(), theme, 0, frames));
+    }
+
+    /// Advance the theme transition (if any) by a frame.
+    fn step_theme_transition(&mut self) {
+        let is_done = match self.maybe_theme_transition {
+            Some((ref from, ref to, ref mut frame, frames)) => {
+                *frame += 1;
+                self.theme = from.interpolate(to, *frame as f32 / frames as f32);
+                *frame >= frames
+            },
+            None => false,
+        };
+        if is_done {
+            if let Some((_, to, _, _)) = self.maybe_theme_transition.take() {
+                self.theme = to;
+            }
+        }
+    }
+
+    /// Return the color `amount` of the way from `a` to `b`, interpolating in
+    /// linear space if the theme enables linear blending.
+    pub fn mix_colors(&self, a: Color, b: Color, amount: f32) -> Color {
+        if self.theme.linear_blending.unwrap_or(false) { a.mix_linear(b, amount) }
+        else { a.mix(b, amount) }
+    }
+
+    /// Register the shape used for hit-testing the widget with the given UIID.
+    pub fn set_hit_shape(&mut self, ui_id: UIID, shape: HitShape) {
+        match shape {
+            HitShape::Rectangle => { self.hit_shapes.remove(&ui_id); },
+            _ => { self.hit_shapes.insert(ui_id, shape); },
+        }
+    }
+
+    /// Return whether or not the point `p` is over the widget with the given
+    /// UIID, position and dimensions, taking its hit shape into account.
+    pub fn is_over(&self, ui_id: UIID, pos: Point, dim: Dimensions, p: Point) -> bool {
+        match self.hit_shapes.get(&ui_id) {
+            Some(shape) => shape.is_over(pos, dim, p),
+            None => HitShape::Rectangle.is_over(pos, dim, p),
+        }
+    }
+
+    /// The zoom applied to the whole UI. This is distinct from the display's
+    /// DPI and may be adjusted by the user via Ctrl+= / Ctrl+- / Ctrl+0
+    /// (Cmd on OS X).
+    pub fn ui_scale(&self) -> f64 {
+        self.ui_scale
+    }
+
+    /// Set the zoom applied to the whole UI. Widgets are laid out within
+    /// the window's dimensions divided by the scale, so layouts reflow.
+    pub fn set_ui_scale(&mut self, scale: f64) {
+        let scale = clamp(scale, MIN_UI_SCALE, MAX_UI_SCALE);
+        let dpi_factor = self.dpi_factor;
+        self.rescale(scale, dpi_factor);
+    }
+
+    /// The ratio of the display's pixels to UI units.
+    pub fn dpi_factor(&self) -> f64 {
+        self.dpi_factor
+    }
+
+    /// Set the display's DPI factor (i.e. the window's `hidpi_factor`), so
+    /// that positions, dimensions and font sizes given in UI units are the
+    /// same physical size on every display. Call this whenever the window
+    /// moves to a display with a different factor.
+    pub fn set_dpi_factor(&mut self, dpi_factor: f64) {
+        if dpi_factor <= 0.0 { return }
+        let ui_scale = self.ui_scale;
+        self.rescale(ui_scale, dpi_factor);
+    }
+
+    /// The number of window pixels per UI unit, combining the display's
+    /// DPI factor with the user's zoom. Input and window dimensions are
+    /// given in pixels and divided by this, so widgets and hit-testing work
+    /// entirely in UI units.
+    pub fn scale_factor(&self) -> f64 {
+        self.ui_scale * self.dpi_factor
+    }
+
+    /// Apply a new zoom and DPI factor, keeping the mouse over the same
+    /// pixel and reflowing layouts within the new window dimensions.
+    fn rescale(&mut self, ui_scale: f64, dpi_factor: f64) {
+        let ratio = self.scale_factor() / (ui_scale * dpi_factor);
+        self.mouse.pos = [self.mouse.pos[0] * ratio, self.mouse.pos[1] * ratio];
+        self.ui_scale = ui_scale;
+        self.dpi_factor = dpi_factor;
+        let scale = self.scale_factor();
+        self.win_w = self.physical_dim[0] / scale;
+        self.win_h = self.physical_dim[1] / scale;
+        self.canvases.reset(self.win_w, self.win_h);
+        self.damage_all();
+    }
+
+    /// Periodically save the session to the given path for crash recovery.
+    /// The application's state is passed in via `autosave` each frame.
+    pub fn set_autosave(&mut self, path: &str, interval: f64) {
+        self.maybe_autosave = Some(Autosave::new(path, interval));
+    }
+
+    /// Save the application's state along with the UI's own state if the
+    /// autosave interval has elapsed. This should be called once per frame.
+    pub fn autosave<T: Encodable>(&mut self, state: &T) -> Result<(), String> {
+        let now = self.time;
+        let ui_scale = self.ui_scale;
+        match self.maybe_autosave {
+            Some(ref mut autosave) if autosave.is_due(now) => autosave.save(ui_scale, state, now),
+            _ => Ok(()),
+        }
+    }
+
+    /// Remove the autosaved session. This should be called when the
+    /// application exits cleanly.
+    pub fn finish_autosave(&mut self) -> Result<(), String> {
+        match self.maybe_autosave {
+            Some(ref mut autosave) => autosave.clear(),
+            None => Ok(()),
+        }
+    }
+
+    /// Recover the session autosaved at the given path after a crash,
+    /// restoring the UI's own state and returning the application's. This
+    /// should be called on startup. Returns None if the last session exited
+    /// cleanly or its save is stale.
+    pub fn recover<T: Decodable>(&mut self, path: &str) -> Result<Option<T>, String> {
+        match try!(autosave::recover(path)) {
+            Some((ui_scale, state)) => {
+                self.set_ui_scale(ui_scale);
+                Ok(Some(state))
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Restore the widget state saved at the given path by a previous session.
+    /// This should be called on startup, before any widgets are drawn. Widgets
+    /// opt in to persisting their state via their `persist` builder method.
+    pub fn restore_persisted(&mut self, path: &str) -> Result<(), String> {
+        self.persisted = try!(Persisted::load(path));
+        Ok(())
+    }
+
+    /// Persist the given value under the UIID, i.e. a split ratio or whether
+    /// a section is collapsed. The first time this is called for a UIID after
+    /// `restore_persisted`, the value is replaced by the restored one.
+    pub fn persist<T: Encodable + Decodable + Clone + 'static>(&mut self, ui_id: UIID, value: &mut T) {
+        self.persisted.value(ui_id, value);
+    }
+
+    /// Save all persisted state to the given path. This should be called
+    /// when the application exits.
+    pub fn save_persisted(&self, path: &str) -> Result<(), String> {
+        self.persisted.save(path)
+    }
+
+    /// Return whether or not the given point is over any placed widget.
+    fn is_over_any_widget(&self, p: Point) -> bool {
+        self.get_placings().iter().any(|&(ui_id, placing)| match placing {
+            widget::Placing::Place(x, y, w, h) => self.is_over(ui_id, [x, y], [w, h], p),
+            widget::Placing::NoPlace => false,
+        })
+    }
+
+    /// Return the current quality tier as determined by the frame time.
+    pub fn quality_tier(&self) -> quality::Tier {
+        self.quality.tier()
+    }
+
+    /// Resolve the circle quality to use for a widget, falling back to the
+    /// theme's and reducing it to suit the current quality tier.
+    pub fn circle_quality(&self, maybe_quality: Option<CircleQuality>) -> CircleQuality {
+        let circle_quality = maybe_quality.or(self.theme.circle_quality)
+            .unwrap_or(CircleQuality::default());
+        match self.quality.tier() {
+            quality::Tier::Low => CircleQuality { smoothing: 0, ..circle_quality },
+            _ => circle_quality,
+        }
+    }
+
+    /// Dock a canvas to an edge of its parent canvas (or the window).
+    pub fn dock_canvas(&mut self, id: CanvasId, maybe_parent: Option<CanvasId>, dock: Dock) -> Rect {
+        self.canvases.dock(id, maybe_parent, dock)
+    }
+
+    /// Split the remaining space of a canvas between the given children
+    /// in proportion to their weights.
+    pub fn split_canvas(&mut self, parent: CanvasId, direction: Direction, children: &[(CanvasId, f64)]) {
+        self.canvases.split(parent, direction, children)
+    }
+
+    /// Return the Rect of the canvas with the given id.
+    pub fn get_canvas(&self, id: CanvasId) -> Option<Rect> {
+        self.canvases.get(id)
+    }
+
+    /// Return the pointer position for a widget being dragged, filtered by
+    /// the given smoothing (if any).
+    pub fn smooth_pointer(&mut self, ui_id: UIID, maybe_smoothing: Option<Smoothing>, p: Point) -> Point {
+        match maybe_smoothing {
+            Some(smoothing) => {
+                if !self.pointer_samples.contains_key(&ui_id) {
+                    self.pointer_samples.insert(ui_id, Vec::new());
+                }
+                let samples = self.pointer_samples.get_mut(&ui_id).unwrap();
+                smoothing.apply(samples, p)
+            },
+            None => p,
+        }
+    }
+
+    /// Record the position at the end of the widget's trail unless it's
+    /// already there, keeping only the last `len` positions.
+    pub fn push_trail(&mut self, ui_id: UIID, p: Point, len: usize) {
+        let trail = match self.trails.entry(ui_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(VecDeque::with_capacity(len)),
+        };
+        if trail.back() == Some(&p) { return }
+        trail.push_back(p);
+        while trail.len() > len {
+            trail.pop_front();
+        }
+    }
+
+    /// The positions recorded for the widget's trail, oldest first.
+    pub fn trail(&self, ui_id: UIID) -> Vec<Point> {
+        self.trails.get(&ui_id).map_or(Vec::new(), |trail| trail.iter().cloned().collect())
+    }
+
+    /// Forget the widget's trail, i.e. when a new gesture begins.
+    pub fn clear_trail(&mut self, ui_id: UIID) {
+        self.trails.remove(&ui_id);
+    }
+
+    /// Clear the pointer history for a widget once its drag has ended.
+    pub fn reset_pointer_smoothing(&mut self, ui_id: UIID) {
+        self.pointer_samples.remove(&ui_id);
+        self.fine_drags.remove(&ui_id);
+        self.drag_origins.remove(&ui_id);
+    }
+
+    /// Return the pointer position for a widget being dragged, scaling the
+    /// pointer's movement down for fine adjustment while Shift is held.
+    pub fn fine_pointer(&mut self, ui_id: UIID, p: Point) -> Point {
+        self.fine_pointer_with(ui_id, p, keyboard::SHIFT)
+    }
+
+    /// As `fine_pointer`, but adjusting finely while the given modifier is
+    /// held instead of Shift.
+    pub fn fine_pointer_with(&mut self, ui_id: UIID, p: Point, modifier: ModifierKeys) -> Point {
+        let adjusted = match self.fine_drags.get(&ui_id) {
+            Some(&(last_raw, last_adjusted)) if self.modifiers.contains(modifier) =>
+                [last_adjusted[0] + (p[0] - last_raw[0]) * FINE_DRAG_SCALE,
+                 last_adjusted[1] + (p[1] - last_raw[1]) * FINE_DRAG_SCALE],
+            _ => p,
+        };
+        self.fine_drags.insert(ui_id, (p, adjusted));
+        adjusted
+    }
+
+    /// Return the pointer position for a widget being dragged, locking its
+    /// movement to whichever axis it has moved furthest along since the drag
+    /// began while Shift is held.
+    pub fn axis_locked_pointer(&mut self, ui_id: UIID, p: Point) -> Point {
+        if !self.drag_origins.contains_key(&ui_id) {
+            self.drag_origins.insert(ui_id, p);
+        }
+        let origin = self.drag_origins[&ui_id];
+        match self.modifiers.contains(keyboard::SHIFT) {
+            true if (p[0] - origin[0]).abs() >= (p[1] - origin[1]).abs() => [p[0], origin[1]],
+            true => [origin[0], p[1]],
+            false => p,
+        }
+    }
+
+    /// The Breakpoint that the current window width falls within.
+    pub fn breakpoint(&self) -> Breakpoint {
+        self.breakpoints.resolve(self.win_w)
+    }
+
+    /// Reset the canvases to the whole window and the layout position to its
+    /// top left, ready for the widgets of the next frame.
+    fn reset_layout(&mut self) {
+        self.canvases.reset(self.win_w, self.win_h);
+        self.layout_pos = [self.layout_spacing, self.layout_spacing];
+    }
+
+    /// Set where the next automatically laid out row or column will begin.
+    /// This is reset to the top left of the window on each render.
+    pub fn set_layout_pos(&mut self, pos: Point) {
+        self.layout_pos = pos;
+    }
+
+    /// Lay out widgets from left to right in a row of the given height,
+    /// wrapping onto new rows at the edge of the window. Widgets declared
+    /// within `f` are positioned via `Flowable::flow`. Subsequent rows
+    /// begin below this one.
+    pub fn layout_row<F>(&mut self, height: f64, f: F)
+        where
+            F: FnOnce(&mut UiContext<C>, &mut Flow)
+    {
+        let pos = self.layout_pos;
+        let wrap_w = self.win_w - pos[0] - self.layout_spacing;
+        let mut flow = Flow::row(pos, height, wrap_w, self.layout_spacing);
+        f(self, &mut flow);
+        self.layout_pos = [pos[0], pos[1] + flow.dim()[1] + self.layout_spacing];
+    }
+
+    /// Lay out widgets from top to bottom in a column of the given width,
+    /// wrapping onto new columns at the bottom of the window. Widgets
+    /// declared within `f` are positioned via `Flowable::flow`. Subsequent
+    /// columns begin to the right of this one.
+    pub fn layout_column<F>(&mut self, width: f64, f: F)
+        where
+            F: FnOnce(&mut UiContext<C>, &mut Flow)
+    {
+        let pos = self.layout_pos;
+        let wrap_h = self.win_h - pos[1] - self.layout_spacing;
+        let mut flow = Flow::column(pos, width, wrap_h, self.layout_spacing);
+        f(self, &mut flow);
+        self.layout_pos = [pos[0] + flow.dim()[0] + self.layout_spacing, pos[1]];
+    }
+
+    /// Register an open overlay (i.e. a menu or dialog) and the area that it
+    /// covers. This should be called each frame that the overlay is open so
+    /// that Escape and clicks outside of its area dismiss it.
+    pub fn open_overlay(&mut self, ui_id: UIID, pos: Point, dim: Dimensions) {
+        match self.overlays.iter_mut().find(|&&mut (id, _, _)| id == ui_id) {
+            Some(overlay) => { *overlay = (ui_id, pos, dim); return },
+            None => (),
+        }
+        self.overlays.push((ui_id, pos, dim));
+    }
+
+    /// Remove an overlay from the dismissal stack once it has closed.
+    pub fn close_overlay(&mut self, ui_id: UIID) {
+        self.overlays.retain(|&(id, _, _)| id != ui_id);
+        self.dismissed.retain(|&id| id != ui_id);
+    }
+
+    /// Return whether or not the overlay has been dismissed since the last
+    /// check. The overlay should close itself if so.
+    pub fn take_dismissed(&mut self, ui_id: UIID) -> bool {
+        let was_dismissed = self.dismissed.iter().any(|&id| id == ui_id);
+        if was_dismissed { self.close_overlay(ui_id) }
+        was_dismissed
+    }
+
+    /// Dismiss the most recently opened overlay.
+    pub fn dismiss_top_overlay(&mut self) {
+        if let Some((ui_id, _, _)) = self.overlays.pop() {
+            self.dismissed.push(ui_id);
+        }
+    }
+
+    /// Clip all following drawing to the given rectangle (within any
+    /// rectangle that drawing is already clipped to) until `pop_clip`.
+    /// Clipping applies to text and to everything drawn via `render` or to
+    /// a layer, using the backend's scissor test.
+    pub fn push_clip(&mut self, pos: Point, dim: Dimensions) {
+        let rect = match self.clip_stack.last() {
+            Some(&outer) => clip::intersect(outer, (pos, dim)),
+            None => (pos, dim),
+        };
+        self.clip_stack.push(rect);
+        self.update_scissor();
+    }
+
+    /// Restore the clip rectangle that preceded the last `push_clip`.
+    pub fn pop_clip(&mut self) {
+        self.clip_stack.pop();
+        self.update_scissor();
+    }
+
+    /// The rectangle that drawing is currently clipped to (if any).
+    pub fn clip_rect(&self) -> Option<(Point, Dimensions)> {
+        self.clip_stack.last().cloned()
+    }
+
+    /// Update the scissor rectangle applied to recorded commands.
+    fn update_scissor(&mut self) {
+        let maybe_scissor = self.clip_stack.last().map(|&(pos, dim)| {
+            clip::scissor(pos, dim, self.scale_factor(), self.physical_dim[1])
+        });
+        self.layer_commands.borrow_mut().maybe_clip = maybe_scissor;
+    }
+
+    /// Report repeated presses for any keys that have been held for longer
+    /// than the repeat delay, at the repeat rate.
+    fn repeat_held_keys(&mut self) {
+        use std::num::Float;
+        let now = self.time;
+        for &mut (key, pressed_at, ref mut repeats) in self.held_keys.iter_mut() {
+            let held = now - pressed_at - self.key_repeat_delay;
+            if held < 0.0 { continue }
+            let due = (held * self.key_repeat_rate).floor() as u32 + 1;
+            while *repeats < due {
+                self.keys_just_pressed.push(key);
+                *repeats += 1;
+            }
+        }
+    }
+
+    /// The state of the given mouse button (if it is tracked).
+    fn mouse_button_state(&mut self, button: input::MouseButton) -> Option<&mut ButtonState> {
+        use piston::input::MouseButton;
+        match button {
+            MouseButton::Left => Some(&mut self.mouse.left),
+            MouseButton::Right => Some(&mut self.mouse.right),
+            MouseButton::Middle => Some(&mut self.mouse.middle),
+            MouseButton::X1 => Some(&mut self.mouse.x1),
+            MouseButton::X2 => Some(&mut self.mouse.x2),
+            _ => None,
+        }
+    }
+
+    /// Count a left click at the given point towards a double or triple-click.
+    fn count_click(&mut self, p: Point) {
+        use std::num::Float;
+        let now = precise_time_s();
+        self.mouse.clicks = match self.maybe_last_click {
+            Some((time, pos)) if now - time <= self.double_click_threshold
+                && (p[0] - pos[0]).abs() <= MULTI_CLICK_DISTANCE
+                && (p[1] - pos[1]).abs() <= MULTI_CLICK_DISTANCE => self.mouse.clicks + 1,
+            _ => 1,
+        };
+        self.maybe_last_click = Some((now, p));
+    }
+
+    /// Dismiss overlays from the top of the stack down until one is found
+    /// that lies under the given point.
+    fn dismiss_overlays_not_under(&mut self, p: Point) {
+        loop {
+            match self.overlays.last() {
+                Some(&(_, pos, dim)) if !rectangle::is_over(pos, p, dim) => (),
+                _ => return,
+            }
+            self.dismiss_top_overlay();
+        }
+    }
+
+    /// Generate a UIID that is unique to the current id scope. The same
+    /// sequence of ids is generated each frame, so widgets declared in the
+    /// same order keep their state.
+    pub fn next_id(&mut self) -> UIID {
+        let &mut (scope, ref mut count) = self.id_scopes.last_mut().unwrap();
+        let id = scoped_id(scope, *count);
+        *count += 1;
+        id
+    }
+
+    /// Call `f` within a named id scope. Ids generated via `next_id` within
+    /// the scope won't collide with those of other scopes, so loops that
+    /// declare dynamic numbers of widgets don't need manual id offsets.
+    pub fn scope<F, T>(&mut self, name: &str, f: F) -> T
+        where
+            F: FnOnce(&mut UiContext<C>) -> T
+    {
+        let parent = self.id_scopes.last().unwrap().0;
+        self.id_scopes.push((scoped_id(parent, name), 0));
+        let result = f(self);
+        self.id_scopes.pop();
+        result
+    }
+
+    /// Restart id generation for a new frame.
+    fn reset_id_scopes(&mut self) {
+        self.id_scopes.truncate(1);
+        self.id_scopes[0].1 = 0;
+    }
+
+    /// Link the given value widgets so that a change made by the user to
+    /// any one of them is propagated to the others with the given mode
+    /// (i.e. ganging the left and right channels of a stereo control).
+    /// Only Sliders currently apply and propagate linked changes. Linking
+    /// the same widgets again (i.e. every frame) keeps their pending changes.
+    pub fn link_widgets(&mut self, ids: &[UIID], mode: LinkMode) {
+        self.links.link(ids, mode);
+    }
+
+    /// Remove the given widget from its linked group (if any).
+    pub fn unlink_widget(&mut self, ui_id: UIID) {
+        self.links.unlink(ui_id);
+    }
+
+    /// Access the linked widget groups.
+    pub fn links(&mut self) -> &mut Links {
+        &mut self.links
+    }
+
+    /// Record whether or not the given widget is being dragged, comparing
+    /// against the previous frame in order to detect a drag beginning or
+    /// ending.
+    pub fn set_dragging(&mut self, ui_id: UIID, was_dragging: bool, is_dragging: bool) {
+        match (was_dragging, is_dragging) {
+            (false, true) => self.drags.push((ui_id, Drag::Began)),
+            (true, false) => self.drags.push((ui_id, Drag::Ended)),
+            _ => (),
+        }
+    }
+
+    /// Whether a drag began or ended on the given widget this frame. This
+    /// allows expensive work to be deferred until the user lets go.
+    pub fn drag_event(&self, ui_id: UIID) -> Option<Drag> {
+        self.drags.iter().find(|&&(id, _)| id == ui_id).map(|&(_, drag)| drag)
+    }
+
+    /// Take the text being edited for the given widget (if any).
+    pub fn take_text_buffer(&mut self, ui_id: UIID) -> Option<String> {
+        self.text_buffers.remove(&ui_id)
+    }
+
+    /// Store the text being edited for the given widget until next frame.
+    pub fn set_text_buffer(&mut self, ui_id: UIID, text: String) {
+        self.text_buffers.insert(ui_id, text);
+    }
+
     /// Get the UIID of the previous widget.
     pub fn get_prev_uiid(&self) -> UIID { self.prev_uiid }
 
     /// Get the Placing for a particular widget.
     pub fn get_placing(&self, ui_id: UIID) -> widget::Placing {
-        if ui_id as usize >= self.data.len() { widget::Placing::NoPlace }
-        else {
-            match self.data[ui_id as usize] { (_, ref placing) => *placing }
+        match self.data.get(&ui_id) {
+            Some(&(_, placing)) => placing,
+            None => widget::Placing::NoPlace,
+        }
+    }
+
+    /// Return the position and dimensions of the widget with the given UIID,
+    /// if it has been placed.
+    pub fn rect_of(&self, ui_id: UIID) -> Option<(Point, Dimensions)> {
+        match self.get_placing(ui_id) {
+            widget::Placing::Place(x, y, w, h) => Some(([x, y], [w, h])),
+            widget::Placing::NoPlace => None,
         }
     }
+
+    /// Return the UIID and Placing of every widget that has been placed.
+    pub fn get_placings(&self) -> Vec<(UIID, widget::Placing)> {
+        let mut placings: Vec<(UIID, widget::Placing)> = self.data.iter()
+            .filter_map(|(&ui_id, &(_, placing))| match placing {
+                widget::Placing::Place(..) => Some((ui_id, placing)),
+                widget::Placing::NoPlace => None,
+            }).collect();
+        placings.sort_by(|a, b| a.0.cmp(&b.0));
+        placings
+    }
 }
 
 /// Id property.
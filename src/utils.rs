@@ -5,7 +5,7 @@ use std::num::ToPrimitive;
 use std::num::FromPrimitive;
 
 /// Clamp a value between a given min and max.
-pub fn clamp<T: Float + PartialOrd>(n: T, min: T, max: T) -> T {
+pub fn clamp<T: PartialOrd>(n: T, min: T, max: T) -> T {
     if n < min { min } else if n > max { max } else { n }
 }
 
@@ -52,6 +52,10 @@ pub fn map_range<X: Float + Copy + FromPrimitive + ToPrimitive,
     ).unwrap()
 }
 
+/// A closure that formats a value for display, i.e. as "1.2 kHz", a note
+/// name or a time code, in place of `val_to_string`.
+pub type ValueFormatter<'a> = Box<Fn(f64) -> String + 'a>;
+
 /// Get a suitable string from the value, its max and the pixel range.
 pub fn val_to_string<T: ToString + ToPrimitive>
 (val: T, max: T, val_rng: T, pixel_range: usize) -> String {
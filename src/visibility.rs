@@ -0,0 +1,33 @@
+use piston::quack::{ Get, GetFrom, Pair, Set, SetAt };
+
+/// Visible property.
+#[derive(Copy)]
+pub struct Visible(pub bool);
+
+/// A trait for widgets that may be hidden. A hidden widget is neither
+/// updated nor drawn, however its stored state is kept so that it is
+/// restored when the widget is shown again.
+pub trait Hideable {
+    fn visible(self, visible: bool) -> Self;
+    fn hide(self) -> Self;
+    fn is_visible(&self) -> bool;
+}
+
+impl<T> Hideable for T
+    where
+        (Visible, T): Pair<Data = Visible, Object = T> + SetAt + GetFrom
+{
+    #[inline]
+    fn visible(self, visible: bool) -> Self {
+        self.set(Visible(visible))
+    }
+    #[inline]
+    fn hide(self) -> Self {
+        self.set(Visible(false))
+    }
+    #[inline]
+    fn is_visible(&self) -> bool {
+        let Visible(visible) = self.get();
+        visible
+    }
+}
@@ -1,16 +1,23 @@
 
 use button;
+use custom_widget;
 use drop_down_list;
 use envelope_editor;
+use key_capture_box;
+use number_box;
 use number_dialer;
+use plot;
+use segmented_control;
 use slider;
+use tabs;
 use text_box;
 use toggle;
+use toggle_matrix;
 use xy_pad;
 
 /// Represents the placement of the widget including
 /// x / y position, width and height.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum Placing {
     Place(f64, f64, f64, f64), // (x, y, w, h)
     NoPlace,
@@ -45,16 +52,23 @@ impl Placing {
 
 /// Algebraic widget type for storing in ui_context
 /// and for ease of state-matching.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub enum Widget {
     NoWidget,
     Button(button::State),
+    CustomWidget(custom_widget::State),
     DropDownList(drop_down_list::State),
     EnvelopeEditor(envelope_editor::State),
+    KeyCaptureBox(key_capture_box::State),
+    NumberBox(number_box::State),
     NumberDialer(number_dialer::State),
+    Plot(plot::State),
+    SegmentedControl(segmented_control::State),
     Slider(slider::State),
+    Tabs(tabs::State),
     TextBox(text_box::State),
     Toggle(toggle::State),
+    ToggleMatrix(toggle_matrix::State),
     XYPad(xy_pad::State),
 }
 
@@ -63,12 +77,19 @@ impl Widget {
         match (self, other) {
             (&Widget::NoWidget, &Widget::NoWidget) => true,
             (&Widget::Button(_), &Widget::Button(_)) => true,
+            (&Widget::CustomWidget(_), &Widget::CustomWidget(_)) => true,
             (&Widget::DropDownList(_), &Widget::DropDownList(_)) => true,
             (&Widget::EnvelopeEditor(_), &Widget::EnvelopeEditor(_)) => true,
+            (&Widget::KeyCaptureBox(_), &Widget::KeyCaptureBox(_)) => true,
+            (&Widget::NumberBox(_), &Widget::NumberBox(_)) => true,
             (&Widget::NumberDialer(_), &Widget::NumberDialer(_)) => true,
+            (&Widget::Plot(_), &Widget::Plot(_)) => true,
+            (&Widget::SegmentedControl(_), &Widget::SegmentedControl(_)) => true,
             (&Widget::Slider(_), &Widget::Slider(_)) => true,
+            (&Widget::Tabs(_), &Widget::Tabs(_)) => true,
             (&Widget::TextBox(_), &Widget::TextBox(_)) => true,
             (&Widget::Toggle(_), &Widget::Toggle(_)) => true,
+            (&Widget::ToggleMatrix(_), &Widget::ToggleMatrix(_)) => true,
             (&Widget::XYPad(_), &Widget::XYPad(_)) => true,
             _ => false
         }
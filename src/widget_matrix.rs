@@ -105,6 +105,7 @@ quack! {
     wm: WidgetMatrix[]
     get:
         fn () -> Size [] { Size(wm.pos) }
+        fn () -> Position [] { Position(wm.pos) }
     set:
         fn (val: Position) [] { wm.pos = val.0 }
         fn (val: Size) [] { wm.dim = val.0 }
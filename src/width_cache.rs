@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::iter::repeat;
+use font::FontId;
+use label::FontSize;
+
+/// The number of code points whose widths are stored in each flat table.
+const TABLE_LEN: usize = 256;
+
+/// Caches the advance width of each character per font and size, so that
+/// measuring text doesn't query the CharacterCache for every character of
+/// every string each frame. Latin-1 characters are stored in a flat table
+/// per font and size, others in a map.
+#[derive(Clone, Debug)]
+pub struct WidthCache {
+    /// The widths of the first `TABLE_LEN` code points. Widths that haven't
+    /// been cached yet are negative.
+    tables: HashMap<(FontId, FontSize), Vec<f64>>,
+    others: HashMap<(FontId, FontSize, char), f64>,
+}
+
+impl WidthCache {
+
+    /// Construct an empty WidthCache.
+    pub fn new() -> WidthCache {
+        WidthCache {
+            tables: HashMap::new(),
+            others: HashMap::new(),
+        }
+    }
+
+    /// The cached width of the character (if any).
+    pub fn get(&self, font: FontId, size: FontSize, ch: char) -> Option<f64> {
+        let i = ch as usize;
+        if i < TABLE_LEN {
+            match self.tables.get(&(font, size)) {
+                Some(table) if table[i] >= 0.0 => Some(table[i]),
+                _ => None,
+            }
+        } else {
+            self.others.get(&(font, size, ch)).cloned()
+        }
+    }
+
+    /// Cache the width of the character.
+    pub fn insert(&mut self, font: FontId, size: FontSize, ch: char, width: f64) {
+        let i = ch as usize;
+        if i < TABLE_LEN {
+            let table = match self.tables.entry((font, size)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(repeat(-1.0).take(TABLE_LEN).collect()),
+            };
+            table[i] = width;
+        } else {
+            self.others.insert((font, size, ch), width);
+        }
+    }
+
+    /// Forget all cached widths.
+    pub fn clear(&mut self) {
+        self.tables.clear();
+        self.others.clear();
+    }
+
+}
@@ -1,15 +1,20 @@
 use std::num::Float;
 use std::num::ToPrimitive;
 use std::num::FromPrimitive;
+use axis;
 use color::Color;
+use density::DisplayDensity;
 use dimensions::Dimensions;
-use graphics;
 use graphics::Graphics;
 use graphics::character::CharacterCache;
+use keyboard;
 use label;
 use label::FontSize;
 use mouse::Mouse;
 use point::Point;
+use primitive;
+use primitive::LineStyle;
+use smoothing::Smoothing;
 use rectangle;
 use rectangle::{
     Corner
@@ -23,6 +28,7 @@ use utils::{
     clamp,
     map_range,
     val_to_string,
+    ValueFormatter,
 };
 use vecmath::{
     vec2_add,
@@ -37,6 +43,14 @@ use LabelColor;
 use LabelFontSize;
 use Position;
 use Size;
+use Visible;
+
+/// The distance in pixels within which the crosshair sticks to a detent.
+pub const DETENT_RADIUS: f64 = 6.0;
+
+/// The time in seconds over which a spring-return pad closes roughly two
+/// thirds of the distance to its rest position.
+pub const SPRING_TIME: f64 = 0.08;
 
 /// Represents the state of the xy_pad widget.
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -83,26 +97,32 @@ fn draw_crosshair<B: Graphics>(
     line_width: f64,
     vert_x: f64, hori_y: f64,
     pad_dim: Dimensions,
-    color: Color
+    color: Color,
+    style: LineStyle
 ) {
-    let draw_state = graphics::default_draw_state();
-    let transform = graphics::abs_transform(win_w, win_h);
-    let Color(col) = color;
-    let line = graphics::Line::new(col, 0.5 * line_width);
-    line.draw(
-        [vert_x, pos[1], vert_x, pos[1] + pad_dim[1]],
-        draw_state,
-        transform,
-        graphics
-    );
-    line.draw(
-        [pos[0], hori_y, pos[0] + pad_dim[0], hori_y],
-        draw_state,
-        transform,
-        graphics
-    );
+    primitive::styled_polyline(win_w, win_h, graphics,
+                               &[[vert_x, pos[1]], [vert_x, pos[1] + pad_dim[1]]],
+                               line_width, &[color], style);
+    primitive::styled_polyline(win_w, win_h, graphics,
+                               &[[pos[0], hori_y], [pos[0] + pad_dim[0], hori_y]],
+                               line_width, &[color], style);
+}
+
+/// Round the value to the nearest step from `min`, within the range.
+fn snap_to_step(value: f64, min: f64, max: f64, step: f64) -> f64 {
+    if step <= 0.0 { return value }
+    let (lo, hi) = if min < max { (min, max) } else { (max, min) };
+    clamp(lo + ((value - lo) / step).round() * step, lo, hi)
 }
 
+/// The detent closest to the value if it lies within `radius`, otherwise
+/// the value itself.
+fn stick_to_detents(value: f64, detents: &[f64], radius: f64) -> f64 {
+    detents.iter().fold((value, radius), |(closest, closest_distance), &detent| {
+        let distance = (value - detent).abs();
+        if distance <= closest_distance { (detent, distance) } else { (closest, closest_distance) }
+    }).0
+}
 
 /// A context on which the builder pattern can be implemented.
 pub struct XYPad<'a, X, Y, F> {
@@ -110,8 +130,23 @@ pub struct XYPad<'a, X, Y, F> {
     x: X, min_x: X, max_x: X,
     y: Y, min_y: Y, max_y: Y,
     line_width: f64,
+    maybe_crosshair_style: Option<LineStyle>,
+    maybe_smoothing: Option<Smoothing>,
+    maybe_density: Option<DisplayDensity>,
     font_size: FontSize,
+    maybe_grid: Option<axis::Divisions>,
+    tick_labels: bool,
+    maybe_units: Option<(&'a str, &'a str)>,
+    maybe_value_formatter: Option<ValueFormatter<'a>>,
+    maybe_y_value_formatter: Option<ValueFormatter<'a>>,
+    maybe_snap: Option<[f64; 2]>,
+    maybe_detents: Option<(&'a [f64], &'a [f64])>,
+    maybe_rest: Option<(X, Y)>,
+    maybe_heatmap: Option<(&'a [Color], usize)>,
+    maybe_trail: Option<usize>,
+    maybe_default_value: Option<(X, Y)>,
     pos: Point,
+    visible: bool,
     dim: Dimensions,
     maybe_callback: Option<F>,
     maybe_color: Option<Color>,
@@ -128,9 +163,94 @@ impl <'a, X, Y, F> XYPad<'a, X, Y, F> {
         XYPad { line_width: width, ..self }
     }
     #[inline]
+    pub fn crosshair_style(self, style: LineStyle) -> XYPad<'a, X, Y, F> {
+        XYPad { maybe_crosshair_style: Some(style), ..self }
+    }
+    /// Filter the pointer position while dragging to tame jittery input.
+    #[inline]
+    pub fn smoothing(self, smoothing: Smoothing) -> XYPad<'a, X, Y, F> {
+        XYPad { maybe_smoothing: Some(smoothing), ..self }
+    }
+    /// Set the amount of detail to display. The value readout is hidden when Compact.
+    #[inline]
+    pub fn density(self, density: DisplayDensity) -> XYPad<'a, X, Y, F> {
+        XYPad { maybe_density: Some(density), ..self }
+    }
+    #[inline]
     pub fn value_font_size(self, size: FontSize) -> XYPad<'a, X, Y, F> {
         XYPad { font_size: size, ..self }
     }
+    /// Draw grid lines dividing the X and Y axes into the given number of divisions.
+    #[inline]
+    pub fn grid(self, x_divisions: usize, y_divisions: usize) -> XYPad<'a, X, Y, F> {
+        XYPad { maybe_grid: Some((x_divisions, y_divisions)), ..self }
+    }
+    /// Label each grid line with its value.
+    #[inline]
+    pub fn tick_labels(self, labels: bool) -> XYPad<'a, X, Y, F> {
+        XYPad { tick_labels: labels, ..self }
+    }
+    /// The units of the X and Y values (i.e. "Hz" and "dB"), which are
+    /// appended to the values in the readout.
+    #[inline]
+    pub fn units(self, x_unit: &'a str, y_unit: &'a str) -> XYPad<'a, X, Y, F> {
+        XYPad { maybe_units: Some((x_unit, y_unit)), ..self }
+    }
+    /// Format the values shown in the readout. This formats both the X and
+    /// the Y value unless a `y_value_formatter` is also given.
+    #[inline]
+    pub fn value_formatter<G>(self, formatter: G) -> XYPad<'a, X, Y, F>
+        where G: Fn(f64) -> String + 'a
+    {
+        XYPad { maybe_value_formatter: Some(Box::new(formatter)), ..self }
+    }
+    /// Format the Y value shown in the readout.
+    #[inline]
+    pub fn y_value_formatter<G>(self, formatter: G) -> XYPad<'a, X, Y, F>
+        where G: Fn(f64) -> String + 'a
+    {
+        XYPad { maybe_y_value_formatter: Some(Box::new(formatter)), ..self }
+    }
+    /// Round dragged values to multiples of the X and Y steps from the
+    /// minimum of each range. A step of 0.0 leaves its axis continuous.
+    /// Holding Alt while dragging bypasses snapping.
+    #[inline]
+    pub fn snap(self, steps: [f64; 2]) -> XYPad<'a, X, Y, F> {
+        XYPad { maybe_snap: Some(steps), ..self }
+    }
+    /// Values along the X and Y axes that the crosshair sticks to when
+    /// dragged within `DETENT_RADIUS` of them, i.e. 0.0 at the center of a
+    /// bipolar pad. Holding Alt while dragging bypasses the detents.
+    #[inline]
+    pub fn detents(self, x_detents: &'a [f64], y_detents: &'a [f64]) -> XYPad<'a, X, Y, F> {
+        XYPad { maybe_detents: Some((x_detents, y_detents)), ..self }
+    }
+    /// Return the crosshair to the rest position once released, like a
+    /// joystick or pitch-bend wheel. The callback is called with each value
+    /// along the way.
+    #[inline]
+    pub fn spring_return(self, rest_x: X, rest_y: Y) -> XYPad<'a, X, Y, F> {
+        XYPad { maybe_rest: Some((rest_x, rest_y)), ..self }
+    }
+    /// Fill the pad with a grid of colored cells behind the crosshair, i.e.
+    /// a spectrogram or a map of the parameter space. The cells are given row
+    /// by row from the top left, `columns` to a row.
+    #[inline]
+    pub fn heatmap(self, cells: &'a [Color], columns: usize) -> XYPad<'a, X, Y, F> {
+        XYPad { maybe_heatmap: Some((cells, columns)), ..self }
+    }
+    /// Draw the last `len` positions of the crosshair as a fading trail,
+    /// showing the gesture just performed. The trail restarts with each drag.
+    #[inline]
+    pub fn trail(self, len: usize) -> XYPad<'a, X, Y, F> {
+        XYPad { maybe_trail: Some(len), ..self }
+    }
+    /// Set the values to which the pad is reset when Ctrl-clicked or
+    /// double-clicked.
+    #[inline]
+    pub fn default_value(self, x: X, y: Y) -> XYPad<'a, X, Y, F> {
+        XYPad { maybe_default_value: Some((x, y)), ..self }
+    }
 }
 
 impl<'a, X, Y, F> XYPad<'a, X, Y, F> {
@@ -143,8 +263,23 @@ impl<'a, X, Y, F> XYPad<'a, X, Y, F> {
             x: x_val, min_x: min_x, max_x: max_x,
             y: y_val, min_y: min_y, max_y: max_y,
             line_width: 1.0,
+            maybe_crosshair_style: None,
+            maybe_smoothing: None,
+            maybe_density: None,
             font_size: 18u32,
+            maybe_grid: None,
+            tick_labels: false,
+            maybe_units: None,
+            maybe_value_formatter: None,
+            maybe_y_value_formatter: None,
+            maybe_snap: None,
+            maybe_detents: None,
+            maybe_rest: None,
+            maybe_heatmap: None,
+            maybe_trail: None,
+            maybe_default_value: None,
             pos: [0.0, 0.0],
+            visible: true,
             dim: [128.0, 128.0],
             maybe_callback: None,
             maybe_color: None,
@@ -161,6 +296,8 @@ quack! {
     xy_pad: XYPad['a, X, Y, F]
     get:
         fn () -> Size [] { Size(xy_pad.dim) }
+        fn () -> Position [] { Position(xy_pad.pos) }
+        fn () -> Visible [] { Visible(xy_pad.visible) }
         fn () -> DefaultWidgetState [] {
             DefaultWidgetState(Widget::XYPad(State::Normal))
         }
@@ -176,6 +313,7 @@ quack! {
         fn (val: LabelColor) [] { xy_pad.maybe_label_color = Some(val.0) }
         fn (val: LabelFontSize) [] { xy_pad.maybe_label_font_size = Some(val.0) }
         fn (val: Position) [] { xy_pad.pos = val.0 }
+        fn (val: Visible) [] { xy_pad.visible = val.0 }
         fn (val: Size) [] { xy_pad.dim = val.0 }
     action:
 }
@@ -192,6 +330,7 @@ impl<'a, X, Y, F> ::draw::Drawable for XYPad<'a, X, Y, F>
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        if !self.visible { uic.keep_state(self.ui_id); return }
 
         // Init.
         let state = *get_state(uic, self.ui_id);
@@ -204,20 +343,85 @@ impl<'a, X, Y, F> ::draw::Drawable for XYPad<'a, X, Y, F>
         };
         let pad_dim = vec2_sub(self.dim, [frame_w2; 2]);
         let pad_pos = vec2_add(self.pos, [frame_w, frame_w]);
-        let is_over_pad = rectangle::is_over(pad_pos, mouse.pos, pad_dim);
+        let is_over_pad = uic.is_over(self.ui_id, pad_pos, pad_dim, mouse.pos);
         let new_state = get_new_state(is_over_pad, state, mouse);
+        let drag_pos = match new_state {
+            State::Clicked => {
+                let fine_pos = uic.fine_pointer(self.ui_id, mouse.pos);
+                uic.smooth_pointer(self.ui_id, self.maybe_smoothing, fine_pos)
+            },
+            _ => { uic.reset_pointer_smoothing(self.ui_id); mouse.pos },
+        };
+        uic.set_dragging(self.ui_id, state == State::Clicked, new_state == State::Clicked);
+
+        // Ctrl-clicking or double-clicking resets the values to their
+        // defaults (if any) rather than dragging.
+        let is_resetting = (uic.get_modifier_keys().contains(keyboard::CTRL) || mouse.clicks >= 2)
+            && self.maybe_default_value.is_some();
 
         // Determine new values.
         let (new_x, new_y) = match (is_over_pad, new_state) {
             (_, State::Normal) | (_, State::Highlighted) => (self.x, self.y),
+            (_, State::Clicked) if is_resetting => {
+                // The default is kept within the pad's ranges.
+                let (default_x, default_y) = self.maybe_default_value.unwrap();
+                (clamp(default_x, self.min_x.min(self.max_x), self.min_x.max(self.max_x)),
+                 clamp(default_y, self.min_y.min(self.max_y), self.min_y.max(self.max_y)))
+            },
             (_, State::Clicked) => {
-                let temp_x = clamp(mouse.pos[0], pad_pos[0], pad_pos[0] + pad_dim[0]);
-                let temp_y = clamp(mouse.pos[1], pad_pos[1], pad_pos[1] + pad_dim[1]);
+                let temp_x = clamp(drag_pos[0], pad_pos[0], pad_pos[0] + pad_dim[0]);
+                let temp_y = clamp(drag_pos[1], pad_pos[1], pad_pos[1] + pad_dim[1]);
                 (map_range(temp_x - self.pos[0], pad_dim[0], 0.0, self.min_x, self.max_x),
                  map_range(temp_y - self.pos[1], pad_dim[1], 0.0, self.min_y, self.max_y))
             }
         };
 
+        // Spring back towards the rest position when released. The pad is
+        // damaged so that frames keep coming until it arrives.
+        let (new_x, new_y) = match self.maybe_rest {
+            Some((rest_x, rest_y)) if new_state != State::Clicked && (new_x != rest_x || new_y != rest_y) => {
+                let decay = (-uic.dt() / SPRING_TIME).exp();
+                // Values within half a pixel of the rest position arrive.
+                let spring = |value: f64, rest: f64, min: f64, max: f64, pixels: f64| {
+                    let sprung = rest + (value - rest) * decay;
+                    if (sprung - rest).abs() * pixels < 0.5 * (max - min).abs() { rest } else { sprung }
+                };
+                uic.damage(self.pos, self.dim);
+                (FromPrimitive::from_f64(spring(new_x.to_f64().unwrap(), rest_x.to_f64().unwrap(),
+                                                self.min_x.to_f64().unwrap(), self.max_x.to_f64().unwrap(),
+                                                pad_dim[0])).unwrap(),
+                 FromPrimitive::from_f64(spring(new_y.to_f64().unwrap(), rest_y.to_f64().unwrap(),
+                                                self.min_y.to_f64().unwrap(), self.max_y.to_f64().unwrap(),
+                                                pad_dim[1])).unwrap())
+            },
+            _ => (new_x, new_y),
+        };
+
+        // Snap dragged values to their steps and detents unless Alt is held.
+        let is_snapping = new_state == State::Clicked && !is_resetting
+            && (self.maybe_snap.is_some() || self.maybe_detents.is_some())
+            && !uic.get_modifier_keys().contains(keyboard::ALT);
+        let (new_x, new_y) = match is_snapping {
+            true => {
+                let (min_x, max_x) = (self.min_x.to_f64().unwrap(), self.max_x.to_f64().unwrap());
+                let (min_y, max_y) = (self.min_y.to_f64().unwrap(), self.max_y.to_f64().unwrap());
+                let (mut x, mut y) = (new_x.to_f64().unwrap(), new_y.to_f64().unwrap());
+                if let Some(steps) = self.maybe_snap {
+                    x = snap_to_step(x, min_x, max_x, steps[0]);
+                    y = snap_to_step(y, min_y, max_y, steps[1]);
+                }
+                if let Some((x_detents, y_detents)) = self.maybe_detents {
+                    x = stick_to_detents(x, x_detents, DETENT_RADIUS * (max_x - min_x).abs() / pad_dim[0]);
+                    y = stick_to_detents(y, y_detents, DETENT_RADIUS * (max_y - min_y).abs() / pad_dim[1]);
+                }
+                (FromPrimitive::from_f64(x).unwrap(), FromPrimitive::from_f64(y).unwrap())
+            },
+            false => (new_x, new_y),
+        };
+
+        // A change of value doesn't change the state, so damage the pad here.
+        if self.x != new_x || self.y != new_y { uic.damage(self.pos, self.dim) }
+
         // Callback if value is changed or the pad is clicked/released.
         match self.maybe_callback {
             Some(ref mut callback) => {
@@ -235,20 +439,66 @@ impl<'a, X, Y, F> ::draw::Drawable for XYPad<'a, X, Y, F>
 
         // Draw.
         let rect_state = new_state.as_rectangle_state();
-        let color = self.maybe_color.unwrap_or(uic.theme.shape_color);
+        let color = uic.flash_color(self.ui_id, self.maybe_color.unwrap_or(uic.theme.shape_color));
         rectangle::draw(uic.win_w, uic.win_h, graphics, rect_state, self.pos,
                         self.dim, maybe_frame, color);
         let (vert_x, hori_y) = match (is_over_pad, new_state) {
-            (_, State::Normal) | (_, State::Highlighted) =>
+            (_, State::Clicked) if !is_resetting && !is_snapping =>
+                (clamp(drag_pos[0], pad_pos[0], pad_pos[0] + pad_dim[0]),
+                 clamp(drag_pos[1], pad_pos[1], pad_pos[1] + pad_dim[1])),
+            _ =>
                 (pad_pos[0] + map_range(new_x, self.min_x, self.max_x, pad_dim[0], 0.0),
                  pad_pos[1] + map_range(new_y, self.min_y, self.max_y, pad_dim[1], 0.0)),
-            (_, State::Clicked) =>
-                (clamp(mouse.pos[0], pad_pos[0], pad_pos[0] + pad_dim[0]),
-                 clamp(mouse.pos[1], pad_pos[1], pad_pos[1] + pad_dim[1])),
         };
+        // Heatmap.
+        if let Some((cells, columns)) = self.maybe_heatmap {
+            if columns > 0 && cells.len() >= columns {
+                let rows = cells.len() / columns;
+                let cell_dim = [pad_dim[0] / columns as f64, pad_dim[1] / rows as f64];
+                for (i, &cell_color) in cells.iter().take(rows * columns).enumerate() {
+                    let cell_pos = [pad_pos[0] + cell_dim[0] * (i % columns) as f64,
+                                    pad_pos[1] + cell_dim[1] * (i / columns) as f64];
+                    rectangle::draw(uic.win_w, uic.win_h, graphics, rectangle::State::Normal,
+                                    cell_pos, cell_dim, None, cell_color);
+                }
+            }
+        }
+        // Grid.
+        if let Some((x_divisions, y_divisions)) = self.maybe_grid {
+            let (min_x, max_x) = (self.min_x.to_f64().unwrap(), self.max_x.to_f64().unwrap());
+            let (min_y, max_y) = (self.min_y.to_f64().unwrap(), self.max_y.to_f64().unwrap());
+            // The X axis runs from `max_x` on the left to `min_x` on the right.
+            let x_ticks: Vec<axis::Tick> = axis::linear_ticks(min_x, max_x, x_divisions).into_iter()
+                .map(|(perc, x)| (1.0 - perc, axis::tick_label(x, min_x, max_x, pad_dim[0])))
+                .collect();
+            let y_ticks: Vec<axis::Tick> = axis::linear_ticks(min_y, max_y, y_divisions).into_iter()
+                .map(|(perc, y)| (perc, axis::tick_label(y, min_y, max_y, pad_dim[1])))
+                .collect();
+            let small_font_size = uic.theme.font_size_small;
+            axis::draw(uic, graphics, pad_pos, pad_dim, &x_ticks, &y_ticks, self.tick_labels,
+                       color.plain_contrast(), small_font_size);
+        }
+        // Trail. Positions are recorded relative to the pad so that the trail
+        // follows it if it moves or is resized.
+        if let Some(len) = self.maybe_trail {
+            if state != State::Clicked && new_state == State::Clicked { uic.clear_trail(self.ui_id) }
+            uic.push_trail(self.ui_id, [(vert_x - pad_pos[0]) / pad_dim[0],
+                                        (hori_y - pad_pos[1]) / pad_dim[1]], len);
+            let trail = uic.trail(self.ui_id);
+            let trail_color = color.plain_contrast();
+            for i in 1..trail.len() {
+                let (a, b) = (trail[i - 1], trail[i]);
+                primitive::line(uic.win_w, uic.win_h, graphics,
+                                [pad_pos[0] + a[0] * pad_dim[0], pad_pos[1] + a[1] * pad_dim[1]],
+                                [pad_pos[0] + b[0] * pad_dim[0], pad_pos[1] + b[1] * pad_dim[1]],
+                                self.line_width, trail_color.with_alpha(i as f32 / len as f32));
+            }
+        }
         // Crosshair.
+        let crosshair_style = self.maybe_crosshair_style.or(uic.theme.line_style)
+            .unwrap_or(LineStyle::solid());
         draw_crosshair(uic.win_w, uic.win_h, graphics, pad_pos, self.line_width,
-                       vert_x, hori_y, pad_dim, color.plain_contrast());
+                       vert_x, hori_y, pad_dim, color.plain_contrast(), crosshair_style);
         // Label.
         if let Some(l_text) = self.maybe_label {
             let l_color = self.maybe_label_color.unwrap_or(uic.theme.label_color);
@@ -260,23 +510,37 @@ impl<'a, X, Y, F> ::draw::Drawable for XYPad<'a, X, Y, F>
             uic.draw_text(graphics, l_pos, l_size, l_color, l_text);
         }
         // xy value string.
-        let x_string = val_to_string(self.x, self.max_x,
-                                     self.max_x - self.min_x, self.dim[0] as usize);
-        let y_string = val_to_string(self.y, self.max_y,
-                                     self.max_y - self.min_y, self.dim[1] as usize);
-        let xy_string = format!("{}, {}", x_string, y_string);
-        let xy_string_w = label::width(uic, self.font_size, &xy_string);
-        let xy_string_pos = {
-            match rectangle::corner(pad_pos, [vert_x, hori_y], pad_dim) {
-                Corner::TopLeft => [vert_x, hori_y],
-                Corner::TopRight => [vert_x - xy_string_w, hori_y],
-                Corner::BottomLeft => [vert_x, hori_y - self.font_size as f64],
-                Corner::BottomRight => [vert_x - xy_string_w, hori_y - self.font_size as f64],
-            }
-        };
-        uic.draw_text(graphics, xy_string_pos, self.font_size,
-                    color.plain_contrast(), &xy_string);
+        let density = self.maybe_density.or(uic.theme.display_density).unwrap_or(DisplayDensity::Normal);
+        if density != DisplayDensity::Compact {
+            let x_string = match self.maybe_value_formatter {
+                Some(ref formatter) => formatter(self.x.to_f64().unwrap()),
+                None => val_to_string(self.x, self.max_x,
+                                      self.max_x - self.min_x, self.dim[0] as usize),
+            };
+            let y_string = match self.maybe_y_value_formatter.as_ref().or(self.maybe_value_formatter.as_ref()) {
+                Some(formatter) => formatter(self.y.to_f64().unwrap()),
+                None => val_to_string(self.y, self.max_y,
+                                      self.max_y - self.min_y, self.dim[1] as usize),
+            };
+            let xy_string = match self.maybe_units {
+                Some((x_unit, y_unit)) => format!("{} {}, {} {}", x_string, x_unit, y_string, y_unit),
+                None => format!("{}, {}", x_string, y_string),
+            };
+            let xy_string_w = label::value_width(uic, self.font_size, &xy_string);
+            let xy_string_pos = {
+                match rectangle::corner(pad_pos, [vert_x, hori_y], pad_dim) {
+                    Corner::TopLeft => [vert_x, hori_y],
+                    Corner::TopRight => [vert_x - xy_string_w, hori_y],
+                    Corner::BottomLeft => [vert_x, hori_y - self.font_size as f64],
+                    Corner::BottomRight => [vert_x - xy_string_w, hori_y - self.font_size as f64],
+                }
+            };
+            uic.draw_value_text(graphics, xy_string_pos, self.font_size,
+                                color.plain_contrast(), &xy_string);
+        }
 
+        uic.tooltips.set_value(self.ui_id, format!("{:.2}, {:.2}", new_x.to_f64().unwrap(),
+                                                                   new_y.to_f64().unwrap()));
         set_state(uic, self.ui_id, Widget::XYPad(new_state), self.pos, self.dim);
 
     }